@@ -3,6 +3,8 @@ using System.Drawing;
 using System.Drawing.Imaging;
 using System.IO;
 using System.Linq;
+using System.Net.Sockets;
+using System.Runtime.InteropServices;
 using System.Text;
 using System.Text.Json;
 using System.Text.Json.Serialization;
@@ -34,6 +36,14 @@ namespace VROverlayApp
 
         [JsonPropertyName("abilities")]
         public List<TerrorAbility> Abilities { get; set; } = new();
+
+        // Base64 PNG, only sent when icon display is enabled
+        [JsonPropertyName("icon")]
+        public string? Icon { get; set; }
+
+        // On the user's watchlist
+        [JsonPropertyName("highlighted")]
+        public bool Highlighted { get; set; }
     }
 
     // Command types from Tauri
@@ -50,24 +60,244 @@ namespace VROverlayApp
 
         [JsonPropertyName("position")]
         public string? Position { get; set; }
+
+        [JsonPropertyName("pitch")]
+        public float? Pitch { get; set; }
+
+        [JsonPropertyName("transform")]
+        public OverlayTransform? Transform { get; set; }
+
+        [JsonPropertyName("scale")]
+        public float? Scale { get; set; }
+
+        [JsonPropertyName("seconds")]
+        public int? Seconds { get; set; }
+
+        [JsonPropertyName("enabled")]
+        public bool? Enabled { get; set; }
+
+        [JsonPropertyName("current_streak")]
+        public int? CurrentStreak { get; set; }
+
+        [JsonPropertyName("session_survival_rate")]
+        public double? SessionSurvivalRate { get; set; }
+
+        [JsonPropertyName("session_rounds")]
+        public int? SessionRounds { get; set; }
+
+        [JsonPropertyName("code")]
+        public string? Code { get; set; }
+
+        [JsonPropertyName("timestamp")]
+        public string? Timestamp { get; set; }
+
+        [JsonPropertyName("elapsed_secs")]
+        public int? ElapsedSecs { get; set; }
+
+        [JsonPropertyName("strength")]
+        public float? Strength { get; set; }
+
+        [JsonPropertyName("pattern")]
+        public string? Pattern { get; set; }
+
+        [JsonPropertyName("panel")]
+        public string? Panel { get; set; }
+
+        [JsonPropertyName("separate")]
+        public bool? Separate { get; set; }
+
+        [JsonPropertyName("background_color")]
+        public string? BackgroundColor { get; set; }
+
+        [JsonPropertyName("accent_color")]
+        public string? AccentColor { get; set; }
+
+        [JsonPropertyName("font_scale")]
+        public float? FontScale { get; set; }
+
+        [JsonPropertyName("text")]
+        public string? Text { get; set; }
+
+        [JsonPropertyName("duration_secs")]
+        public int? DurationSecs { get; set; }
+    }
+
+    // Fine-tuning applied on top of the preset position
+    public class OverlayTransform
+    {
+        [JsonPropertyName("offset_x")]
+        public float OffsetX { get; set; }
+
+        [JsonPropertyName("offset_y")]
+        public float OffsetY { get; set; }
+
+        [JsonPropertyName("offset_z")]
+        public float OffsetZ { get; set; }
+
+        // In-plane rotation in degrees
+        [JsonPropertyName("rotation")]
+        public float Rotation { get; set; }
+
+        // Extra distance away from the anchor in meters
+        [JsonPropertyName("distance")]
+        public float Distance { get; set; }
+    }
+
+    // Stats / code panel that can be placed apart from the terror panel
+    public class DetachedPanel
+    {
+        public string Name { get; set; } = "";
+        public Overlay? Overlay { get; set; }
+        public string ImagePath { get; set; } = "";
+        public bool Separate { get; set; }
+        public OverlayPosition Position { get; set; } = OverlayPosition.Above;
+        public OverlayTransform Transform { get; set; } = new();
+        public float Pitch { get; set; } = -20.0f;
+        public bool IsVisible { get; set; }
+    }
+
+    // Version handshake exchanged right after connecting: {"hello": {"protocol_version": 1}}
+    public class IpcHelloInfo
+    {
+        [JsonPropertyName("protocol_version")]
+        public uint ProtocolVersion { get; set; }
+    }
+
+    // Framed message from Tauri: {"id": 1, "command": {...}}
+    // or the handshake hello
+    public class IpcFrame
+    {
+        [JsonPropertyName("id")]
+        public ulong Id { get; set; }
+
+        [JsonPropertyName("command")]
+        public VrCommand? Command { get; set; }
+
+        [JsonPropertyName("hello")]
+        public IpcHelloInfo? Hello { get; set; }
+    }
+
+    // Acknowledgment sent back to Tauri for each frame
+    public class IpcAck
+    {
+        [JsonPropertyName("ack")]
+        public ulong Ack { get; set; }
+
+        [JsonPropertyName("ok")]
+        public bool Ok { get; set; }
+
+        [JsonPropertyName("error")]
+        public string? Error { get; set; }
+    }
+
+    // User interaction reported to Tauri: {"event": {"type": "copy_code"}}
+    public class OverlayEvent
+    {
+        [JsonPropertyName("type")]
+        public string Type { get; set; } = "";
+
+        [JsonPropertyName("index")]
+        [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+        public int? Index { get; set; }
+
+        [JsonPropertyName("name")]
+        [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+        public string? Name { get; set; }
+
+        [JsonPropertyName("action")]
+        [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+        public string? Action { get; set; }
+    }
+
+    public class OverlayEventMessage
+    {
+        [JsonPropertyName("event")]
+        public OverlayEvent Event { get; set; } = new();
     }
 
     public enum OverlayPosition
     {
         RightHand,
         LeftHand,
-        Above
+        Above,
+        Head
     }
 
     class Program
     {
+        private const float BaseWidthInMeters = 0.12f;
+        // Must match PROTOCOL_VERSION in src-tauri/src/vr_ipc.rs
+        private const uint ProtocolVersion = 1;
+
         private static Application? _app;
         private static Overlay? _overlay;
         private static string _imagePath = "";
         private static OverlayPosition _currentPosition = OverlayPosition.RightHand;
+        private static OverlayTransform _transform = new();
+        private static float _headPitch = -20.0f;
         private static List<TerrorInfo> _currentTerrors = new();
         private static string _currentRoundType = "";
         private static bool _isVisible = false;
+        // True while there is something to show (not cleared, dismissed or auto-hidden)
+        private static bool _panelActive = false;
+
+        // SteamVR input actions the user can bind to controller buttons; presses are reported to Tauri
+        private const string ActionSetPath = "/actions/tsst";
+        private static readonly (string Name, string English, string Japanese)[] InputActions =
+        {
+            ("toggle_overlay", "Show/hide overlay", "オーバーレイの表示切り替え"),
+            ("copy_code", "Copy latest save code", "最新のセーブコードをコピー"),
+            ("cycle_panels", "Cycle panels", "パネルの切り替え"),
+            ("disable_overlay", "Turn off overlay", "オーバーレイを無効にする"),
+        };
+        private static ulong _actionSetHandle = 0;
+        private static readonly Dictionary<string, ulong> _actionHandles = new();
+        private static int? _autoHideSeconds;
+
+        // Stats panel (streak / session survival rate / rounds played)
+        private static bool _statsEnabled = false;
+        private static string? _statsText;
+
+        // Latest captured save code (already truncated by the app)
+        private static bool _codeEnabled = false;
+        private static string? _codeText;
+
+        // Separately positioned stats / code panels, keyed by "stats" / "code"
+        private static Dictionary<string, DetachedPanel> _panels = new()
+        {
+            ["stats"] = new DetachedPanel { Name = "stats" },
+            ["code"] = new DetachedPanel { Name = "code" },
+        };
+        private static float _scale = 1.0f;
+
+        // Theme pushed by the app (set_theme)
+        private static Color _backgroundColor = Color.FromArgb(15, 15, 20);
+        private static Color _accentColor = Color.FromArgb(0, 140, 255);
+        private static float _fontScale = 1.0f;
+
+        // Short message from show_message, removed again at _messageUntil
+        private static string? _messageText;
+        private static DateTime? _messageUntil;
+
+        // Elapsed time of the current round, pushed by the app every second
+        private static string? _timerText;
+
+        // Wrist-watch mode: only show a hand-attached panel while the wrist faces the HMD
+        private const float GazeAngleDegrees = 35.0f;
+        private static bool _gazeMode = false;
+        private static bool _isGazing = false;
+        private static uint _wristDeviceIndex = OpenVR.k_unTrackedDeviceIndexInvalid;
+        private static HmdVector3_t _wristLocalPosition;
+        private static HmdVector3_t _wristLocalNormal;
+        private static DateTime? _hideAt;
+        private static StreamWriter? _ipcWriter;
+
+        // Clickable regions of the last rendered image (pixel coordinates, origin top-left)
+        private static int _imageWidth = 0;
+        private static int _imageHeight = 0;
+        private static List<(int Top, int Bottom)> _terrorRegions = new();
+        private static Rectangle _closeButton = Rectangle.Empty;
+        private static Rectangle _copyButton = Rectangle.Empty;
 
         static void Main(string[] args)
         {
@@ -75,21 +305,35 @@ namespace VROverlayApp
 
             TrySetConsoleEncoding();
 
+            int ipcPort = 0;
+
             // Parse command line arguments
             for (int i = 0; i < args.Length; i++)
             {
+                if (args[i] == "--ipc-port" && i + 1 < args.Length)
+                {
+                    int.TryParse(args[i + 1], out ipcPort);
+                }
+
                 if (args[i] == "--position" && i + 1 < args.Length)
                 {
                     _currentPosition = args[i + 1].ToLower() switch
                     {
                         "left" => OverlayPosition.LeftHand,
                         "above" => OverlayPosition.Above,
+                        "head" => OverlayPosition.Head,
                         _ => OverlayPosition.RightHand
                     };
                     Console.WriteLine($"[VROverlay] Position set to: {_currentPosition}");
                 }
             }
 
+            if (ipcPort <= 0)
+            {
+                Console.WriteLine("[VROverlay] --ipc-port is required");
+                return;
+            }
+
             try
             {
                 // Initialize OpenVR
@@ -102,34 +346,54 @@ namespace VROverlayApp
                 // Create initial empty overlay
                 CreateOverlayImage(_imagePath, new List<TerrorInfo>(), "");
                 _overlay.SetTextureFromFile(_imagePath);
-                _overlay.WidthInMeters = 0.12f;
+                _overlay.WidthInMeters = BaseWidthInMeters;
+
+                // Accept laser-pointer input so clicks can be reported back to Tauri
+                OpenVR.Overlay.SetOverlayInputMethod(_overlay.Handle, VROverlayInputMethod.Mouse);
+                UpdateMouseScale();
 
                 // Set initial position
                 UpdateOverlayPosition();
 
+                InitInputActions();
+
                 Console.WriteLine("[VROverlay] Initialized successfully");
 
-                // Start stdin reading thread
+                // Connect to Tauri once the overlay is ready to handle commands
+                var client = new TcpClient { NoDelay = true };
+                client.Connect("127.0.0.1", ipcPort);
+                Console.WriteLine($"[VROverlay] Connected to IPC port {ipcPort}");
+
+                // Start IPC reading thread
                 bool running = true;
+                var stream = client.GetStream();
+                var reader = new StreamReader(stream, new UTF8Encoding(false));
+                var writer = new StreamWriter(stream, new UTF8Encoding(false)) { AutoFlush = true, NewLine = "\n" };
+                _ipcWriter = writer;
                 Thread inputThread = new Thread(() =>
                 {
                     while (running)
                     {
+                        string? line;
                         try
                         {
-                            string? line = Console.ReadLine();
-                            if (line == null)
-                            {
-                                Thread.Sleep(100);
-                                continue;
-                            }
-
-                            ProcessCommand(line);
+                            line = reader.ReadLine();
                         }
                         catch (Exception ex)
                         {
-                            Console.WriteLine($"[VROverlay] Input error: {ex.Message}");
+                            Console.WriteLine($"[VROverlay] IPC read error: {ex.Message}");
+                            line = null;
                         }
+
+                        if (line == null)
+                        {
+                            // The app closed the connection, so no more commands will arrive
+                            Console.WriteLine("[VROverlay] IPC connection closed, exiting");
+                            Shutdown();
+                            return;
+                        }
+
+                        HandleFrame(line, writer);
                     }
                 });
                 inputThread.IsBackground = true;
@@ -138,8 +402,23 @@ namespace VROverlayApp
                 // Main loop
                 while (running)
                 {
-                    // Check if we need to refresh the overlay texture
-                    Thread.Sleep(100);
+                    if (_hideAt is DateTime hideAt && DateTime.Now >= hideAt)
+                    {
+                        _hideAt = null;
+                        HideOverlay();
+                    }
+                    if (_messageUntil is DateTime messageUntil && DateTime.Now >= messageUntil)
+                    {
+                        ExpireMessage();
+                    }
+                    if (_gazeMode)
+                    {
+                        _isGazing = IsLookingAtWrist();
+                        RefreshVisibility();
+                    }
+                    PollOverlayInput();
+                    PollInputActions();
+                    Thread.Sleep(50);
                 }
 
                 _app.Shutdown();
@@ -151,98 +430,452 @@ namespace VROverlayApp
             }
         }
 
-        static void ProcessCommand(string line)
+        static void HandleFrame(string line, StreamWriter writer)
         {
+            if (string.IsNullOrWhiteSpace(line)) return;
+
+            IpcFrame? frame;
             try
             {
-                if (string.IsNullOrWhiteSpace(line)) return;
+                frame = JsonSerializer.Deserialize<IpcFrame>(line.Trim().TrimStart('\uFEFF'));
+            }
+            catch (JsonException ex)
+            {
+                Console.WriteLine($"[VROverlay] JSON parse error: {ex.Message}");
+                return;
+            }
 
-                var trimmed = line.Trim();
-                if (trimmed.Length > 0 && trimmed[0] == '\uFEFF')
+            if (frame == null)
+            {
+                Console.WriteLine("[VROverlay] Frame is null after deserialize");
+                return;
+            }
+
+            if (frame.Hello != null)
+            {
+                Console.WriteLine($"[VROverlay] App protocol version: {frame.Hello.ProtocolVersion} (overlay: {ProtocolVersion})");
+                var hello = new IpcFrame { Hello = new IpcHelloInfo { ProtocolVersion = ProtocolVersion } };
+                var options = new JsonSerializerOptions { DefaultIgnoreCondition = JsonIgnoreCondition.WhenWritingNull };
+                WriteLine(writer, JsonSerializer.Serialize(hello, options));
+                return;
+            }
+
+            string? error = frame.Command == null
+                ? "missing command"
+                : ProcessCommand(frame.Command);
+
+            var ack = new IpcAck { Ack = frame.Id, Ok = error == null, Error = error };
+            WriteLine(writer, JsonSerializer.Serialize(ack));
+
+            if (frame.Command?.Type == "quit")
+            {
+                Console.WriteLine("[VROverlay] Quit command received");
+                Shutdown();
+            }
+        }
+
+        private static int _shuttingDown = 0;
+
+        // Releases the OpenVR handles before exiting so SteamVR drops the overlays cleanly
+        static void Shutdown()
+        {
+            if (Interlocked.Exchange(ref _shuttingDown, 1) == 1) return;
+            try
+            {
+                _overlay?.Hide();
+                foreach (var panel in _panels.Values)
+                {
+                    panel.Overlay?.Hide();
+                }
+                _app?.Shutdown();
+                Console.WriteLine("[VROverlay] OpenVR shut down");
+            }
+            catch (Exception ex)
+            {
+                Console.WriteLine($"[VROverlay] Error during shutdown: {ex.Message}");
+            }
+            Environment.Exit(0);
+        }
+
+        static void SendEvent(OverlayEvent ev)
+        {
+            if (_ipcWriter == null) return;
+            Console.WriteLine($"[VROverlay] Sending event: {ev.Type}");
+            WriteLine(_ipcWriter, JsonSerializer.Serialize(new OverlayEventMessage { Event = ev }));
+        }
+
+        static void UpdateMouseScale()
+        {
+            if (_overlay == null || _imageWidth == 0 || _imageHeight == 0) return;
+            var scale = new HmdVector2_t { v0 = _imageWidth, v1 = _imageHeight };
+            OpenVR.Overlay.SetOverlayMouseScale(_overlay.Handle, ref scale);
+        }
+
+        static void PollOverlayInput()
+        {
+            if (_overlay == null) return;
+
+            var ev = new VREvent_t();
+            uint size = (uint)Marshal.SizeOf<VREvent_t>();
+            while (OpenVR.Overlay.PollNextOverlayEvent(_overlay.Handle, ref ev, size))
+            {
+                if (ev.eventType != (uint)EVREventType.VREvent_MouseButtonDown) continue;
+
+                // OpenVR mouse coordinates have their origin at the bottom-left
+                int x = (int)ev.data.mouse.x;
+                int y = _imageHeight - (int)ev.data.mouse.y;
+                HandleClick(x, y);
+            }
+        }
+
+        // Writes the action manifest next to the executable and registers it with SteamVR
+        static void InitInputActions()
+        {
+            try
+            {
+                var manifest = new Dictionary<string, object>
                 {
-                    trimmed = trimmed.TrimStart('\uFEFF');
+                    ["default_bindings"] = Array.Empty<object>(),
+                    ["action_sets"] = new[]
+                    {
+                        new Dictionary<string, string> { ["name"] = ActionSetPath, ["usage"] = "leftright" },
+                    },
+                    ["actions"] = InputActions
+                        .Select(a => new Dictionary<string, string> { ["name"] = ActionPath(a.Name), ["type"] = "boolean" })
+                        .ToArray(),
+                    ["localization"] = new[]
+                    {
+                        Localization("en_US", "ToN Simple Save Tool", a => a.English),
+                        Localization("ja_JP", "ToN Simple Save Tool", a => a.Japanese),
+                    },
+                };
+                string manifestPath = Path.Combine(AppDomain.CurrentDomain.BaseDirectory, "actions.json");
+                File.WriteAllText(manifestPath, JsonSerializer.Serialize(manifest));
+
+                var error = OpenVR.Input.SetActionManifestPath(manifestPath);
+                if (error != EVRInputError.None)
+                {
+                    Console.WriteLine($"[VROverlay] Failed to load action manifest: {error}");
+                    return;
                 }
 
-                string json = trimmed;
-                if (trimmed.StartsWith("b64:", StringComparison.OrdinalIgnoreCase))
+                OpenVR.Input.GetActionSetHandle(ActionSetPath, ref _actionSetHandle);
+                foreach (var action in InputActions)
                 {
-                    var payload = trimmed.Substring(4);
-                    var bytes = Convert.FromBase64String(payload);
-                    json = Encoding.UTF8.GetString(bytes);
+                    ulong handle = 0;
+                    if (OpenVR.Input.GetActionHandle(ActionPath(action.Name), ref handle) == EVRInputError.None)
+                    {
+                        _actionHandles[action.Name] = handle;
+                    }
                 }
+                Console.WriteLine($"[VROverlay] Registered {_actionHandles.Count} input actions");
+            }
+            catch (Exception ex)
+            {
+                Console.WriteLine($"[VROverlay] Failed to set up input actions: {ex.Message}");
+            }
+        }
 
-                Console.WriteLine($"[VROverlay] Received command: {json}");
+        static string ActionPath(string name) => $"{ActionSetPath}/in/{name}";
 
-                var command = JsonSerializer.Deserialize<VrCommand>(json);
-                if (command == null)
+        static Dictionary<string, string> Localization(
+            string language,
+            string setName,
+            Func<(string Name, string English, string Japanese), string> label)
+        {
+            var entries = new Dictionary<string, string> { ["language_tag"] = language, [ActionSetPath] = setName };
+            foreach (var action in InputActions)
+            {
+                entries[ActionPath(action.Name)] = label(action);
+            }
+            return entries;
+        }
+
+        // Reports bound button presses (rising edge only) to Tauri
+        static void PollInputActions()
+        {
+            if (_actionSetHandle == 0 || _actionHandles.Count == 0) return;
+
+            var sets = new[] { new VRActiveActionSet_t { ulActionSet = _actionSetHandle } };
+            if (OpenVR.Input.UpdateActionState(sets, (uint)Marshal.SizeOf<VRActiveActionSet_t>()) != EVRInputError.None) return;
+
+            foreach (var (name, handle) in _actionHandles)
+            {
+                var data = new InputDigitalActionData_t();
+                var error = OpenVR.Input.GetDigitalActionData(
+                    handle,
+                    ref data,
+                    (uint)Marshal.SizeOf<InputDigitalActionData_t>(),
+                    OpenVR.k_ulInvalidInputValueHandle);
+                if (error == EVRInputError.None && data.bActive && data.bChanged && data.bState)
                 {
-                    Console.WriteLine("[VROverlay] Command is null after deserialize");
+                    SendEvent(new OverlayEvent { Type = "input_action", Action = name });
+                }
+            }
+        }
+
+        static void HandleClick(int x, int y)
+        {
+            if (_closeButton.Contains(x, y))
+            {
+                HideOverlay();
+                SendEvent(new OverlayEvent { Type = "panel_dismissed" });
+                return;
+            }
+
+            if (_copyButton.Contains(x, y))
+            {
+                SendEvent(new OverlayEvent { Type = "copy_code" });
+                return;
+            }
+
+            for (int i = 0; i < _terrorRegions.Count && i < _currentTerrors.Count; i++)
+            {
+                var region = _terrorRegions[i];
+                if (y >= region.Top && y < region.Bottom)
+                {
+                    SendEvent(new OverlayEvent { Type = "terror_clicked", Index = i, Name = _currentTerrors[i].Name });
                     return;
                 }
+            }
+        }
 
+        static void WriteLine(StreamWriter writer, string line)
+        {
+            try
+            {
+                lock (writer)
+                {
+                    writer.WriteLine(line);
+                }
+            }
+            catch (Exception ex)
+            {
+                Console.WriteLine($"[VROverlay] IPC write error: {ex.Message}");
+            }
+        }
+
+        // Returns null on success, or an error message to send back in the ack
+        static string? ProcessCommand(VrCommand command)
+        {
+            try
+            {
                 Console.WriteLine($"[VROverlay] Command type: {command.Type}");
 
                 switch (command.Type)
                 {
                     case "update_terrors":
-                        if (command.Terrors != null)
+                        if (command.Terrors == null)
                         {
-                            Console.WriteLine($"[VROverlay] Terrors count: {command.Terrors.Count}");
-                            foreach (var t in command.Terrors)
-                            {
-                                Console.WriteLine($"[VROverlay]   Terror: {t.Name}, Color: {t.Color}, Abilities: {t.Abilities.Count}");
-                            }
-                            _currentTerrors = command.Terrors;
-                            _currentRoundType = command.RoundType ?? "";
-                            UpdateOverlay();
+                            return "terrors is null";
                         }
-                        else
+                        Console.WriteLine($"[VROverlay] Terrors count: {command.Terrors.Count}");
+                        foreach (var t in command.Terrors)
                         {
-                            Console.WriteLine("[VROverlay] Terrors is null");
+                            Console.WriteLine($"[VROverlay]   Terror: {t.Name}, Color: {t.Color}, Abilities: {t.Abilities.Count}");
                         }
-                        break;
+                        _currentTerrors = command.Terrors;
+                        _currentRoundType = command.RoundType ?? "";
+                        UpdateOverlay();
+                        return null;
 
                     case "set_position":
+                        if (command.Pitch != null)
+                        {
+                            _headPitch = command.Pitch.Value;
+                        }
                         if (command.Position != null)
                         {
-                            _currentPosition = command.Position switch
-                            {
-                                "LeftHand" => OverlayPosition.LeftHand,
-                                "Above" => OverlayPosition.Above,
-                                _ => OverlayPosition.RightHand
-                            };
+                            _currentPosition = ParsePosition(command.Position);
                             UpdateOverlayPosition();
                         }
-                        break;
+                        return null;
+
+                    case "set_transform":
+                        if (command.Transform == null)
+                        {
+                            return "transform is null";
+                        }
+                        _transform = command.Transform;
+                        UpdateOverlayPosition();
+                        return null;
+
+                    case "set_scale":
+                        if (command.Scale == null || command.Scale <= 0)
+                        {
+                            return "invalid scale";
+                        }
+                        _scale = command.Scale.Value;
+                        if (_overlay != null)
+                        {
+                            _overlay.WidthInMeters = BaseWidthInMeters * _scale;
+                        }
+                        foreach (var panel in _panels.Values)
+                        {
+                            if (panel.Overlay != null) panel.Overlay.WidthInMeters = BaseWidthInMeters * _scale;
+                        }
+                        return null;
+
+                    case "set_theme":
+                        if (!TryParseHexColor(command.BackgroundColor, out var background) ||
+                            !TryParseHexColor(command.AccentColor, out var accent))
+                        {
+                            return "invalid theme color";
+                        }
+                        _backgroundColor = background;
+                        _accentColor = accent;
+                        _fontScale = Math.Clamp(command.FontScale ?? 1.0f, 0.5f, 2.0f);
+                        if (_panelActive)
+                        {
+                            RenderTexture();
+                        }
+                        foreach (var panel in _panels.Values)
+                        {
+                            UpdateDetachedPanel(panel);
+                        }
+                        return null;
+
+                    case "set_auto_hide":
+                        _autoHideSeconds = command.Seconds > 0 ? command.Seconds : null;
+                        if (_autoHideSeconds == null)
+                        {
+                            _hideAt = null;
+                        }
+                        return null;
+
+                    case "set_gaze_mode":
+                        _gazeMode = command.Enabled ?? false;
+                        RefreshVisibility();
+                        return null;
+
+                    case "show_message":
+                        if (string.IsNullOrWhiteSpace(command.Text))
+                        {
+                            return "text is empty";
+                        }
+                        _messageText = command.Text;
+                        _messageUntil = DateTime.Now.AddSeconds(Math.Max(command.DurationSecs ?? 5, 1));
+                        UpdateOverlay();
+                        return null;
+
+                    case "show":
+                        // Re-show the current terrors (restarts the auto-hide timer)
+                        UpdateOverlay();
+                        return null;
+
+                    case "toggle_visibility":
+                        if (_panelActive)
+                        {
+                            HideOverlay();
+                        }
+                        else
+                        {
+                            UpdateOverlay();
+                        }
+                        return null;
+
+                    case "set_stats_panel":
+                        _statsEnabled = command.Enabled ?? false;
+                        UpdateOverlay();
+                        UpdateDetachedPanel(_panels["stats"]);
+                        return null;
+
+                    case "update_stats":
+                        _statsText = $"Streak: {command.CurrentStreak ?? 0}  |  Session: {command.SessionRounds ?? 0} rounds, {(command.SessionSurvivalRate ?? 0) * 100:0}% survived";
+                        UpdateOverlay();
+                        UpdateDetachedPanel(_panels["stats"]);
+                        return null;
+
+                    case "round_timer":
+                        _timerText = command.ElapsedSecs is int secs ? $"Round time {secs / 60}:{secs % 60:00}" : null;
+                        // Ticks only redraw; they must not re-show the panel or restart auto-hide
+                        if (_panelActive)
+                        {
+                            RenderTexture();
+                        }
+                        return null;
+
+                    case "haptic":
+                        TriggerHaptic(command.Strength ?? 0.8f, command.Pattern ?? "single");
+                        return null;
+
+                    case "show_code":
+                        _codeText = command.Code == null ? null : $"Saved {command.Timestamp}  {command.Code}";
+                        UpdateOverlay();
+                        UpdateDetachedPanel(_panels["code"]);
+                        return null;
+
+                    case "set_code_panel":
+                        _codeEnabled = command.Enabled ?? false;
+                        UpdateOverlay();
+                        UpdateDetachedPanel(_panels["code"]);
+                        return null;
+
+                    case "set_panel_layout":
+                        if (command.Panel == null || !_panels.TryGetValue(command.Panel, out var layoutPanel))
+                        {
+                            return $"unknown panel: {command.Panel}";
+                        }
+                        layoutPanel.Separate = command.Separate ?? false;
+                        layoutPanel.Position = command.Position != null ? ParsePosition(command.Position) : OverlayPosition.Above;
+                        layoutPanel.Pitch = command.Pitch ?? -20.0f;
+                        layoutPanel.Transform = command.Transform ?? new OverlayTransform();
+                        // The line moves between the main panel and its own overlay
+                        UpdateOverlay();
+                        UpdateDetachedPanel(layoutPanel);
+                        return null;
 
                     case "clear":
                         _currentTerrors.Clear();
                         _currentRoundType = "";
-                        HideOverlay();
-                        break;
+                        // Hides the panel unless the stats panel has something to show
+                        UpdateOverlay();
+                        return null;
 
                     case "quit":
-                        Console.WriteLine("[VROverlay] Quit command received");
-                        Environment.Exit(0);
-                        break;
+                        // Exit happens after the ack has been written
+                        return null;
 
                     default:
                         Console.WriteLine($"[VROverlay] Unknown command type: {command.Type}");
-                        break;
+                        return $"unknown command type: {command.Type}";
                 }
             }
-            catch (JsonException ex)
-            {
-                Console.WriteLine($"[VROverlay] JSON parse error: {ex.Message}");
-                Console.WriteLine($"[VROverlay] Stack: {ex.StackTrace}");
-            }
             catch (Exception ex)
             {
                 Console.WriteLine($"[VROverlay] Error in ProcessCommand: {ex.Message}");
                 Console.WriteLine($"[VROverlay] Stack: {ex.StackTrace}");
+                return ex.Message;
             }
         }
 
+        static OverlayPosition ParsePosition(string position)
+        {
+            return position switch
+            {
+                "LeftHand" => OverlayPosition.LeftHand,
+                "Above" => OverlayPosition.Above,
+                "Head" => OverlayPosition.Head,
+                _ => OverlayPosition.RightHand
+            };
+        }
+
+        // Parses "#RRGGBB"
+        static bool TryParseHexColor(string? value, out Color color)
+        {
+            color = Color.Empty;
+            if (value == null || value.Length != 7 || value[0] != '#') return false;
+            if (!int.TryParse(value.AsSpan(1), System.Globalization.NumberStyles.HexNumber, null, out int rgb)) return false;
+            color = Color.FromArgb((rgb >> 16) & 0xFF, (rgb >> 8) & 0xFF, rgb & 0xFF);
+            return true;
+        }
+
+        // Scales a pixel size by the theme font scale
+        static int Scaled(int pixels)
+        {
+            return (int)Math.Round(pixels * _fontScale);
+        }
+
         static void TrySetConsoleEncoding()
         {
             try
@@ -267,24 +900,20 @@ namespace VROverlayApp
                     return;
                 }
 
-                if (_currentTerrors.Count == 0)
+                if (_currentTerrors.Count == 0 && InfoLines().Count == 0)
                 {
                     HideOverlay();
                     return;
                 }
 
                 Console.WriteLine($"[VROverlay] Creating overlay image for {_currentTerrors.Count} terrors");
-                CreateOverlayImage(_imagePath, _currentTerrors, _currentRoundType);
-                Console.WriteLine($"[VROverlay] Image created at {_imagePath}");
-                
-                _overlay.SetTextureFromFile(_imagePath);
+                RenderTexture();
                 Console.WriteLine("[VROverlay] Texture set");
                 
-                if (!_isVisible)
-                {
-                    _overlay.Show();
-                    _isVisible = true;
-                }
+                _panelActive = true;
+                RefreshVisibility();
+
+                _hideAt = _autoHideSeconds is int seconds ? DateTime.Now.AddSeconds(seconds) : null;
 
                 Console.WriteLine($"[VROverlay] Updated with {_currentTerrors.Count} terrors");
             }
@@ -295,39 +924,244 @@ namespace VROverlayApp
             }
         }
 
+        // Pulses the controller the overlay is attached to (both controllers otherwise)
+        static void TriggerHaptic(float strength, string pattern)
+        {
+            var devices = new List<uint>();
+            if (IsHandPosition() && _wristDeviceIndex != OpenVR.k_unTrackedDeviceIndexInvalid)
+            {
+                devices.Add(_wristDeviceIndex);
+            }
+            else
+            {
+                foreach (var role in new[] { ETrackedControllerRole.RightHand, ETrackedControllerRole.LeftHand })
+                {
+                    uint index = OpenVR.System.GetTrackedDeviceIndexForControllerRole(role);
+                    if (index != OpenVR.k_unTrackedDeviceIndexInvalid) devices.Add(index);
+                }
+            }
+            if (devices.Count == 0) return;
+
+            // Pattern as (buzz ms, pause ms) steps
+            (int On, int Off)[] steps = pattern switch
+            {
+                "double" => new[] { (100, 100), (100, 0) },
+                "long" => new[] { (500, 0) },
+                _ => new[] { (150, 0) },
+            };
+            // The legacy pulse API lasts at most ~4ms per call, so repeat it for the duration
+            ushort pulse = (ushort)(Math.Clamp(strength, 0.0f, 1.0f) * 3999);
+            if (pulse == 0) return;
+
+            var thread = new Thread(() =>
+            {
+                foreach (var (on, off) in steps)
+                {
+                    var end = DateTime.Now.AddMilliseconds(on);
+                    while (DateTime.Now < end)
+                    {
+                        foreach (var device in devices)
+                        {
+                            OpenVR.System.TriggerHapticPulse(device, 0, pulse);
+                        }
+                        Thread.Sleep(5);
+                    }
+                    if (off > 0) Thread.Sleep(off);
+                }
+            });
+            thread.IsBackground = true;
+            thread.Start();
+        }
+
+        static void RenderTexture()
+        {
+            if (_overlay == null) return;
+            CreateOverlayImage(_imagePath, _currentTerrors, _currentRoundType);
+            _overlay.SetTextureFromFile(_imagePath);
+            UpdateMouseScale();
+        }
+
+        // Lines shown at the top of the panel (message, round timer, stats, captured code)
+        static List<string> InfoLines()
+        {
+            var lines = new List<string>();
+            if (_messageText != null) lines.Add(_messageText);
+            if (_timerText != null && _currentTerrors.Count > 0) lines.Add(_timerText);
+            if (!_panels["stats"].Separate && StatsLine() is string stats) lines.Add(stats);
+            if (!_panels["code"].Separate && CodeLine() is string code) lines.Add(code);
+            return lines;
+        }
+
+        static string? StatsLine()
+        {
+            return _statsEnabled ? _statsText : null;
+        }
+
+        static string? CodeLine()
+        {
+            return _codeEnabled ? _codeText : null;
+        }
+
+        // Detached panels follow only their own content; auto-hide and gaze mode apply to the main panel
+        static void UpdateDetachedPanel(DetachedPanel panel)
+        {
+            if (_app == null) return;
+
+            string? text = panel.Separate
+                ? (panel.Name == "stats" ? StatsLine() : CodeLine())
+                : null;
+
+            if (text == null)
+            {
+                if (panel.Overlay != null && panel.IsVisible)
+                {
+                    panel.Overlay.Hide();
+                    panel.IsVisible = false;
+                }
+                return;
+            }
+
+            if (panel.Overlay == null)
+            {
+                panel.Overlay = new Overlay($"tsst-vr-overlay-{panel.Name}", $"ToN {panel.Name}");
+                panel.ImagePath = Path.Combine(AppDomain.CurrentDomain.BaseDirectory, $"overlay-{panel.Name}.png");
+                panel.Overlay.WidthInMeters = BaseWidthInMeters * _scale;
+            }
+
+            CreateLineImage(panel.ImagePath, text);
+            panel.Overlay.SetTextureFromFile(panel.ImagePath);
+            ApplyPosition(panel.Overlay, panel.Position, panel.Transform, panel.Pitch, false);
+            if (!panel.IsVisible)
+            {
+                panel.Overlay.Show();
+                panel.IsVisible = true;
+            }
+        }
+
+        static void ExpireMessage()
+        {
+            _messageText = null;
+            _messageUntil = null;
+            if (!_panelActive) return;
+
+            // Keep showing whatever else is on the panel without restarting auto-hide
+            if (_currentTerrors.Count == 0 && InfoLines().Count == 0)
+            {
+                HideOverlay();
+            }
+            else
+            {
+                RenderTexture();
+            }
+        }
+
         static void HideOverlay()
         {
-            if (_overlay != null && _isVisible)
+            _hideAt = null;
+            _panelActive = false;
+            RefreshVisibility();
+        }
+
+        static bool IsHandPosition()
+        {
+            return _currentPosition == OverlayPosition.RightHand || _currentPosition == OverlayPosition.LeftHand;
+        }
+
+        // Shows or hides the overlay according to the panel state and the gaze gate
+        static void RefreshVisibility()
+        {
+            if (_overlay == null) return;
+
+            bool gateOpen = !_gazeMode || !IsHandPosition() || _isGazing;
+            bool shouldShow = _panelActive && gateOpen;
+            if (shouldShow == _isVisible) return;
+
+            if (shouldShow)
+            {
+                _overlay.Show();
+                Console.WriteLine("[VROverlay] Shown");
+            }
+            else
             {
                 _overlay.Hide();
-                _isVisible = false;
                 Console.WriteLine("[VROverlay] Hidden");
             }
+            _isVisible = shouldShow;
+        }
+
+        // Whether the panel's face on the wrist is turned towards the HMD
+        static bool IsLookingAtWrist()
+        {
+            // Without a tracked controller there is nothing to gate on, so keep the panel visible
+            if (_wristDeviceIndex == OpenVR.k_unTrackedDeviceIndexInvalid)
+            {
+                return true;
+            }
+
+            var poses = new TrackedDevicePose_t[OpenVR.k_unMaxTrackedDeviceCount];
+            OpenVR.System.GetDeviceToAbsoluteTrackingPose(ETrackingUniverseOrigin.TrackingUniverseStanding, 0, poses);
+
+            var hmd = poses[OpenVR.k_unTrackedDeviceIndex_Hmd];
+            var wrist = poses[_wristDeviceIndex];
+            if (!hmd.bPoseIsValid || !wrist.bPoseIsValid) return _isGazing;
+
+            var m = wrist.mDeviceToAbsoluteTracking;
+            var p = _wristLocalPosition;
+            var n = _wristLocalNormal;
+
+            // Panel position and normal in world space
+            float px = m.m0 * p.v0 + m.m1 * p.v1 + m.m2 * p.v2 + m.m3;
+            float py = m.m4 * p.v0 + m.m5 * p.v1 + m.m6 * p.v2 + m.m7;
+            float pz = m.m8 * p.v0 + m.m9 * p.v1 + m.m10 * p.v2 + m.m11;
+            float nx = m.m0 * n.v0 + m.m1 * n.v1 + m.m2 * n.v2;
+            float ny = m.m4 * n.v0 + m.m5 * n.v1 + m.m6 * n.v2;
+            float nz = m.m8 * n.v0 + m.m9 * n.v1 + m.m10 * n.v2;
+
+            var h = hmd.mDeviceToAbsoluteTracking;
+            float dx = h.m3 - px;
+            float dy = h.m7 - py;
+            float dz = h.m11 - pz;
+            float length = (float)Math.Sqrt(dx * dx + dy * dy + dz * dz);
+            if (length < 0.001f) return false;
+
+            float dot = (nx * dx + ny * dy + nz * dz) / length;
+            return dot >= (float)Math.Cos(GazeAngleDegrees * Math.PI / 180.0);
         }
 
         static void UpdateOverlayPosition()
         {
             if (_overlay == null) return;
 
-            switch (_currentPosition)
+            ApplyPosition(_overlay, _currentPosition, _transform, _headPitch, true);
+
+            RefreshVisibility();
+            Console.WriteLine($"[VROverlay] Position updated to: {_currentPosition}");
+        }
+
+        // isMain: the terror panel, whose wrist pose is used for gaze mode and haptics
+        static void ApplyPosition(Overlay overlay, OverlayPosition position, OverlayTransform transform, float pitch, bool isMain)
+        {
+            switch (position)
             {
                 case OverlayPosition.RightHand:
-                    AttachToController(_overlay, ETrackedControllerRole.RightHand);
+                    AttachToController(overlay, ETrackedControllerRole.RightHand, transform, isMain);
                     break;
 
                 case OverlayPosition.LeftHand:
-                    AttachToController(_overlay, ETrackedControllerRole.LeftHand);
+                    AttachToController(overlay, ETrackedControllerRole.LeftHand, transform, isMain);
                     break;
 
                 case OverlayPosition.Above:
-                    AttachAboveHead(_overlay);
+                    AttachAboveHead(overlay, transform);
                     break;
-            }
 
-            Console.WriteLine($"[VROverlay] Position updated to: {_currentPosition}");
+                case OverlayPosition.Head:
+                    AttachToHead(overlay, pitch, transform);
+                    break;
+            }
         }
 
-        static void AttachToController(Overlay overlay, ETrackedControllerRole role)
+        static void AttachToController(Overlay overlay, ETrackedControllerRole role, OverlayTransform t, bool isMain)
         {
             uint deviceIndex = OpenVR.System.GetTrackedDeviceIndexForControllerRole(role);
             
@@ -335,6 +1169,7 @@ namespace VROverlayApp
             {
                 // Fall back to absolute positioning if controller not found
                 Console.WriteLine($"[VROverlay] Controller not found for {role}, using absolute position");
+                if (isMain) _wristDeviceIndex = OpenVR.k_unTrackedDeviceIndexInvalid;
                 var transform = new HmdMatrix34_t
                 {
                     m0 = 1, m1 = 0, m2 = 0, m3 = role == ETrackedControllerRole.RightHand ? 0.3f : -0.3f,
@@ -358,7 +1193,7 @@ namespace VROverlayApp
             // Value > 0:
             //   Right Hand: Rotates Left (CCW)
             //   Left Hand:  Rotates Right (CW)
-            float tiltAngle = 115.0f; 
+            float tiltAngle = 115.0f + t.Rotation;
             float rad = tiltAngle * (float)Math.PI / 180.0f;
             float cos = (float)Math.Cos(rad);
             float sin = (float)Math.Sin(rad);
@@ -406,6 +1241,18 @@ namespace VROverlayApp
                 u2 = -sin;
             }
             
+            // Apply user offsets, and push the panel along its normal by the extra distance
+            xOffset += t.OffsetX + f0 * t.Distance;
+            yOffset += t.OffsetY + f1 * t.Distance;
+            zOffset += t.OffsetZ + f2 * t.Distance;
+
+            if (isMain)
+            {
+                _wristDeviceIndex = deviceIndex;
+                _wristLocalPosition = new HmdVector3_t { v0 = xOffset, v1 = yOffset, v2 = zOffset };
+                _wristLocalNormal = new HmdVector3_t { v0 = f0, v1 = f1, v2 = f2 };
+            }
+
             var controllerTransform = new HmdMatrix34_t
             {
                 m0 = r0, m1 = u0, m2 = f0, m3 = xOffset,
@@ -420,16 +1267,20 @@ namespace VROverlayApp
             );
         }
 
-        static void AttachAboveHead(Overlay overlay)
+        static void AttachAboveHead(Overlay overlay, OverlayTransform t)
         {
             // Attach relative to HMD
             uint hmdIndex = OpenVR.k_unTrackedDeviceIndex_Hmd;
             
+            float rad = t.Rotation * (float)Math.PI / 180.0f;
+            float cos = (float)Math.Cos(rad);
+            float sin = (float)Math.Sin(rad);
+
             var hmdTransform = new HmdMatrix34_t
             {
-                m0 = 1, m1 = 0, m2 = 0, m3 = 0,
-                m4 = 0, m5 = 1, m6 = 0, m7 = 0.3f,   // Above
-                m8 = 0, m9 = 0, m10 = 1, m11 = -0.5f // In front
+                m0 = cos, m1 = -sin, m2 = 0, m3 = t.OffsetX,
+                m4 = sin, m5 = cos, m6 = 0, m7 = 0.3f + t.OffsetY,                // Above
+                m8 = 0, m9 = 0, m10 = 1, m11 = -0.5f + t.OffsetZ - t.Distance // In front
             };
 
             OpenVR.Overlay.SetOverlayTransformTrackedDeviceRelative(
@@ -439,6 +1290,34 @@ namespace VROverlayApp
             );
         }
 
+        static void AttachToHead(Overlay overlay, float pitchDegrees, OverlayTransform t)
+        {
+            // Fixed in view like a HUD: placed in front of the eyes and tilted by the pitch
+            // so the panel keeps facing the viewer (negative pitch = below the line of sight)
+            float distance = 0.6f + t.Distance;
+            float pitch = pitchDegrees * (float)Math.PI / 180.0f;
+            float pc = (float)Math.Cos(pitch);
+            float ps = (float)Math.Sin(pitch);
+
+            float roll = t.Rotation * (float)Math.PI / 180.0f;
+            float rc = (float)Math.Cos(roll);
+            float rs = (float)Math.Sin(roll);
+
+            // Rotation = Rx(pitch) * Rz(roll)
+            var hudTransform = new HmdMatrix34_t
+            {
+                m0 = rc,      m1 = -rs,      m2 = 0,   m3 = t.OffsetX,
+                m4 = pc * rs, m5 = pc * rc,  m6 = -ps, m7 = distance * ps + t.OffsetY,
+                m8 = ps * rs, m9 = ps * rc,  m10 = pc, m11 = -distance * pc + t.OffsetZ
+            };
+
+            OpenVR.Overlay.SetOverlayTransformTrackedDeviceRelative(
+                overlay.Handle,
+                OpenVR.k_unTrackedDeviceIndex_Hmd,
+                ref hudTransform
+            );
+        }
+
         static void CreateOverlayImage(string path, List<TerrorInfo> terrors, string roundType)
         {
             if (!OperatingSystem.IsWindows())
@@ -448,16 +1327,20 @@ namespace VROverlayApp
             }
 
             int width = 420;
-            int terrorHeaderHeight = 28;
-            int abilityLineHeight = 22;
+            int terrorHeaderHeight = Scaled(28);
+            int abilityLineHeight = Scaled(22);
+            int infoLineHeight = Scaled(24);
             int terrorSpacing = 12;
             int padding = 16;
+            int buttonHeight = Scaled(28);
             
+            var infoLines = InfoLines();
+
             // Calculate total height based on content
-            int contentHeight = 0;
+            int contentHeight = infoLines.Count * infoLineHeight; // Stats / code header lines
             if (!string.IsNullOrEmpty(roundType))
             {
-                contentHeight += 24; // Round type header
+                contentHeight += infoLineHeight; // Round type header
             }
             
             foreach (var terror in terrors)
@@ -471,55 +1354,82 @@ namespace VROverlayApp
             
             if (terrors.Count == 0)
             {
-                contentHeight = 40; // Waiting message
+                if (infoLines.Count == 0)
+                {
+                    contentHeight = 40; // Waiting message
+                }
+            }
+            else
+            {
+                contentHeight += buttonHeight; // Copy code button
             }
             
             int height = padding * 2 + Math.Max(40, contentHeight);
+            _imageWidth = width;
+            _imageHeight = height;
+            _terrorRegions = new List<(int Top, int Bottom)>();
+            _closeButton = Rectangle.Empty;
+            _copyButton = Rectangle.Empty;
 
             using var bitmap = new Bitmap(width, height);
             using var g = Graphics.FromImage(bitmap);
             
-            // Semi-transparent dark background
-            g.Clear(Color.FromArgb(230, 15, 15, 20));
+            // Semi-transparent background
+            g.Clear(Color.FromArgb(230, _backgroundColor));
             
             // Enable anti-aliasing for smoother text
             g.TextRenderingHint = System.Drawing.Text.TextRenderingHint.AntiAlias;
 
             // Draw border
-            using var borderPen = new Pen(Color.FromArgb(200, 0, 140, 255), 2);
+            using var borderPen = new Pen(Color.FromArgb(200, _accentColor), 2);
             g.DrawRectangle(borderPen, 1, 1, width - 3, height - 3);
 
-            using var terrorFont = new Font("Segoe UI", 12, FontStyle.Bold);
-            using var abilityLabelFont = new Font("Segoe UI", 9, FontStyle.Bold);
-            using var abilityValueFont = new Font("Segoe UI", 9, FontStyle.Regular);
-            using var roundFont = new Font("Segoe UI", 9, FontStyle.Italic);
+            using var terrorFont = new Font("Segoe UI", 12 * _fontScale, FontStyle.Bold);
+            using var abilityLabelFont = new Font("Segoe UI", 9 * _fontScale, FontStyle.Bold);
+            using var abilityValueFont = new Font("Segoe UI", 9 * _fontScale, FontStyle.Regular);
+            using var roundFont = new Font("Segoe UI", 9 * _fontScale, FontStyle.Italic);
             using var whiteBrush = new SolidBrush(Color.White);
             using var grayBrush = new SolidBrush(Color.FromArgb(160, 160, 160));
-            using var accentBrush = new SolidBrush(Color.FromArgb(80, 180, 255));
-            using var labelBrush = new SolidBrush(Color.FromArgb(100, 200, 255));
+            using var accentBrush = new SolidBrush(_accentColor);
+            using var labelBrush = new SolidBrush(_accentColor);
 
             int y = padding;
 
+            // Draw stats / captured code lines
+            foreach (var line in infoLines)
+            {
+                g.DrawString(line, abilityLabelFont, accentBrush, padding, y);
+                y += infoLineHeight;
+            }
+
             // Draw round type if available
             if (!string.IsNullOrEmpty(roundType))
             {
                 g.DrawString($"Round: {roundType}", roundFont, grayBrush, padding, y);
-                y += 24;
+                y += infoLineHeight;
             }
 
             // Draw terrors with abilities
             if (terrors.Count == 0)
             {
-                g.DrawString("Waiting for terrors...", terrorFont, grayBrush, padding, y);
+                if (infoLines.Count == 0)
+                {
+                    g.DrawString("Waiting for terrors...", terrorFont, grayBrush, padding, y);
+                }
             }
             else
             {
+                // Close button in the top-right corner
+                _closeButton = new Rectangle(width - padding - 20, padding - 4, 24, 24);
+                g.DrawString("×", terrorFont, grayBrush, _closeButton.X + 2, _closeButton.Y);
+
                 for (int i = 0; i < terrors.Count; i++)
                 {
                     var terror = terrors[i];
+                    int terrorTop = y;
                     
                     // Parse and use terror color if available
-                    Color terrorColor = Color.FromArgb(80, 180, 255);
+                    Color terrorColor = _accentColor;
                     if (!string.IsNullOrEmpty(terror.Color))
                     {
                         try
@@ -537,8 +1447,18 @@ namespace VROverlayApp
                     using var colorBrush = new SolidBrush(terrorColor);
                     g.FillRectangle(colorBrush, padding, y + 4, 4, terrorHeaderHeight - 8);
                     
-                    // Draw terror name
-                    g.DrawString(terror.Name, terrorFont, whiteBrush, padding + 12, y);
+                    // Draw icon, then terror name
+                    int nameX = padding + 12;
+                    using (var icon = DecodeIcon(terror.Icon))
+                    {
+                        if (icon != null)
+                        {
+                            int iconSize = terrorHeaderHeight - 4;
+                            g.DrawImage(icon, nameX, y + 2, iconSize, iconSize);
+                            nameX += iconSize + 6;
+                        }
+                    }
+                    g.DrawString(terror.Name, terrorFont, whiteBrush, nameX, y);
                     y += terrorHeaderHeight;
                     
                     // Draw abilities (max 4)
@@ -560,11 +1480,71 @@ namespace VROverlayApp
                         // Don't add more y height, just note it
                     }
                     
+                    // Warning border around watchlisted terrors
+                    if (terror.Highlighted)
+                    {
+                        using var warningPen = new Pen(Color.FromArgb(230, 255, 60, 60), 3);
+                        g.DrawRectangle(warningPen, padding - 6, terrorTop, width - padding * 2 + 12, y - terrorTop);
+                    }
+
                     y += terrorSpacing;
+                    _terrorRegions.Add((terrorTop, y));
                 }
+
+                // Copy code button
+                _copyButton = new Rectangle(padding, y, width - padding * 2, buttonHeight - 4);
+                using var buttonBrush = new SolidBrush(Color.FromArgb(120, _accentColor));
+                g.FillRectangle(buttonBrush, _copyButton);
+                g.DrawString("Copy code", abilityLabelFont, whiteBrush, _copyButton.X + 8, _copyButton.Y + 4);
             }
 
             bitmap.Save(path, ImageFormat.Png);
         }
+
+        static Image? DecodeIcon(string? base64)
+        {
+            if (string.IsNullOrEmpty(base64) || !OperatingSystem.IsWindows()) return null;
+            try
+            {
+                // Image.FromStream needs the stream for the lifetime of the image, so copy into a Bitmap
+                using var stream = new MemoryStream(Convert.FromBase64String(base64));
+                using var decoded = Image.FromStream(stream);
+                return new Bitmap(decoded);
+            }
+            catch (Exception ex)
+            {
+                Console.WriteLine($"[VROverlay] Invalid terror icon: {ex.Message}");
+                return null;
+            }
+        }
+
+        // Single-line image for a detached stats / code panel
+        static void CreateLineImage(string path, string text)
+        {
+            if (!OperatingSystem.IsWindows())
+            {
+                Console.WriteLine("[VROverlay] Image generation only supported on Windows");
+                return;
+            }
+
+            int width = 420;
+            int padding = 16;
+            int height = padding * 2 + Scaled(24);
+
+            using var bitmap = new Bitmap(width, height);
+            using var g = Graphics.FromImage(bitmap);
+
+            g.Clear(Color.FromArgb(230, _backgroundColor));
+            g.TextRenderingHint = System.Drawing.Text.TextRenderingHint.AntiAlias;
+
+            using var borderPen = new Pen(Color.FromArgb(200, _accentColor), 2);
+            g.DrawRectangle(borderPen, 1, 1, width - 3, height - 3);
+
+            using var font = new Font("Segoe UI", 9 * _fontScale, FontStyle.Bold);
+            using var accentBrush = new SolidBrush(_accentColor);
+            g.DrawString(text, font, accentBrush, padding, padding);
+
+            bitmap.Save(path, ImageFormat.Png);
+        }
     }
 }
@@ -0,0 +1,58 @@
+//! バックエンドで起きたエラーをフロントエンドに知らせる
+//!
+//! ログに出すだけだと利用者には見えないため、`app_error`イベントを発行し、
+//! 直近のものは後から`get_recent_errors`で取り出せるように保持する
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::now_timestamp;
+
+/// 保持するエラーの件数
+const MAX_RECENT_ERRORS: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 処理は続けられる（再試行する・一部だけ失敗した）
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppError {
+    pub severity: Severity,
+    pub message: String,
+    pub timestamp: String,
+}
+
+static RECENT_ERRORS: Mutex<VecDeque<AppError>> = Mutex::new(VecDeque::new());
+
+/// ログに出し、直近のエラーとして保持して`app_error`イベントを発行する
+pub fn report(app_handle: &AppHandle, severity: Severity, message: impl Into<String>) {
+    let error = AppError {
+        severity,
+        message: message.into(),
+        timestamp: now_timestamp(),
+    };
+    println!("[tsst] {}", error.message);
+
+    if let Ok(mut recent) = RECENT_ERRORS.lock() {
+        if recent.len() >= MAX_RECENT_ERRORS {
+            recent.pop_front();
+        }
+        recent.push_back(error.clone());
+    }
+    let _ = app_handle.emit("app_error", &error);
+}
+
+/// 直近のエラー（古い順）
+pub fn recent() -> Vec<AppError> {
+    RECENT_ERRORS
+        .lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
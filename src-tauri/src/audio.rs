@@ -0,0 +1,118 @@
+//! イベント時にユーザーが選んだ音声ファイルを再生する
+
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use rodio::{Decoder, OutputStream, Sink};
+use serde::{Deserialize, Serialize};
+
+/// 効果音を鳴らすイベント
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundEvent {
+    CodeSaved,
+    RoundStarted,
+    TerrorSpawned,
+    Death,
+}
+
+/// 効果音の設定（ファイル未設定のイベントは鳴らさない）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    /// 音量（0.0〜1.0）
+    pub volume: f32,
+    pub code_saved: Option<String>,
+    pub round_started: Option<String>,
+    pub terror_spawned: Option<String>,
+    pub death: Option<String>,
+    /// テラーIDごとの警報音（設定されていれば出現時の音の代わりに鳴らす）
+    pub terror_sounds: HashMap<u32, String>,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 0.8,
+            code_saved: None,
+            round_started: None,
+            terror_spawned: None,
+            death: None,
+            terror_sounds: HashMap::new(),
+        }
+    }
+}
+
+impl SoundSettings {
+    pub fn file_for(&self, event: SoundEvent) -> Option<&str> {
+        match event {
+            SoundEvent::CodeSaved => self.code_saved.as_deref(),
+            SoundEvent::RoundStarted => self.round_started.as_deref(),
+            SoundEvent::TerrorSpawned => self.terror_spawned.as_deref(),
+            SoundEvent::Death => self.death.as_deref(),
+        }
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        [
+            &self.code_saved,
+            &self.round_started,
+            &self.terror_spawned,
+            &self.death,
+        ]
+        .into_iter()
+        .filter_map(|file| file.as_deref())
+        .chain(self.terror_sounds.values().map(String::as_str))
+    }
+}
+
+/// 有効かつファイルが設定されていればイベントの音を鳴らす
+pub fn play_event(settings: &SoundSettings, event: SoundEvent) {
+    if !settings.enabled {
+        return;
+    }
+    if let Some(file) = settings.file_for(event) {
+        play_file(file, settings.volume);
+    }
+}
+
+/// 出現したテラーの警報音を鳴らす（個別の音がなければ共通の出現音）
+pub fn play_terror_spawned(settings: &SoundSettings, killers: &[u32]) {
+    if !settings.enabled {
+        return;
+    }
+    let mut files: Vec<&str> = killers
+        .iter()
+        .filter_map(|id| settings.terror_sounds.get(id).map(String::as_str))
+        .collect();
+    files.dedup();
+    if files.is_empty() {
+        play_event(settings, SoundEvent::TerrorSpawned);
+    }
+    for file in files {
+        play_file(file, settings.volume);
+    }
+}
+
+/// 音声ファイルを別スレッドで再生する（再生の終了は待たない）
+pub fn play_file(path: &str, volume: f32) {
+    let path = path.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = play_blocking(Path::new(&path), volume) {
+            println!("[tsst] Failed to play sound {}: {}", path, e);
+        }
+    });
+}
+
+fn play_blocking(path: &Path, volume: f32) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let source = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+    // OutputStreamはスレッドをまたげないので再生スレッドごとに開く
+    let (_stream, handle) = OutputStream::try_default().map_err(|e| e.to_string())?;
+    let sink = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+    sink.set_volume(volume.clamp(0.0, 1.0));
+    sink.append(source);
+    sink.sleep_until_end();
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! data.jsonのクラウドバックアップ
+//!
+//! データが変わってから一定時間たったらアップロードし、ディスクが壊れても履歴を戻せるようにする。
+//! パスワードなどの秘密情報は設定ファイルには書かず、OSの資格情報ストアに保存する
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::s3::{self, S3Settings};
+use crate::secrets;
+
+/// アップロード先のファイル名
+const BACKUP_FILE_NAME: &str = "ton-simple-save-tool-data.json";
+/// 通信のタイムアウト
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// 変更からアップロードまでの待ち時間として受け付ける範囲（秒）
+pub const DEBOUNCE_SECS_RANGE: (u32, u32) = (5, 3600);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupProvider {
+    #[default]
+    WebDav,
+    /// S3互換のオブジェクトストレージ
+    S3,
+}
+
+impl BackupProvider {
+    /// 資格情報ストアでのアカウント名
+    fn keyring_user(self) -> &'static str {
+        match self {
+            BackupProvider::WebDav => "webdav",
+            BackupProvider::S3 => "s3",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct WebDavSettings {
+    /// 保存先フォルダのURL（このフォルダにバックアップファイルを置く）
+    pub url: String,
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CloudBackupSettings {
+    pub enabled: bool,
+    pub provider: BackupProvider,
+    /// 最後の変更からアップロードまでの秒数（続けて変わったらまとめて1回にする）
+    pub debounce_secs: u32,
+    pub webdav: WebDavSettings,
+    /// シークレットアクセスキーは資格情報ストアに保存する
+    pub s3: S3Settings,
+}
+
+impl Default for CloudBackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: BackupProvider::default(),
+            debounce_secs: 30,
+            webdav: WebDavSettings::default(),
+            s3: S3Settings::default(),
+        }
+    }
+}
+
+impl CloudBackupSettings {
+    pub fn validate(&mut self) -> Result<(), String> {
+        self.debounce_secs = self
+            .debounce_secs
+            .clamp(DEBOUNCE_SECS_RANGE.0, DEBOUNCE_SECS_RANGE.1);
+        if !self.enabled {
+            return Ok(());
+        }
+        match self.provider {
+            BackupProvider::WebDav => {
+                let url = &self.webdav.url;
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    return Err(format!("webdav url must start with http(s)://: {}", url));
+                }
+            }
+            BackupProvider::S3 => self.s3.validate()?,
+        }
+        Ok(())
+    }
+}
+
+/// 秘密情報を資格情報ストアに保存する（空文字なら削除する）
+pub fn store_secret(provider: BackupProvider, secret: &str) -> Result<(), String> {
+    secrets::store(provider.keyring_user(), secret)
+}
+
+fn load_secret(provider: BackupProvider) -> Result<String, String> {
+    secrets::load(provider.keyring_user())
+}
+
+/// バックアップファイルのURL
+fn backup_url(folder: &str) -> String {
+    format!("{}/{}", folder.trim_end_matches('/'), BACKUP_FILE_NAME)
+}
+
+fn basic_auth(username: &str, password: &str) -> String {
+    let credentials = format!("{}:{}", username, password);
+    format!(
+        "Basic {}",
+        base64::engine::general_purpose::STANDARD.encode(credentials)
+    )
+}
+
+/// アップロードする（呼び出し元のスレッドで完了まで待つ）
+pub fn upload(settings: &CloudBackupSettings, payload: &[u8]) -> Result<(), String> {
+    match settings.provider {
+        BackupProvider::WebDav => {
+            let webdav = &settings.webdav;
+            let password = load_secret(settings.provider)?;
+            ureq::put(&backup_url(&webdav.url))
+                .timeout(REQUEST_TIMEOUT)
+                .set("Authorization", &basic_auth(&webdav.username, &password))
+                .set("Content-Type", "application/json")
+                .send_bytes(payload)
+                .map(|_| ())
+                .map_err(|e| format!("webdav upload failed: {}", e))
+        }
+        BackupProvider::S3 => {
+            let secret_key = load_secret(settings.provider)?;
+            s3::put_object(&settings.s3, &secret_key, BACKUP_FILE_NAME, payload)
+        }
+    }
+}
+
+/// バックアップを取得する
+pub fn download(settings: &CloudBackupSettings) -> Result<String, String> {
+    match settings.provider {
+        BackupProvider::WebDav => {
+            let webdav = &settings.webdav;
+            let password = load_secret(settings.provider)?;
+            ureq::get(&backup_url(&webdav.url))
+                .timeout(REQUEST_TIMEOUT)
+                .set("Authorization", &basic_auth(&webdav.username, &password))
+                .call()
+                .map_err(|e| format!("webdav download failed: {}", e))?
+                .into_string()
+                .map_err(|e| e.to_string())
+        }
+        BackupProvider::S3 => {
+            let secret_key = load_secret(settings.provider)?;
+            s3::get_object(&settings.s3, &secret_key, BACKUP_FILE_NAME)
+        }
+    }
+}
+
+/// 未アップロードの変更（Tauriのstateとして管理する）
+#[derive(Default)]
+pub struct CloudBackup {
+    changed_at: Mutex<Option<Instant>>,
+}
+
+impl CloudBackup {
+    /// データが変わったことを記録する（待ち時間はここから数え直す）
+    pub fn mark_changed(&self) {
+        if let Ok(mut changed_at) = self.changed_at.lock() {
+            *changed_at = Some(Instant::now());
+        }
+    }
+
+    /// 最後の変更から`debounce`たっていれば、未アップロードの印を外して`true`を返す
+    pub fn take_due(&self, debounce: Duration) -> bool {
+        let Ok(mut changed_at) = self.changed_at.lock() else {
+            return false;
+        };
+        if changed_at.is_some_and(|at| at.elapsed() >= debounce) {
+            *changed_at = None;
+            return true;
+        }
+        false
+    }
+}
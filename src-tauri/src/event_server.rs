@@ -0,0 +1,299 @@
+//! ローカルのWebSocketイベントサーバー
+//!
+//! Tauriのイベントと同じものをJSONで配信し、配信用オーバーレイや外部ツールから受け取れるようにする。
+//! 接続は127.0.0.1からのみ受け付け、REST APIと同じトークンを`?token=<token>`か
+//! `Authorization: Bearer <token>`で求める。ブラウザで開いた他のサイトから読まれないよう、
+//! localhost以外の`Origin`からの接続は断る
+
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tungstenite::handshake::server::{Callback, ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::Message;
+
+use crate::rest_api::token_matches;
+use crate::tonsave_compat;
+
+/// 外部に配信するイベント（`state_updated`は設定を含むため配信しない）
+pub const FORWARDED_EVENTS: [&str; 9] = [
+    "code_found",
+    "round_started",
+    "terrors_spawned",
+    "player_died",
+    "round_ended",
+    "next_round_predicted",
+    "session_ended",
+    "milestone_unlocked",
+    "watchlist_terror_spawned",
+];
+
+/// 接続待ち・送信待ちの確認間隔（停止の反映やクライアントからのCloseの確認に使う）
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 配信するメッセージの形式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventFormat {
+    /// `{"event": イベント名, "payload": Tauriのイベントと同じ内容}`
+    #[default]
+    Native,
+    /// ToNSaveManagerのWebSocket APIと同じ形式
+    TonSaveManager,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EventServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub format: EventFormat,
+}
+
+impl Default for EventServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 11398,
+            format: EventFormat::default(),
+        }
+    }
+}
+
+/// 配信するメッセージ
+#[derive(Debug, Serialize)]
+struct EventMessage<'a> {
+    event: &'a str,
+    payload: serde_json::Value,
+}
+
+type Clients = Arc<Mutex<Vec<Sender<String>>>>;
+
+struct Running {
+    port: u16,
+    format: EventFormat,
+    /// 接続時の認証に使うトークン（作り直されたら起動し直さずに差し替える）
+    token: Arc<Mutex<String>>,
+    stop: Arc<AtomicBool>,
+    clients: Clients,
+}
+
+/// 起動中のサーバー（Tauriのstateとして管理する）
+#[derive(Default)]
+pub struct EventServer {
+    running: Mutex<Option<Running>>,
+}
+
+impl EventServer {
+    /// 設定に合わせて起動・停止する（ポートが変わったら起動し直す）
+    pub fn configure(&self, settings: &EventServerSettings, token: &str) -> Result<(), String> {
+        let mut running = self
+            .running
+            .lock()
+            .map_err(|_| "event server lock failed")?;
+        if let Some(current) = running.as_mut() {
+            if settings.enabled && current.port == settings.port {
+                current.format = settings.format;
+                if let Ok(mut current_token) = current.token.lock() {
+                    *current_token = token.to_string();
+                }
+                return Ok(());
+            }
+            current.stop.store(true, Ordering::Relaxed);
+            *running = None;
+            println!("[tsst] Event server stopped");
+        }
+        if settings.enabled {
+            if token.is_empty() {
+                return Err("event server token is empty".to_string());
+            }
+            *running = Some(start(settings.port, settings.format, token)?);
+            println!(
+                "[tsst] Event server listening on 127.0.0.1:{}",
+                settings.port
+            );
+        }
+        Ok(())
+    }
+
+    /// 接続中のクライアントにイベントを送る（`payload`はTauriのイベントと同じJSON）
+    pub fn broadcast(&self, event: &str, payload: &str) {
+        let Ok(running) = self.running.lock() else {
+            return;
+        };
+        let Some(running) = running.as_ref() else {
+            return;
+        };
+        let Ok(mut clients) = running.clients.lock() else {
+            return;
+        };
+        if clients.is_empty() {
+            return;
+        }
+        let payload = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
+        let texts: Vec<String> = match running.format {
+            EventFormat::Native => serde_json::to_string(&EventMessage { event, payload })
+                .into_iter()
+                .collect(),
+            EventFormat::TonSaveManager => tonsave_compat::messages(event, &payload)
+                .iter()
+                .map(|message| message.to_string())
+                .collect(),
+        };
+        // 切断済みのクライアントは送信に失敗するので取り除く
+        for text in texts {
+            clients.retain(|client| client.send(text.clone()).is_ok());
+        }
+    }
+}
+
+fn start(port: u16, format: EventFormat, token: &str) -> Result<Running, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("failed to listen on port {}: {}", port, e))?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let clients: Clients = Arc::default();
+    let token = Arc::new(Mutex::new(token.to_string()));
+    {
+        let stop = stop.clone();
+        let clients = clients.clone();
+        let token = token.clone();
+        std::thread::spawn(move || accept_loop(listener, stop, clients, token));
+    }
+    Ok(Running {
+        port,
+        format,
+        token,
+        stop,
+        clients,
+    })
+}
+
+fn accept_loop(
+    listener: TcpListener,
+    stop: Arc<AtomicBool>,
+    clients: Clients,
+    token: Arc<Mutex<String>>,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                println!("[tsst] Event server client connected: {}", addr);
+                let stop = stop.clone();
+                let clients = clients.clone();
+                let token = token.lock().map(|t| t.clone()).unwrap_or_default();
+                std::thread::spawn(move || {
+                    if let Err(e) = serve_client(stream, &clients, &token, &stop) {
+                        println!("[tsst] Event server client disconnected: {}", e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                println!("[tsst] Event server accept failed: {}", e);
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// 接続を受け付けてよいか（トークンが正しく、ブラウザからならlocalhostのページか）
+fn check_handshake(request: &Request, token: &str) -> Result<(), (StatusCode, &'static str)> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    if let Some(origin) = header("Origin") {
+        if !matches!(origin_host(origin), "localhost" | "127.0.0.1" | "[::1]") {
+            return Err((StatusCode::FORBIDDEN, "origin not allowed"));
+        }
+    }
+    let provided = header("Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .or_else(|| {
+            request
+                .uri()
+                .query()
+                .and_then(|query| query.split('&').find_map(|p| p.strip_prefix("token=")))
+        });
+    if !provided.is_some_and(|provided| token_matches(provided, token)) {
+        return Err((StatusCode::UNAUTHORIZED, "unauthorized"));
+    }
+    Ok(())
+}
+
+/// ハンドシェイクで接続を確かめる（拒否するときは理由を付けて返す）
+struct HandshakeCheck<'a> {
+    token: &'a str,
+}
+
+impl Callback for HandshakeCheck<'_> {
+    fn on_request(self, request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        check_handshake(request, self.token)
+            .map(|_| response)
+            .map_err(|(status, reason)| {
+                let mut error = ErrorResponse::new(Some(reason.to_string()));
+                *error.status_mut() = status;
+                error
+            })
+    }
+}
+
+/// `Origin`のホスト名（`http://localhost:5173`なら`localhost`）
+fn origin_host(origin: &str) -> &str {
+    let rest = origin.split_once("://").map_or("", |(_, rest)| rest);
+    if rest.starts_with('[') {
+        return rest.find(']').map_or(rest, |end| &rest[..=end]);
+    }
+    rest.split(':').next().unwrap_or_default()
+}
+
+fn serve_client(
+    stream: TcpStream,
+    clients: &Clients,
+    token: &str,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+    let mut socket =
+        tungstenite::accept_hdr(stream, HandshakeCheck { token }).map_err(|e| e.to_string())?;
+    // 認証できた接続にだけイベントを送る
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut clients) = clients.lock() {
+        clients.push(tx);
+    }
+    // 受信は送信の合間に確認するだけなので短くタイムアウトさせる
+    socket
+        .get_ref()
+        .set_read_timeout(Some(Duration::from_millis(10)))
+        .map_err(|e| e.to_string())?;
+
+    while !stop.load(Ordering::Relaxed) {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(text) => socket
+                .send(Message::text(text))
+                .map_err(|e| e.to_string())?,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        match socket.read() {
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use std::{fs, path::Path};
+
+use crate::stats::{best_streak, current_streak, death_causes, query_rounds, StatsFilter};
+use crate::terror_updates::get_terrors_data;
+use crate::{AppData, RoundRecord};
+
+/// CSVのフィールドをエスケープ（カンマ・改行・引用符を含む場合は引用符で囲む）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|f| csv_field(f))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// ラウンド記録1件分のテラー名（" / " 区切り）
+pub fn terror_names(record: &RoundRecord) -> String {
+    get_terrors_data(&record.killers, &record.round_type)
+        .into_iter()
+        .map(|d| d.name)
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// 無効化されていないラウンド記録をCSVとして書き出す
+pub fn write_rounds_csv(path: &Path, rounds: &[RoundRecord]) -> Result<usize, String> {
+    let mut lines = vec![csv_row(&[
+        "timestamp".to_string(),
+        "map".to_string(),
+        "round_type".to_string(),
+        "terrors".to_string(),
+        "result".to_string(),
+        "duration_secs".to_string(),
+        "stuns".to_string(),
+        "code".to_string(),
+        "max_bpm".to_string(),
+        "avg_bpm".to_string(),
+    ])];
+
+    let mut count = 0;
+    for record in rounds.iter().filter(|r| !r.excluded) {
+        lines.push(csv_row(&[
+            record.ended_at.clone(),
+            record.map_name.clone().unwrap_or_default(),
+            record.round_type.clone(),
+            terror_names(record),
+            if record.is_dead { "died" } else { "survived" }.to_string(),
+            record
+                .duration_secs
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            record.stuns.to_string(),
+            record.save_code.clone().unwrap_or_default(),
+            record.max_bpm.map(|b| b.to_string()).unwrap_or_default(),
+            record.avg_bpm.map(|b| b.to_string()).unwrap_or_default(),
+        ]));
+        count += 1;
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    // Excelで文字化けしないようBOM付きUTF-8で出力
+    let payload = format!("\u{feff}{}\r\n", lines.join("\r\n"));
+    fs::write(path, payload).map_err(|err| err.to_string())?;
+    Ok(count)
+}
+
+/// プレイヤー名・インスタンスID・アカウントを取り除いたデータを作成
+pub fn anonymized(data: &AppData) -> AppData {
+    let mut data = data.clone();
+    for record in data.rounds.iter_mut() {
+        record.account = None;
+    }
+    for session in data.sessions.iter_mut() {
+        session.instance_id = None;
+        session.players.clear();
+    }
+    data
+}
+
+/// データ全体をJSONとして書き出す（anonymize=trueの場合は個人情報を除去）
+pub fn write_data_json(path: &Path, data: &AppData, anonymize: bool) -> Result<(), String> {
+    let data = if anonymize {
+        anonymized(data)
+    } else {
+        data.clone()
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let payload = serde_json::to_string_pretty(&data).map_err(|err| err.to_string())?;
+    fs::write(path, payload).map_err(|err| err.to_string())
+}
+
+/// 共有用のテキスト形式で統計をまとめる（Discord等への貼り付け用）
+pub fn stats_summary_text(data: &AppData, session_since: Option<&str>) -> String {
+    let stats = &data.stats;
+    let total = stats.survivals + stats.deaths;
+    let rate = if total == 0 {
+        0.0
+    } else {
+        stats.survivals as f64 / total as f64 * 100.0
+    };
+
+    let mut lines = vec![
+        "【ToN Simple Save Tool 統計】".to_string(),
+        format!(
+            "通算: {}ラウンド / 生存 {} / 死亡 {} (生存率 {:.1}%)",
+            total, stats.survivals, stats.deaths, rate
+        ),
+        format!(
+            "連続生存: 現在 {} / 最長 {}",
+            current_streak(&data.rounds),
+            best_streak(&data.rounds)
+        ),
+    ];
+
+    if let Some(since) = session_since {
+        let filter = StatsFilter {
+            from: Some(since.to_string()),
+            ..Default::default()
+        };
+        let session = query_rounds(&data.rounds, &filter);
+        lines.push(format!(
+            "セッション: {}ラウンド / 生存 {} / 死亡 {} (生存率 {:.1}%)",
+            session.rounds,
+            session.survivals,
+            session.deaths,
+            session.survival_rate * 100.0
+        ));
+    }
+
+    if let Some(nemesis) = death_causes(&data.rounds)
+        .into_iter()
+        .find(|c| c.deaths > 0)
+    {
+        lines.push(format!(
+            "天敵: {} (死亡 {}回)",
+            nemesis.name, nemesis.deaths
+        ));
+    }
+
+    lines.join("\n")
+}
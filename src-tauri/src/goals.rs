@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::{best_streak, in_range};
+use crate::terror_updates::get_terrors_data;
+use crate::{now_timestamp, RoundRecord};
+
+/// 目標の種類
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GoalKind {
+    /// 指定ラウンドタイプ（未指定なら全て）でN回生存
+    SurviveRounds {
+        round_type: Option<String>,
+        count: u32,
+    },
+    /// 指定ラウンドタイプ（未指定なら全て）をNラウンドプレイ
+    PlayRounds {
+        round_type: Option<String>,
+        count: u32,
+    },
+    /// N連続生存
+    SurvivalStreak { count: u32 },
+    /// N種類のテラーに遭遇
+    EncounterTerrors { count: u32 },
+}
+
+/// ユーザー定義の目標
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: u64,
+    pub title: String,
+    #[serde(flatten)]
+    pub kind: GoalKind,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// 目標と進捗
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalProgress {
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub current: u32,
+    pub target: u32,
+}
+
+impl Goal {
+    pub fn new(id: u64, title: String, kind: GoalKind) -> Self {
+        Self {
+            id,
+            title,
+            kind,
+            created_at: now_timestamp(),
+            completed_at: None,
+        }
+    }
+
+    /// 目標作成以降のラウンド記録から進捗を計算
+    pub fn progress(&self, rounds: &[RoundRecord]) -> GoalProgress {
+        let rounds = rounds
+            .iter()
+            .filter(|r| !r.excluded && in_range(&r.ended_at, Some(self.created_at.as_str()), None));
+        let matches_type = |record: &RoundRecord, round_type: &Option<String>| match round_type {
+            Some(rt) => &record.round_type == rt,
+            None => true,
+        };
+
+        let (current, target) = match &self.kind {
+            GoalKind::SurviveRounds { round_type, count } => (
+                rounds
+                    .filter(|r| !r.is_dead && matches_type(r, round_type))
+                    .count() as u32,
+                *count,
+            ),
+            GoalKind::PlayRounds { round_type, count } => (
+                rounds.filter(|r| matches_type(r, round_type)).count() as u32,
+                *count,
+            ),
+            GoalKind::SurvivalStreak { count } => (best_streak(rounds), *count),
+            GoalKind::EncounterTerrors { count } => {
+                let terrors: HashSet<String> = rounds
+                    .flat_map(|r| get_terrors_data(&r.killers, &r.round_type))
+                    .map(|d| d.name)
+                    .collect();
+                (terrors.len() as u32, *count)
+            }
+        };
+
+        GoalProgress {
+            goal: self.clone(),
+            current: current.min(target),
+            target,
+        }
+    }
+}
+
+/// 未達成の目標をチェックし、新たに達成したものを記録して返す
+pub fn check_goals(goals: &mut [Goal], rounds: &[RoundRecord]) -> Vec<Goal> {
+    let mut completed = Vec::new();
+    for goal in goals.iter_mut().filter(|g| g.completed_at.is_none()) {
+        let progress = goal.progress(rounds);
+        if progress.current >= progress.target {
+            goal.completed_at = Some(now_timestamp());
+            completed.push(goal.clone());
+        }
+    }
+    completed
+}
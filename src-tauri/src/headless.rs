@@ -0,0 +1,68 @@
+//! ウィンドウ・トレイなしで動かすモード（`--headless`）
+//!
+//! 配信用PCなどで、ログ監視・保存・クリップボード・外部連携だけを動かす。
+//! ウィンドウを1つも作らないのでWebViewも初期化しない。
+//! イベントは1行1つのJSON（`{"event": ..., "timestamp": ..., "payload": ...}`）で標準出力に書き出す
+//! （`[tsst]`で始まる行は従来どおりのログ）
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::{json, Value};
+
+/// ヘッドレスモードで起動する引数
+pub const ARG_HEADLESS: &str = "--headless";
+
+/// 標準出力に書き出すイベント（頻繁に出る`state_updated`は除く）
+pub const PRINTED_EVENTS: [&str; 11] = [
+    "round_started",
+    "terrors_spawned",
+    "player_died",
+    "round_ended",
+    "code_found",
+    "session_ended",
+    "milestone_unlocked",
+    "goal_completed",
+    "watchlist_terror_spawned",
+    "terror_data_updated",
+    "app_error",
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 起動引数に`--headless`があるか
+pub fn requested(mut args: impl Iterator<Item = String>) -> bool {
+    args.any(|arg| arg == ARG_HEADLESS)
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+    attach_console();
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// イベントを1行のJSONで書き出す
+pub fn print_event(event: &str, payload: &str) {
+    let payload: Value = serde_json::from_str(payload).unwrap_or(Value::Null);
+    let line = json!({
+        "event": event,
+        "timestamp": chrono::Local::now().to_rfc3339(),
+        "payload": payload,
+    });
+    println!("{}", line);
+}
+
+/// リリースビルドはGUIアプリとしてビルドしているため、起動元のコンソールに出力をつなぐ
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // 起動元がコンソールでない場合（リダイレクト先がある場合を含む）は失敗してよい
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
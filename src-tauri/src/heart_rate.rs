@@ -0,0 +1,233 @@
+//! Pulsoid / HypeRateからの心拍数の受信
+//!
+//! WebSocketで受け取った心拍数をラウンドごとに記録し、最大・平均BPMとして残す。
+//! 切断されたら一定時間おいて接続し直す
+
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::secrets;
+
+/// 受信待ちのタイムアウト（停止の確認に使う）
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+/// 切断後に接続し直すまでの時間
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(10);
+/// HypeRateの接続を保つためのheartbeatの間隔
+const HYPERATE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(25);
+/// 資格情報ストアでのアカウント名
+const PULSOID_SECRET: &str = "pulsoid";
+const HYPERATE_SECRET: &str = "hyperate";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeartRateProvider {
+    #[default]
+    Pulsoid,
+    HypeRate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct HeartRateSettings {
+    pub enabled: bool,
+    pub provider: HeartRateProvider,
+    /// Pulsoidのアクセストークン（設定ファイルには書かず、受け取ったら資格情報ストアに移す）
+    #[serde(skip_serializing)]
+    pub pulsoid_token: String,
+    /// HypeRateのAPIキー（設定ファイルには書かず、受け取ったら資格情報ストアに移す）
+    #[serde(skip_serializing)]
+    pub hyperate_api_key: String,
+    /// HypeRateのセッションID（アプリに表示されるID）
+    pub hyperate_id: String,
+}
+
+impl HeartRateSettings {
+    /// 受け取ったトークン・APIキーを資格情報ストアに移す（移したものがあれば`true`）
+    pub fn store_secrets(&mut self) -> Result<bool, String> {
+        let pulsoid = secrets::take_into_store(PULSOID_SECRET, &mut self.pulsoid_token)?;
+        let hyperate = secrets::take_into_store(HYPERATE_SECRET, &mut self.hyperate_api_key)?;
+        Ok(pulsoid || hyperate)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.provider == HeartRateProvider::HypeRate && self.hyperate_id.is_empty() {
+            return Err(format!("{:?} credentials are not set", self.provider));
+        }
+        Ok(())
+    }
+
+    /// 使う接続先のトークン・APIキー
+    fn load_secret(&self) -> Result<String, String> {
+        secrets::load(match self.provider {
+            HeartRateProvider::Pulsoid => PULSOID_SECRET,
+            HeartRateProvider::HypeRate => HYPERATE_SECRET,
+        })
+    }
+}
+
+/// 受け取った心拍数の処理（lib側で記録する）
+pub type SampleHandler = Arc<dyn Fn(u32) + Send + Sync>;
+
+struct Running {
+    settings: HeartRateSettings,
+    /// 接続に使ったトークン（作り直されたら接続し直す）
+    secret: String,
+    stop: Arc<AtomicBool>,
+}
+
+/// 受信中の接続（Tauriのstateとして管理する）
+#[derive(Default)]
+pub struct HeartRateMonitor {
+    running: Mutex<Option<Running>>,
+}
+
+impl HeartRateMonitor {
+    /// 設定に合わせて接続・切断する（接続先が変わったら接続し直す）
+    pub fn configure(
+        &self,
+        settings: &HeartRateSettings,
+        handler: SampleHandler,
+    ) -> Result<(), String> {
+        settings.validate()?;
+        let secret = if settings.enabled {
+            settings.load_secret()?
+        } else {
+            String::new()
+        };
+        let mut running = self.running.lock().map_err(|_| "heart rate lock failed")?;
+        if let Some(current) = running.as_ref() {
+            if settings.enabled && current.settings == *settings && current.secret == secret {
+                return Ok(());
+            }
+            current.stop.store(true, Ordering::Relaxed);
+            *running = None;
+            println!("[tsst] Heart rate monitor stopped");
+        }
+        if settings.enabled {
+            let stop = Arc::new(AtomicBool::new(false));
+            {
+                let settings = settings.clone();
+                let secret = secret.clone();
+                let stop = stop.clone();
+                std::thread::spawn(move || run(&settings, &secret, &stop, &handler));
+            }
+            *running = Some(Running {
+                settings: settings.clone(),
+                secret,
+                stop,
+            });
+            println!(
+                "[tsst] Heart rate monitor started ({:?})",
+                settings.provider
+            );
+        }
+        Ok(())
+    }
+}
+
+fn run(settings: &HeartRateSettings, secret: &str, stop: &AtomicBool, handler: &SampleHandler) {
+    while !stop.load(Ordering::Relaxed) {
+        if let Err(e) = receive(settings, secret, stop, handler) {
+            println!("[tsst] Heart rate connection lost: {}", e);
+        }
+        let started = Instant::now();
+        while started.elapsed() < RECONNECT_INTERVAL && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(READ_TIMEOUT);
+        }
+    }
+}
+
+fn receive(
+    settings: &HeartRateSettings,
+    secret: &str,
+    stop: &AtomicBool,
+    handler: &SampleHandler,
+) -> Result<(), String> {
+    let url = match settings.provider {
+        HeartRateProvider::Pulsoid => format!(
+            "wss://dev.pulsoid.net/api/v1/data/real_time?access_token={}",
+            secret
+        ),
+        HeartRateProvider::HypeRate => {
+            format!("wss://app.hyperate.io/socket/websocket?token={}", secret)
+        }
+    };
+    let (mut socket, _) = tungstenite::connect(url).map_err(|e| e.to_string())?;
+    set_read_timeout(&socket)?;
+    println!("[tsst] Heart rate connected ({:?})", settings.provider);
+
+    let topic = format!("hr:{}", settings.hyperate_id);
+    let mut last_heartbeat = Instant::now();
+    if settings.provider == HeartRateProvider::HypeRate {
+        let join = json!({ "topic": topic, "event": "phx_join", "payload": {}, "ref": 0 });
+        socket
+            .send(Message::text(join.to_string()))
+            .map_err(|e| e.to_string())?;
+    }
+
+    while !stop.load(Ordering::Relaxed) {
+        if settings.provider == HeartRateProvider::HypeRate
+            && last_heartbeat.elapsed() >= HYPERATE_HEARTBEAT_INTERVAL
+        {
+            let heartbeat =
+                json!({ "topic": "phoenix", "event": "heartbeat", "payload": {}, "ref": 0 });
+            socket
+                .send(Message::text(heartbeat.to_string()))
+                .map_err(|e| e.to_string())?;
+            last_heartbeat = Instant::now();
+        }
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                if let Some(bpm) = parse_bpm(settings.provider, &text) {
+                    handler(bpm);
+                }
+            }
+            Ok(Message::Close(_)) => return Err("closed by server".to_string()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    let _ = socket.close(None);
+    Ok(())
+}
+
+fn set_read_timeout(socket: &WebSocket<MaybeTlsStream<TcpStream>>) -> Result<(), String> {
+    let stream = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream,
+        MaybeTlsStream::Rustls(stream) => stream.get_ref(),
+        _ => return Ok(()),
+    };
+    stream
+        .set_read_timeout(Some(READ_TIMEOUT))
+        .map_err(|e| e.to_string())
+}
+
+/// 受信したメッセージから心拍数を取り出す
+fn parse_bpm(provider: HeartRateProvider, text: &str) -> Option<u32> {
+    let message: Value = serde_json::from_str(text).ok()?;
+    let bpm = match provider {
+        // {"measured_at": ..., "data": {"heart_rate": 80}}
+        HeartRateProvider::Pulsoid => message.get("data")?.get("heart_rate")?,
+        // {"event": "hr_update", "payload": {"hr": 80}, ...}
+        HeartRateProvider::HypeRate => {
+            if message.get("event")?.as_str()? != "hr_update" {
+                return None;
+            }
+            message.get("payload")?.get("hr")?
+        }
+    };
+    bpm.as_u64().map(|bpm| bpm as u32).filter(|&bpm| bpm > 0)
+}
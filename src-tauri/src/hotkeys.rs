@@ -0,0 +1,100 @@
+//! グローバルホットキーの割り当て
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+/// ホットキーで実行する操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    CopyLatestCode,
+    ToggleVrOverlay,
+    ToggleDesktopOverlay,
+    ToggleMonitoring,
+    ShockKillSwitch,
+}
+
+/// 操作ごとのキー割り当て（`Ctrl+Shift+C` 形式、未設定なら登録しない）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct HotkeySettings {
+    pub copy_latest_code: Option<String>,
+    pub toggle_vr_overlay: Option<String>,
+    pub toggle_desktop_overlay: Option<String>,
+    pub toggle_monitoring: Option<String>,
+    /// OpenShock / PiShockのキルスイッチ
+    pub shock_kill_switch: Option<String>,
+}
+
+impl HotkeySettings {
+    fn entries(&self) -> [(HotkeyAction, Option<&str>); 5] {
+        [
+            (
+                HotkeyAction::CopyLatestCode,
+                self.copy_latest_code.as_deref(),
+            ),
+            (
+                HotkeyAction::ToggleVrOverlay,
+                self.toggle_vr_overlay.as_deref(),
+            ),
+            (
+                HotkeyAction::ToggleDesktopOverlay,
+                self.toggle_desktop_overlay.as_deref(),
+            ),
+            (
+                HotkeyAction::ToggleMonitoring,
+                self.toggle_monitoring.as_deref(),
+            ),
+            (
+                HotkeyAction::ShockKillSwitch,
+                self.shock_kill_switch.as_deref(),
+            ),
+        ]
+    }
+
+    /// 割り当てを解析し、同じキーが複数の操作に使われていないか確かめる
+    pub fn parse(&self) -> Result<Vec<(HotkeyAction, Shortcut)>, String> {
+        let mut parsed: Vec<(HotkeyAction, Shortcut)> = Vec::new();
+        for (action, key) in self.entries() {
+            let Some(key) = key.map(str::trim).filter(|k| !k.is_empty()) else {
+                continue;
+            };
+            let shortcut =
+                Shortcut::from_str(key).map_err(|e| format!("invalid hotkey {}: {}", key, e))?;
+            if let Some((other, _)) = parsed.iter().find(|(_, s)| *s == shortcut) {
+                return Err(format!(
+                    "hotkey {} is assigned to both {:?} and {:?}",
+                    key, other, action
+                ));
+            }
+            parsed.push((action, shortcut));
+        }
+        Ok(parsed)
+    }
+
+    /// 押されたキーに割り当てられた操作
+    pub fn action_for(&self, shortcut: &Shortcut) -> Option<HotkeyAction> {
+        self.parse()
+            .ok()?
+            .into_iter()
+            .find(|(_, s)| s == shortcut)
+            .map(|(action, _)| action)
+    }
+}
+
+/// 登録済みのホットキーを外して割り当てを登録し直す
+///
+/// 他のアプリが使用中のキーは登録に失敗するため、エラーで返す
+pub fn register(app_handle: &AppHandle, hotkeys: &HotkeySettings) -> Result<(), String> {
+    let parsed = hotkeys.parse()?;
+    let manager = app_handle.global_shortcut();
+    manager.unregister_all().map_err(|e| e.to_string())?;
+    for (action, shortcut) in parsed {
+        manager
+            .register(shortcut)
+            .map_err(|e| format!("failed to register {:?} hotkey: {}", action, e))?;
+    }
+    Ok(())
+}
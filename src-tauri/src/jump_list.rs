@@ -0,0 +1,334 @@
+//! タスクバーのジャンプリスト（最近のセーブコードとショートカット）
+//!
+//! 項目をクリックすると引数付きでアプリが起動され、
+//! 単一インスタンスプラグイン経由で起動中のアプリに引数が渡される
+
+/// 記録時刻で指定したセーブコードをコピーする
+pub const ARG_COPY_CODE: &str = "--copy-code";
+/// 設定画面を開く
+pub const ARG_OPEN_SETTINGS: &str = "--open-settings";
+/// ログ監視の一時停止・再開を切り替える
+pub const ARG_TOGGLE_MONITORING: &str = "--toggle-monitoring";
+
+/// ジャンプリストに載せるセーブコード
+#[derive(Debug, Clone)]
+#[cfg_attr(not(windows), allow(dead_code))] // ジャンプリストはWindowsにしかない
+pub struct RecentCode {
+    pub title: String,
+    pub timestamp: String,
+}
+
+/// ジャンプリストを作り直す（失敗してもログに出すだけ）
+pub fn update(recent_codes: &[RecentCode], monitoring_paused: bool) {
+    if let Err(e) = imp::update(recent_codes, monitoring_paused) {
+        println!("[tsst] Failed to update jump list: {}", e);
+    }
+}
+
+#[cfg(windows)]
+#[allow(dead_code)] // vtableの未使用メソッドは位置合わせのためだけに置いている
+mod imp {
+    //! windows-sysにはCOMインターフェースの定義がないため、必要なメソッドだけvtableを定義して呼ぶ
+
+    use std::ffi::c_void;
+    use std::ptr;
+
+    use windows_sys::core::{GUID, HRESULT};
+    use windows_sys::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_APARTMENTTHREADED,
+    };
+
+    use super::{RecentCode, ARG_COPY_CODE, ARG_OPEN_SETTINGS, ARG_TOGGLE_MONITORING};
+
+    const CLSID_DESTINATION_LIST: GUID = GUID::from_u128(0x77f10cf0_3db5_4966_b520_b7c54fd35ed6);
+    const CLSID_ENUMERABLE_OBJECT_COLLECTION: GUID =
+        GUID::from_u128(0x2d3468c1_36a7_43b6_ac24_d3f02fd9607a);
+    const CLSID_SHELL_LINK: GUID = GUID::from_u128(0x00021401_0000_0000_c000_000000000046);
+    const IID_ICUSTOM_DESTINATION_LIST: GUID =
+        GUID::from_u128(0x6332debf_87b5_4670_90c0_5e57b408a49e);
+    const IID_IOBJECT_ARRAY: GUID = GUID::from_u128(0x92ca9dcd_5622_4bba_a805_5e9f541bd8c9);
+    const IID_IOBJECT_COLLECTION: GUID = GUID::from_u128(0x5632b1a4_e38a_400a_928a_d4cd63230295);
+    const IID_ISHELL_LINK_W: GUID = GUID::from_u128(0x000214f9_0000_0000_c000_000000000046);
+    const IID_IPROPERTY_STORE: GUID = GUID::from_u128(0x886d8eeb_8cf2_4446_8d02_cdba1dbdcf99);
+
+    /// PKEY_Title（ジャンプリストに表示される項目名）
+    const PKEY_TITLE: PropertyKey = PropertyKey {
+        fmtid: GUID::from_u128(0xf29f85e0_4ff9_1068_ab91_08002b27b3d9),
+        pid: 2,
+    };
+    const VT_LPWSTR: u16 = 31;
+    const RPC_E_CHANGED_MODE: HRESULT = 0x8001_0106_u32 as HRESULT;
+
+    type Method = unsafe extern "system" fn(*mut c_void) -> HRESULT;
+    type StrMethod = unsafe extern "system" fn(*mut c_void, *const u16) -> HRESULT;
+    type ObjMethod = unsafe extern "system" fn(*mut c_void, *mut c_void) -> HRESULT;
+
+    #[repr(C)]
+    struct IUnknownVtbl {
+        query_interface:
+            unsafe extern "system" fn(*mut c_void, *const GUID, *mut *mut c_void) -> HRESULT,
+        add_ref: unsafe extern "system" fn(*mut c_void) -> u32,
+        release: unsafe extern "system" fn(*mut c_void) -> u32,
+    }
+
+    #[repr(C)]
+    struct ICustomDestinationListVtbl {
+        unknown: IUnknownVtbl,
+        set_app_id: usize,
+        begin_list: unsafe extern "system" fn(
+            *mut c_void,
+            *mut u32,
+            *const GUID,
+            *mut *mut c_void,
+        ) -> HRESULT,
+        append_category: unsafe extern "system" fn(*mut c_void, *const u16, *mut c_void) -> HRESULT,
+        append_known_category: usize,
+        add_user_tasks: ObjMethod,
+        commit_list: Method,
+        get_removed_destinations: usize,
+        delete_list: usize,
+        abort_list: Method,
+    }
+
+    #[repr(C)]
+    struct IObjectCollectionVtbl {
+        unknown: IUnknownVtbl,
+        get_count: usize,
+        get_at: usize,
+        add_object: ObjMethod,
+    }
+
+    #[repr(C)]
+    struct IShellLinkWVtbl {
+        unknown: IUnknownVtbl,
+        get_path: usize,
+        get_id_list: usize,
+        set_id_list: usize,
+        get_description: usize,
+        set_description: StrMethod,
+        get_working_directory: usize,
+        set_working_directory: usize,
+        get_arguments: usize,
+        set_arguments: StrMethod,
+        get_hotkey: usize,
+        set_hotkey: usize,
+        get_show_cmd: usize,
+        set_show_cmd: usize,
+        get_icon_location: usize,
+        set_icon_location: unsafe extern "system" fn(*mut c_void, *const u16, i32) -> HRESULT,
+        set_relative_path: usize,
+        resolve: usize,
+        set_path: StrMethod,
+    }
+
+    #[repr(C)]
+    struct IPropertyStoreVtbl {
+        unknown: IUnknownVtbl,
+        get_count: usize,
+        get_at: usize,
+        get_value: usize,
+        set_value: unsafe extern "system" fn(
+            *mut c_void,
+            *const PropertyKey,
+            *const PropVariant,
+        ) -> HRESULT,
+        commit: Method,
+    }
+
+    #[repr(C)]
+    struct PropertyKey {
+        fmtid: GUID,
+        pid: u32,
+    }
+
+    /// 文字列（VT_LPWSTR）だけを扱うPROPVARIANT
+    #[repr(C)]
+    struct PropVariant {
+        vt: u16,
+        reserved: [u16; 3],
+        value: *const u16,
+        padding: usize,
+    }
+
+    /// 破棄時にReleaseするCOMポインタ
+    struct Com(*mut c_void);
+
+    impl Com {
+        unsafe fn vtbl<T>(&self) -> &T {
+            &**(self.0 as *const *const T)
+        }
+
+        unsafe fn query(&self, iid: &GUID) -> Result<Com, String> {
+            let mut out = ptr::null_mut();
+            check(
+                (self.vtbl::<IUnknownVtbl>().query_interface)(self.0, iid, &mut out),
+                "QueryInterface",
+            )?;
+            Ok(Com(out))
+        }
+    }
+
+    impl Drop for Com {
+        fn drop(&mut self) {
+            unsafe {
+                (self.vtbl::<IUnknownVtbl>().release)(self.0);
+            }
+        }
+    }
+
+    fn check(hr: HRESULT, what: &str) -> Result<(), String> {
+        if hr < 0 {
+            Err(format!("{} failed: 0x{:08X}", what, hr as u32))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    unsafe fn create(clsid: &GUID, iid: &GUID) -> Result<Com, String> {
+        let mut out = ptr::null_mut();
+        check(
+            CoCreateInstance(
+                clsid,
+                ptr::null_mut(),
+                CLSCTX_INPROC_SERVER as _,
+                iid,
+                &mut out,
+            ),
+            "CoCreateInstance",
+        )?;
+        Ok(Com(out))
+    }
+
+    /// 自分自身を引数付きで起動するショートカットを作る
+    unsafe fn shell_link(exe: &[u16], args: &str, title: &str) -> Result<Com, String> {
+        let link = create(&CLSID_SHELL_LINK, &IID_ISHELL_LINK_W)?;
+        let vtbl = link.vtbl::<IShellLinkWVtbl>();
+        let args = wide(args);
+        let title = wide(title);
+        check((vtbl.set_path)(link.0, exe.as_ptr()), "SetPath")?;
+        check((vtbl.set_arguments)(link.0, args.as_ptr()), "SetArguments")?;
+        check(
+            (vtbl.set_icon_location)(link.0, exe.as_ptr(), 0),
+            "SetIconLocation",
+        )?;
+        check(
+            (vtbl.set_description)(link.0, title.as_ptr()),
+            "SetDescription",
+        )?;
+
+        let store = link.query(&IID_IPROPERTY_STORE)?;
+        let store_vtbl = store.vtbl::<IPropertyStoreVtbl>();
+        let value = PropVariant {
+            vt: VT_LPWSTR,
+            reserved: [0; 3],
+            value: title.as_ptr(),
+            padding: 0,
+        };
+        check(
+            (store_vtbl.set_value)(store.0, &PKEY_TITLE, &value),
+            "SetValue",
+        )?;
+        check((store_vtbl.commit)(store.0), "Commit")?;
+        Ok(link)
+    }
+
+    unsafe fn collection(links: Vec<Com>) -> Result<Com, String> {
+        let collection = create(&CLSID_ENUMERABLE_OBJECT_COLLECTION, &IID_IOBJECT_COLLECTION)?;
+        for link in &links {
+            check(
+                (collection.vtbl::<IObjectCollectionVtbl>().add_object)(collection.0, link.0),
+                "AddObject",
+            )?;
+        }
+        Ok(collection)
+    }
+
+    pub fn update(recent_codes: &[RecentCode], monitoring_paused: bool) -> Result<(), String> {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        let exe = wide(&exe.to_string_lossy());
+
+        unsafe {
+            // 呼び出し元のスレッドが別のモードで初期化済みならそのまま使う
+            let hr = CoInitializeEx(ptr::null(), COINIT_APARTMENTTHREADED as _);
+            if hr != RPC_E_CHANGED_MODE {
+                check(hr, "CoInitializeEx")?;
+            }
+            let result = build(&exe, recent_codes, monitoring_paused);
+            if hr >= 0 {
+                CoUninitialize();
+            }
+            result
+        }
+    }
+
+    unsafe fn build(
+        exe: &[u16],
+        recent_codes: &[RecentCode],
+        monitoring_paused: bool,
+    ) -> Result<(), String> {
+        let list = create(&CLSID_DESTINATION_LIST, &IID_ICUSTOM_DESTINATION_LIST)?;
+        let vtbl = list.vtbl::<ICustomDestinationListVtbl>();
+
+        let mut min_slots = 0u32;
+        let mut removed = ptr::null_mut();
+        check(
+            (vtbl.begin_list)(list.0, &mut min_slots, &IID_IOBJECT_ARRAY, &mut removed),
+            "BeginList",
+        )?;
+        if !removed.is_null() {
+            drop(Com(removed));
+        }
+
+        let result = (|| -> Result<(), String> {
+            if !recent_codes.is_empty() {
+                let links = recent_codes
+                    .iter()
+                    .take(min_slots.max(1) as usize)
+                    .map(|code| {
+                        shell_link(
+                            exe,
+                            &format!("{} \"{}\"", ARG_COPY_CODE, code.timestamp),
+                            &code.title,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let category = wide("最近のセーブコード");
+                check(
+                    (vtbl.append_category)(list.0, category.as_ptr(), collection(links)?.0),
+                    "AppendCategory",
+                )?;
+            }
+
+            let monitoring_title = if monitoring_paused {
+                "監視を再開"
+            } else {
+                "監視を一時停止"
+            };
+            let tasks = collection(vec![
+                shell_link(exe, ARG_OPEN_SETTINGS, "設定を開く")?,
+                shell_link(exe, ARG_TOGGLE_MONITORING, monitoring_title)?,
+            ])?;
+            check((vtbl.add_user_tasks)(list.0, tasks.0), "AddUserTasks")?;
+            check((vtbl.commit_list)(list.0), "CommitList")
+        })();
+
+        if result.is_err() {
+            (vtbl.abort_list)(list.0);
+        }
+        result
+    }
+}
+
+#[cfg(not(windows))]
+mod imp {
+    use super::RecentCode;
+
+    pub fn update(_recent_codes: &[RecentCode], _monitoring_paused: bool) -> Result<(), String> {
+        Ok(())
+    }
+}
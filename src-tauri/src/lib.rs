@@ -1,24 +1,86 @@
+mod app_errors;
+mod audio;
+mod cloud_backup;
+mod event_server;
+mod export;
+mod goals;
+mod headless;
+mod heart_rate;
+mod hotkeys;
+mod jump_list;
+mod log_detection;
+mod metrics;
+mod milestones;
+mod notification_templates;
+mod osc_chatbox;
+mod overlay_log;
+mod quiet_hours;
+mod rest_api;
+mod rgb_sync;
+mod s3;
+mod secrets;
+mod shock;
+mod stats;
+mod summary_image;
 mod terror_data;
-
+mod terror_updates;
+mod tonsave_compat;
+mod tonsave_history;
+mod tray;
+mod vr_ipc;
+mod vr_notifications;
+mod webhooks;
+
+use app_errors::Severity;
 use arboard::Clipboard;
 use base64::Engine;
+use chrono::{Local, NaiveDateTime};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     env,
     fs::{self, File},
-    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
     path::{Path, PathBuf},
     process::{Child, Command, Stdio},
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tauri::path::BaseDirectory;
-use tauri::{AppHandle, Emitter, Manager, WindowEvent};
+use tauri::{AppHandle, Emitter, Listener, Manager, PhysicalPosition, PhysicalSize, WindowEvent};
 use tauri_plugin_autostart::MacosLauncher;
-
-use terror_data::{get_moon_terror_index, get_terror_data, get_terrors_data, round_type_to_english, TerrorData};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_opener::OpenerExt;
+use tauri_plugin_updater::UpdaterExt;
+
+use audio::{SoundEvent, SoundSettings};
+use cloud_backup::{CloudBackup, CloudBackupSettings};
+use event_server::{EventServer, EventServerSettings};
+use goals::{check_goals, Goal, GoalKind, GoalProgress};
+use heart_rate::{HeartRateMonitor, HeartRateSettings};
+use hotkeys::{HotkeyAction, HotkeySettings};
+use log_detection::{LogDetectionPatterns, LogDetectionSettings};
+use milestones::{check_milestones, Milestone};
+use notification_templates::{NotificationTemplates, TemplateValues};
+use osc_chatbox::{OscChatbox, OscChatboxSettings};
+use overlay_log::RotatingLog;
+use quiet_hours::QuietHoursSettings;
+use rest_api::{ApiRequest, RestApi, RestApiSettings};
+use rgb_sync::{OpenRgbSettings, Rgb, RgbSync};
+use shock::{ShockSettings, Shocker};
+use stats::{
+    best_streak, combo_stats, compare_periods as compare_period_stats, current_streak,
+    death_causes, duration_between, in_range, predict_next_round, query_rounds,
+    refresh_duration_stats, survival_rate, ComboStats, DateRange, DeathCause, DurationStats,
+    EconomyEvent, EconomyStats, PeriodComparison, StatsFilter, StatsQueryResult,
+};
+use terror_data::{get_moon_terror_index, round_type_to_english, TerrorData};
+use terror_updates::{get_terror_data, get_terrors_data, TerrorDataUpdateSettings};
+use tray::TrayStatus;
+use vr_ipc::{InputAction, OverlayEvent, VrIpc};
+use vr_notifications::VrNotificationSettings;
+use webhooks::{EventValues, Webhook, WebhookEvent};
 
 const WORLD_ID: &str = "wrld_a61cdabe-1218-4287-9ffc-2a4d1414e5bd";
 const MAX_HISTORY: usize = 10;
@@ -52,16 +114,504 @@ pub enum VrOverlayPosition {
     RightHand,
     LeftHand,
     Above,
+    /// 視界に固定（HUD）
+    Head,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// プリセット位置からの微調整
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct VrOverlayTransform {
+    /// 位置オフセット（メートル、取り付け先のローカル座標）
+    offset_x: f32,
+    offset_y: f32,
+    offset_z: f32,
+    /// パネル面内の回転（度）
+    rotation: f32,
+    /// 取り付け先から離す距離の増減（メートル）
+    distance: f32,
+}
+
+impl VrOverlayTransform {
+    /// 数値でない値は0に戻し、受け付ける範囲に収める
+    fn normalize(&mut self) {
+        for (value, limit) in [
+            (&mut self.offset_x, VR_OVERLAY_OFFSET_LIMIT),
+            (&mut self.offset_y, VR_OVERLAY_OFFSET_LIMIT),
+            (&mut self.offset_z, VR_OVERLAY_OFFSET_LIMIT),
+            (&mut self.distance, VR_OVERLAY_OFFSET_LIMIT),
+            (&mut self.rotation, 180.0),
+        ] {
+            *value = if value.is_finite() {
+                value.clamp(-limit, limit)
+            } else {
+                0.0
+            };
+        }
+    }
+}
+
+/// テラーパネルとは別に配置できるパネル
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VrPanel {
+    Stats,
+    Code,
+}
+
+/// パネルごとの配置設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VrPanelSettings {
+    /// trueなら独立したパネルとして表示（falseならテラーパネル内に表示）
+    separate: bool,
+    position: VrOverlayPosition,
+    /// Head位置での上下角度（度）
+    pitch: f32,
+    transform: VrOverlayTransform,
+}
+
+impl Default for VrPanelSettings {
+    fn default() -> Self {
+        Self {
+            separate: false,
+            position: VrOverlayPosition::Above,
+            pitch: default_vr_overlay_head_pitch(),
+            transform: VrOverlayTransform::default(),
+        }
+    }
+}
+
+/// 統計・セーブコードパネルの配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct VrPanelLayouts {
+    stats: VrPanelSettings,
+    code: VrPanelSettings,
+}
+
+impl VrPanelLayouts {
+    fn get(&self, panel: VrPanel) -> &VrPanelSettings {
+        match panel {
+            VrPanel::Stats => &self.stats,
+            VrPanel::Code => &self.code,
+        }
+    }
+
+    fn get_mut(&mut self, panel: VrPanel) -> &mut VrPanelSettings {
+        match panel {
+            VrPanel::Stats => &mut self.stats,
+            VrPanel::Code => &mut self.code,
+        }
+    }
+}
+
+/// 振動パターン
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HapticPattern {
+    #[default]
+    Single,
+    Double,
+    Long,
+}
+
+/// 敵の出現時にコントローラーを振動させる設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VrHapticSettings {
+    enabled: bool,
+    /// 強さ（0.0〜1.0）
+    strength: f32,
+    pattern: HapticPattern,
+}
+
+impl Default for VrHapticSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: 0.8,
+            pattern: HapticPattern::default(),
+        }
+    }
+}
+
+/// イベントごとのデスクトップ通知の有効/無効
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// 新しいセーブコードを記録したとき
+    code_captured: bool,
+    milestones: bool,
+    session_ended: bool,
+    /// ウォッチリストのテラーが出現したとき
+    watchlist: bool,
+    /// 新しいセーブコードを記録したらタスクバーを点滅させる（メインウィンドウが前面にないとき）
+    taskbar_flash: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            code_captured: true,
+            milestones: true,
+            session_ended: true,
+            watchlist: true,
+            taskbar_flash: false,
+        }
+    }
+}
+
+/// アカウントごとに全体設定を上書きする項目（Noneなら全体設定を使う）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct AccountOverrides {
+    auto_copy: Option<bool>,
+    vr_overlay_enabled: Option<bool>,
+    notifications: Option<NotificationSettings>,
+}
+
+/// デスクトップオーバーレイ（非VRプレイヤー向けのテラー表示ウィンドウ）の設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DesktopOverlaySettings {
+    enabled: bool,
+    /// ウィンドウ位置（物理ピクセル、未設定なら中央）
+    x: Option<i32>,
+    y: Option<i32>,
+    width: u32,
+    height: u32,
+    /// クリックをゲームに透過する（オフにすると移動・サイズ変更できる）
+    click_through: bool,
+}
+
+impl Default for DesktopOverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            x: None,
+            y: None,
+            width: 320,
+            height: 420,
+            click_through: true,
+        }
+    }
+}
+
+/// 統計ウィンドウ（2枚目のモニターに置く最前面の統計表示）の設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StatsWindowSettings {
+    /// ウィンドウ位置（物理ピクセル、未設定なら中央）
+    x: Option<i32>,
+    y: Option<i32>,
+    width: u32,
+    height: u32,
+    always_on_top: bool,
+}
+
+impl Default for StatsWindowSettings {
+    fn default() -> Self {
+        Self {
+            x: None,
+            y: None,
+            width: 420,
+            height: 560,
+            always_on_top: true,
+        }
+    }
+}
+
+/// アプリ画面の配色
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UiTheme {
+    #[default]
+    Dark,
+    Light,
+    /// OSの設定に合わせる
+    System,
+}
+
+/// アプリ画面の見た目（設定のエクスポートにも含める）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UiAppearance {
+    theme: UiTheme,
+    /// 強調色（`#RRGGBB`）
+    accent_color: String,
+    /// 基本の文字サイズ（px）
+    font_size: u32,
+}
+
+impl Default for UiAppearance {
+    fn default() -> Self {
+        Self {
+            theme: UiTheme::default(),
+            accent_color: "#0078d4".to_string(),
+            font_size: 16,
+        }
+    }
+}
+
+/// 文字サイズとして受け付ける範囲（px）
+const UI_FONT_SIZE_RANGE: (u32, u32) = (10, 24);
+
+/// コンパクト表示（最新コードと現在のテラーだけの小さな最前面ウィンドウ）の設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CompactModeSettings {
+    enabled: bool,
+    /// ウィンドウ位置（物理ピクセル、未設定なら右上）
+    x: Option<i32>,
+    y: Option<i32>,
+    width: u32,
+    height: u32,
+}
+
+impl Default for CompactModeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            x: None,
+            y: None,
+            width: 320,
+            height: 240,
+        }
+    }
+}
+
+/// オーバーレイの配色と文字の大きさ
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VrOverlayTheme {
+    /// 背景色（`#RRGGBB`）
+    background_color: String,
+    /// 枠線・見出しの色（`#RRGGBB`）
+    accent_color: String,
+    /// 文字の大きさの倍率（1.0が既定）
+    font_scale: f32,
+}
+
+impl Default for VrOverlayTheme {
+    fn default() -> Self {
+        Self {
+            background_color: "#0F0F14".to_string(),
+            accent_color: "#008CFF".to_string(),
+            font_scale: 1.0,
+        }
+    }
+}
+
+/// `#RRGGBB`形式の色かどうか
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// アップデートを自動で確認するタイミング
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoUpdateCheck {
+    #[default]
+    OnStart,
+    Daily,
+    Never,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct AppSettings {
     log_dir: Option<String>,
     auto_switch_tab: bool,
+    /// 新しいセーブコードを記録したらメインウィンドウを出して履歴タブを開く
+    #[serde(default)]
+    show_window_on_code: bool,
+    /// ToNワールドに入ったら最新のセーブコードをクリップボードにコピーする
+    #[serde(default = "default_auto_copy")]
+    auto_copy: bool,
+    /// 自動でコピーせず、通知やオーバーレイで確認してからコピーする
+    #[serde(default)]
+    confirm_copy: bool,
+    /// 起動時にメインウィンドウを出さずトレイに格納する
+    #[serde(default = "default_start_minimized")]
+    start_minimized: bool,
+    /// VRChatが終了したとき、ウィンドウを開いていなければアプリも終了する
+    #[serde(default)]
+    exit_with_vrchat: bool,
+    #[serde(default)]
+    auto_update_check: AutoUpdateCheck,
     vr_overlay_enabled: bool,
     vr_overlay_position: VrOverlayPosition,
+    #[serde(default)]
+    vr_overlay_transform: VrOverlayTransform,
+    /// パネルの大きさの倍率（1.0が既定サイズ）
+    #[serde(default = "default_vr_overlay_scale")]
+    vr_overlay_scale: f32,
+    /// Head位置での上下角度（度、負の値で視線より下）
+    #[serde(default = "default_vr_overlay_head_pitch")]
+    vr_overlay_head_pitch: f32,
+    /// 手首を見たときだけ表示する（腕時計モード）
+    #[serde(default)]
+    vr_overlay_gaze_activation: bool,
+    /// 連続生存数・生存率などの統計パネルを表示する
+    #[serde(default)]
+    vr_overlay_stats_panel: bool,
+    #[serde(default)]
+    vr_overlay_panels: VrPanelLayouts,
+    /// 新しいセーブコードを記録したらオーバーレイに表示する
+    #[serde(default)]
+    vr_overlay_show_code: bool,
+    /// ラウンドの経過時間をオーバーレイに表示する
+    #[serde(default)]
+    vr_overlay_round_timer: bool,
+    #[serde(default)]
+    vr_haptics: VrHapticSettings,
+    #[serde(default)]
+    vr_overlay_theme: VrOverlayTheme,
+    /// オーバーレイに表示するテラーのアイコン画像を置いたフォルダ（未設定ならアイコンなし）
+    #[serde(default)]
+    vr_overlay_icon_dir: Option<String>,
+    /// 出現したら強調表示・通知するテラーID
+    #[serde(default)]
+    terror_watchlist: Vec<u32>,
+    /// オーバーレイでテラー名・能力を伏せる（色は残す）
+    #[serde(default)]
+    spoiler_mode: bool,
+    #[serde(default)]
+    notifications: NotificationSettings,
+    #[serde(default)]
+    notification_templates: NotificationTemplates,
+    #[serde(default)]
+    sounds: SoundSettings,
+    #[serde(default)]
+    quiet_hours: QuietHoursSettings,
+    #[serde(default)]
+    desktop_overlay: DesktopOverlaySettings,
+    #[serde(default)]
+    compact_mode: CompactModeSettings,
+    #[serde(default)]
+    ui_appearance: UiAppearance,
+    #[serde(default)]
+    stats_window: StatsWindowSettings,
+    #[serde(default)]
+    hotkeys: HotkeySettings,
+    /// 外部ツール向けのWebSocketイベントサーバー
+    #[serde(default)]
+    event_server: EventServerSettings,
+    /// 連携ツール向けのローカルREST API
+    #[serde(default)]
+    rest_api: RestApiSettings,
+    /// イベントごとに送るWebhook
+    #[serde(default)]
+    webhooks: Vec<Webhook>,
+    /// VRChatのチャットボックスへのテラー名の送信
+    #[serde(default)]
+    osc_chatbox: OscChatboxSettings,
+    /// 死亡時のOpenShock / PiShock連携
+    #[serde(default)]
+    shock: ShockSettings,
+    /// Pulsoid / HypeRateからの心拍数の記録
+    #[serde(default)]
+    heart_rate: HeartRateSettings,
+    /// OpenRGBでのライティング連携
+    #[serde(default)]
+    open_rgb: OpenRgbSettings,
+    /// XSOverlay / OVR Toolkitへの通知
+    #[serde(default)]
+    vr_notifications: VrNotificationSettings,
+    /// data.jsonのクラウドバックアップ
+    #[serde(default)]
+    cloud_backup: CloudBackupSettings,
+    /// テラーデータの配信による更新
+    #[serde(default)]
+    terror_data_updates: TerrorDataUpdateSettings,
+    /// 最後の生存者・テラーのスタン・ポイント獲得・アイテム購入を検出するログのパターン
+    #[serde(default)]
+    log_detection: LogDetectionSettings,
+    /// 自前でビルドしたVRオーバーレイの実行ファイル（未設定ならバンドル版を使う）
+    #[serde(default)]
+    overlay_binary_path: Option<String>,
+    /// 敵の表示から自動で隠すまでの秒数（Noneなら隠さない）
+    #[serde(default)]
+    vr_overlay_auto_hide_secs: Option<u32>,
+    /// VRChatアカウント（表示名）ごとの上書き設定
+    #[serde(default)]
+    account_overrides: HashMap<String, AccountOverrides>,
+}
+
+fn default_auto_copy() -> bool {
+    true
+}
+
+fn default_start_minimized() -> bool {
+    true
+}
+
+fn default_vr_overlay_scale() -> f32 {
+    1.0
+}
+
+fn default_vr_overlay_head_pitch() -> f32 {
+    -20.0
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            log_dir: None,
+            auto_switch_tab: false,
+            show_window_on_code: false,
+            auto_copy: default_auto_copy(),
+            confirm_copy: false,
+            start_minimized: default_start_minimized(),
+            exit_with_vrchat: false,
+            auto_update_check: AutoUpdateCheck::default(),
+            vr_overlay_enabled: false,
+            vr_overlay_position: VrOverlayPosition::default(),
+            vr_overlay_transform: VrOverlayTransform::default(),
+            vr_overlay_scale: default_vr_overlay_scale(),
+            vr_overlay_head_pitch: default_vr_overlay_head_pitch(),
+            vr_overlay_gaze_activation: false,
+            vr_overlay_stats_panel: false,
+            vr_overlay_panels: VrPanelLayouts::default(),
+            vr_overlay_show_code: false,
+            vr_overlay_round_timer: false,
+            vr_haptics: VrHapticSettings::default(),
+            vr_overlay_theme: VrOverlayTheme::default(),
+            vr_overlay_icon_dir: None,
+            terror_watchlist: Vec::new(),
+            spoiler_mode: false,
+            notifications: NotificationSettings::default(),
+            notification_templates: NotificationTemplates::default(),
+            sounds: SoundSettings::default(),
+            quiet_hours: QuietHoursSettings::default(),
+            desktop_overlay: DesktopOverlaySettings::default(),
+            compact_mode: CompactModeSettings::default(),
+            ui_appearance: UiAppearance::default(),
+            stats_window: StatsWindowSettings::default(),
+            hotkeys: HotkeySettings::default(),
+            event_server: EventServerSettings::default(),
+            rest_api: RestApiSettings::default(),
+            webhooks: Vec::new(),
+            osc_chatbox: OscChatboxSettings::default(),
+            shock: ShockSettings::default(),
+            heart_rate: HeartRateSettings::default(),
+            open_rgb: OpenRgbSettings::default(),
+            vr_notifications: VrNotificationSettings::default(),
+            cloud_backup: CloudBackupSettings::default(),
+            terror_data_updates: TerrorDataUpdateSettings::default(),
+            log_detection: LogDetectionSettings::default(),
+            overlay_binary_path: None,
+            vr_overlay_auto_hide_secs: None,
+            account_overrides: HashMap::new(),
+        }
+    }
 }
 
+/// オーバーレイの倍率として受け付ける範囲
+const VR_OVERLAY_SCALE_RANGE: (f32, f32) = (0.25, 4.0);
+/// 位置オフセット・距離の増減として受け付ける大きさ（メートル）
+const VR_OVERLAY_OFFSET_LIMIT: f32 = 1.0;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CodeEntry {
     code: String,
@@ -80,6 +630,17 @@ struct CodeEntry {
 struct RoundTypeStats {
     survivals: u32,
     deaths: u32,
+    /// 最後の生存者になった回数
+    #[serde(default)]
+    last_survivals: u32,
+    #[serde(flatten)]
+    durations: DurationStats,
+    /// テラーをスタンした回数の合計
+    #[serde(default)]
+    stuns: u32,
+    /// 1ラウンドあたりの平均スタン回数
+    #[serde(default)]
+    avg_stuns: f64,
 }
 
 /// ラウンド統計データ
@@ -88,7 +649,84 @@ struct RoundStats {
     total_rounds: u32,
     survivals: u32,
     deaths: u32,
+    /// 最後の生存者になった回数
+    #[serde(default)]
+    last_survivals: u32,
+    #[serde(flatten)]
+    durations: DurationStats,
+    /// テラーをスタンした回数の合計
+    #[serde(default)]
+    stuns: u32,
+    /// ポイント獲得・アイテム購入の合計
+    #[serde(default)]
+    economy: EconomyStats,
     round_types: HashMap<String, RoundTypeStats>,
+    /// 集計開始日時（最初のラウンド終了時またはリセット時に設定）
+    #[serde(default)]
+    since: Option<String>,
+}
+
+/// リセット時に保存された統計のアーカイブ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatsArchive {
+    stats: RoundStats,
+    /// 集計期間の開始日時
+    started_at: Option<String>,
+    /// 集計期間の終了日時（アーカイブした日時）
+    ended_at: String,
+}
+
+/// ラウンド記録（1ラウンドごとの結果）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RoundRecord {
+    id: u64,
+    started_at: Option<String>,
+    ended_at: String,
+    map_name: Option<String>,
+    round_type: String,
+    killers: Vec<u32>,
+    is_dead: bool,
+    /// ラウンドをプレイしたインスタンスセッション
+    #[serde(default)]
+    session_id: Option<u64>,
+    /// ラウンド開始時のインスタンス人数
+    #[serde(default)]
+    player_count: Option<u32>,
+    /// 最後の生存者として生き残ったか
+    #[serde(default)]
+    last_survivor: bool,
+    /// テラーをスタンした回数
+    #[serde(default)]
+    stuns: u32,
+    save_code: Option<String>,
+    /// ラウンドの所要時間（秒）
+    #[serde(default)]
+    duration_secs: Option<u32>,
+    /// プレイしていたVRChatアカウント（表示名）
+    #[serde(default)]
+    account: Option<String>,
+    /// 手動で無効化されたラウンド（統計から除外）
+    #[serde(default)]
+    excluded: bool,
+    /// ラウンド中の最大心拍数
+    #[serde(default)]
+    max_bpm: Option<u32>,
+    /// ラウンド中の平均心拍数
+    #[serde(default)]
+    avg_bpm: Option<u32>,
+}
+
+/// インスタンスセッション記録（ToNワールドに参加してから離れるまで）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionRecord {
+    id: u64,
+    started_at: String,
+    ended_at: Option<String>,
+    /// インスタンスID（"12345~private(usr_...)" など）
+    instance_id: Option<String>,
+    /// セッション中に同じインスタンスにいたプレイヤー（表示名）
+    #[serde(default)]
+    players: Vec<String>,
 }
 
 /// 内部データファイル（コード履歴と統計を永続化）
@@ -96,6 +734,24 @@ struct RoundStats {
 struct AppData {
     history: Vec<CodeEntry>,
     stats: RoundStats,
+    #[serde(default)]
+    stats_archives: Vec<StatsArchive>,
+    #[serde(default)]
+    rounds: Vec<RoundRecord>,
+    /// 達成済みのマイルストーン
+    #[serde(default)]
+    milestones: Vec<Milestone>,
+    /// ユーザー定義の目標
+    #[serde(default)]
+    goals: Vec<Goal>,
+    #[serde(default)]
+    sessions: Vec<SessionRecord>,
+    /// ToNSaveManagerから取り込んだセーブコード（`history`の件数上限とは別に残す）
+    #[serde(default)]
+    imported_history: Vec<CodeEntry>,
+    /// ポイント獲得・アイテム購入の記録
+    #[serde(default)]
+    economy_events: Vec<EconomyEvent>,
 }
 
 /// リアルタイムラウンド情報
@@ -107,6 +763,21 @@ struct CurrentRoundInfo {
     killers: Vec<u32>,
     is_dead: bool,
     save_code: Option<String>,
+    /// ラウンド開始日時（ログのタイムスタンプ）
+    #[serde(default)]
+    started_at: Option<String>,
+    /// 最後の生存者になったか
+    #[serde(default)]
+    is_last_survivor: bool,
+    /// ラウンド開始時のインスタンス人数
+    #[serde(default)]
+    player_count: Option<u32>,
+    /// このラウンドでテラーをスタンした回数
+    #[serde(default)]
+    stuns: u32,
+    /// ラウンド中に受信した心拍数
+    #[serde(skip)]
+    heart_rates: Vec<u32>,
 }
 
 /// テラーデータ（フロントエンドにシリアライズ用）
@@ -143,12 +814,31 @@ impl From<TerrorData> for TerrorDataResponse {
 #[derive(Debug, Clone, Serialize)]
 struct AppSnapshot {
     settings: AppSettings,
-    history: Vec<CodeEntry>,
     latest_code: Option<CodeEntry>,
     stats: RoundStats,
     survivals: u32,
     current_round: CurrentRoundInfo,
     instance_round_counts: HashMap<String, u32>,
+    /// インスタンス内のラウンド傾向から予測した次のラウンド（"Classic" / "Special"）
+    predicted_next_round: Option<String>,
+    monitoring_paused: bool,
+    /// ログから検出したVRChatアカウント（表示名）
+    current_account: Option<String>,
+    /// 現在のアカウントの上書きを反映した設定
+    active_settings: AppSettings,
+}
+
+/// REST APIで返す状態（設定はトークンや外部サービスの鍵を含むため返さない）
+#[derive(Debug, Clone, Serialize)]
+struct ApiSnapshot {
+    latest_code: Option<CodeEntry>,
+    stats: RoundStats,
+    survivals: u32,
+    current_round: CurrentRoundInfo,
+    instance_round_counts: HashMap<String, u32>,
+    predicted_next_round: Option<String>,
+    monitoring_paused: bool,
+    current_account: Option<String>,
 }
 
 /// ランタイム状態（メモリ上のみ）
@@ -161,24 +851,141 @@ struct AppState {
     last_log_path: Option<PathBuf>,
     last_offset: u64,
     last_copied_code: Option<String>,
+    /// コピーの確認を求めるセーブコード（ログ監視スレッドが確認を出した後にクリア）
+    pending_copy_prompt: Option<CodeEntry>,
     /// インスタンス内ラウンドタイプ別カウンター（メモリのみ、永続化しない）
     instance_round_counts: HashMap<String, u32>,
+    /// ログから検出したVRChatアカウント（表示名）
+    current_account: Option<String>,
+    /// 現在のインスタンスに参加した日時（セッション開始）
+    session_started_at: Option<String>,
+    /// 現在のToNインスタンスセッション
+    current_session_id: Option<u64>,
+    /// 現在のインスタンスにいるプレイヤー（表示名）
+    instance_players: Vec<String>,
+    /// インスタンス内で観測したラウンドタイプの順序（メモリのみ）
+    instance_round_sequence: Vec<String>,
+    /// 次のラウンドの予測（"Classic" / "Special"）
+    predicted_next_round: Option<String>,
+    /// インスタンス内のポイント獲得・アイテム購入（メモリのみ）
+    session_economy: EconomyStats,
+    /// 未通知のマイルストーン（ログ監視スレッドがイベント発行後にクリア）
+    pending_milestones: Vec<Milestone>,
+    /// 未通知の達成済み目標（ログ監視スレッドがイベント発行後にクリア）
+    pending_goals: Vec<Goal>,
+    /// 未通知のセッションまとめ（ログ監視スレッドがイベント発行後にクリア）
+    pending_session_summaries: Vec<SessionSummary>,
+    /// コンパクト表示に切り替える前のメインウィンドウの位置とサイズ（メモリのみ）
+    normal_window_bounds: Option<(PhysicalPosition<i32>, PhysicalSize<u32>)>,
+    /// ウィンドウの位置・サイズの変更回数（保存を遅らせている間に変わったかを見る）
+    window_bounds_changes: u64,
+    /// 最後にセーブコードを記録した時刻（トレイのバッジ用）
+    last_code_captured_at: Option<Instant>,
+    /// 現在トレイに表示している状態
+    tray_status: Option<TrayStatus>,
+    /// 現在トレイに表示しているツールチップ
+    tray_tooltip: Option<String>,
+    /// ログ監視を一時停止中か（メモリのみ）
+    monitoring_paused: bool,
+    /// 検出対象のログ行を`raw_log_line`イベントで配信するか（メモリのみ）
+    raw_log_stream: bool,
 }
 
 /// VRオーバーレイプロセス状態
 struct VrOverlayState {
     process: Option<Child>,
-    stdin_writer: Option<std::process::ChildStdin>,
+    /// オーバーレイとのIPC接続
+    ipc: Option<VrIpc>,
     /// SteamVR待機中フラグ（設定は有効だがSteamVRが未起動）
     waiting_for_steamvr: bool,
+    /// 起動ごとに増える世代番号（古いウォッチドッグの停止判定用）
+    generation: u64,
+    /// 連続した自動再起動の試行回数
+    restart_attempts: u32,
+    /// 最後にプロセスを起動した時刻
+    last_started: Option<Instant>,
+    /// 最後に送信したコマンドの種類
+    last_command: Option<String>,
+    /// 最後に発生したエラー
+    last_error: Option<String>,
 }
 
 impl Default for VrOverlayState {
     fn default() -> Self {
         Self {
             process: None,
-            stdin_writer: None,
+            ipc: None,
             waiting_for_steamvr: false,
+            generation: 0,
+            restart_attempts: 0,
+            last_started: None,
+            last_command: None,
+            last_error: None,
+        }
+    }
+}
+
+impl AppState {
+    fn snapshot(&self) -> AppSnapshot {
+        AppSnapshot {
+            settings: self.settings.clone(),
+            latest_code: self.data.history.last().cloned(),
+            stats: self.data.stats.clone(),
+            survivals: self.data.stats.survivals,
+            current_round: self.current_round.clone(),
+            instance_round_counts: self.instance_round_counts.clone(),
+            predicted_next_round: self.predicted_next_round.clone(),
+            monitoring_paused: self.monitoring_paused,
+            current_account: self.current_account.clone(),
+            active_settings: self.active_settings(),
+        }
+    }
+
+    fn api_snapshot(&self) -> ApiSnapshot {
+        ApiSnapshot {
+            latest_code: self.data.history.last().cloned(),
+            stats: self.data.stats.clone(),
+            survivals: self.data.stats.survivals,
+            current_round: self.current_round.clone(),
+            instance_round_counts: self.instance_round_counts.clone(),
+            predicted_next_round: self.predicted_next_round.clone(),
+            monitoring_paused: self.monitoring_paused,
+            current_account: self.current_account.clone(),
+        }
+    }
+
+    /// 現在のアカウントの上書き設定
+    fn account_overrides(&self) -> Option<&AccountOverrides> {
+        self.current_account
+            .as_ref()
+            .and_then(|account| self.settings.account_overrides.get(account))
+    }
+
+    fn auto_copy(&self) -> bool {
+        self.account_overrides()
+            .and_then(|o| o.auto_copy)
+            .unwrap_or(self.settings.auto_copy)
+    }
+
+    fn vr_overlay_enabled(&self) -> bool {
+        self.account_overrides()
+            .and_then(|o| o.vr_overlay_enabled)
+            .unwrap_or(self.settings.vr_overlay_enabled)
+    }
+
+    fn notifications(&self) -> NotificationSettings {
+        self.account_overrides()
+            .and_then(|o| o.notifications.clone())
+            .unwrap_or_else(|| self.settings.notifications.clone())
+    }
+
+    /// 現在のアカウントの上書きを全体設定に重ねた設定
+    fn active_settings(&self) -> AppSettings {
+        AppSettings {
+            auto_copy: self.auto_copy(),
+            vr_overlay_enabled: self.vr_overlay_enabled(),
+            notifications: self.notifications(),
+            ..self.settings.clone()
         }
     }
 }
@@ -222,890 +1029,5273 @@ fn persist_settings(app_handle: &AppHandle, settings: &AppSettings) -> Result<()
     Ok(())
 }
 
-// ============ データファイル読み書き ============
+// ============ 設定のエクスポート・プロファイル ============
 
-fn load_data(app_handle: &AppHandle) -> Option<AppData> {
-    let path = data_path(app_handle)?;
-    let content = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&content).ok()
+/// プロファイルの保存先（settings.jsonと同じフォルダのprofiles）
+fn profiles_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let path = settings_path(app_handle).ok_or("settings path not found")?;
+    let parent = path.parent().ok_or("settings path not found")?;
+    Ok(parent.join("profiles"))
 }
 
-fn persist_data(app_handle: &AppHandle, data: &AppData) -> Result<(), String> {
-    let path = data_path(app_handle).ok_or("data path not found")?;
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+fn profile_path(app_handle: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty()
+        || name.starts_with('.')
+        || name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|'])
+    {
+        return Err(format!("invalid profile name: {}", name));
     }
-    let payload = serde_json::to_string_pretty(data).map_err(|err| err.to_string())?;
-    fs::write(path, payload).map_err(|err| err.to_string())?;
-    Ok(())
+    Ok(profiles_dir(app_handle)?.join(format!("{}.json", name)))
 }
 
-// ============ Tauri コマンド ============
+fn read_settings_file(path: &Path) -> Result<AppSettings, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
 
-#[tauri::command]
-fn get_state(state: tauri::State<SharedState>) -> AppSnapshot {
-    let state = state.lock().expect("state lock");
-    AppSnapshot {
-        settings: state.settings.clone(),
-        history: state.data.history.clone(),
-        latest_code: state.data.history.last().cloned(),
-        stats: state.data.stats.clone(),
-        survivals: state.data.stats.survivals,
-        current_round: state.current_round.clone(),
-        instance_round_counts: state.instance_round_counts.clone(),
+fn write_settings_file(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
     }
+    let payload = serde_json::to_string_pretty(settings).map_err(|err| err.to_string())?;
+    fs::write(path, payload).map_err(|err| err.to_string())
 }
 
-#[tauri::command]
-fn set_log_dir(
+/// 読み込んだ設定に切り替え、オーバーレイやホットキーにも反映する
+fn apply_settings(
+    app_handle: &AppHandle,
+    mut settings: AppSettings,
+) -> Result<AppSettings, String> {
+    validate_settings(&mut settings)?;
+    // 検証に通った設定だけを資格情報ストアに移す
+    move_secrets_to_keyring(&mut settings)?;
+    let state = app_handle.state::<SharedState>();
+    let (vr_was_enabled, active_settings, snapshot) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        // ウィンドウの表示状態はプロファイルでは切り替えない
+        settings.compact_mode = state.settings.compact_mode.clone();
+        let vr_was_enabled = state.vr_overlay_enabled();
+        state.settings = settings.clone();
+        (vr_was_enabled, state.active_settings(), state.snapshot())
+    };
+    persist_settings(app_handle, &settings)?;
+
+    if let Err(e) = hotkeys::register(app_handle, &settings.hotkeys) {
+        app_errors::report(
+            app_handle,
+            Severity::Warning,
+            format!("Failed to register hotkeys: {}", e),
+        );
+    }
+    if let Err(e) = configure_event_server(app_handle, &settings.event_server) {
+        app_errors::report(
+            app_handle,
+            Severity::Warning,
+            format!("Failed to start event server: {}", e),
+        );
+    }
+    if let Err(e) = app_handle
+        .state::<RestApi>()
+        .configure(&settings.rest_api, rest_api_handler(app_handle))
+    {
+        app_errors::report(
+            app_handle,
+            Severity::Warning,
+            format!("Failed to start REST API: {}", e),
+        );
+    }
+    if let Err(e) = app_handle
+        .state::<HeartRateMonitor>()
+        .configure(&settings.heart_rate, heart_rate_handler(app_handle))
+    {
+        app_errors::report(
+            app_handle,
+            Severity::Warning,
+            format!("Failed to start heart rate monitor: {}", e),
+        );
+    }
+
+    if settings.desktop_overlay.enabled {
+        open_desktop_overlay(app_handle, &settings.desktop_overlay)?;
+    } else if let Some(window) = app_handle.get_webview_window(DESKTOP_OVERLAY_LABEL) {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+
+    switch_vr_overlay(app_handle, vr_was_enabled, &active_settings)?;
+
+    let _ = app_handle.emit("ui_appearance_changed", &settings.ui_appearance);
+    let _ = app_handle.emit("state_updated", &snapshot);
+    Ok(settings)
+}
+
+/// VRオーバーレイの有効・無効の変化を反映する（有効のままなら設定を送り直す）
+fn switch_vr_overlay(
+    app_handle: &AppHandle,
+    was_enabled: bool,
+    settings: &AppSettings,
+) -> Result<(), String> {
+    let vr_state = app_handle.state::<SharedVrState>();
+    match (was_enabled, settings.vr_overlay_enabled) {
+        (false, true) => start_vr_overlay(app_handle, vr_state.inner(), settings)?,
+        (true, true) => {
+            for command in initial_vr_commands(settings) {
+                send_vr_command(vr_state.inner(), &command)?;
+            }
+        }
+        (true, false) => {
+            {
+                let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+                state.waiting_for_steamvr = false;
+            }
+            stop_vr_overlay(vr_state.inner())?;
+        }
+        (false, false) => {}
+    }
+    Ok(())
+}
+
+/// アカウントが切り替わったとき、上書き設定によるVRオーバーレイの有効・無効を反映する
+fn apply_account_overrides(app_handle: &AppHandle, vr_was_enabled: bool) {
+    let state = app_handle.state::<SharedState>();
+    let Ok(settings) = state.lock().map(|s| s.active_settings()) else {
+        return;
+    };
+    if settings.vr_overlay_enabled == vr_was_enabled {
+        return;
+    }
+    if let Err(e) = switch_vr_overlay(app_handle, vr_was_enabled, &settings) {
+        app_errors::report(
+            app_handle,
+            Severity::Error,
+            format!("Failed to apply account overrides: {}", e),
+        );
+    }
+}
+
+/// アカウントごとの上書き設定を変更（Noneで削除）
+#[tauri::command]
+fn set_account_overrides(
     app_handle: AppHandle,
     state: tauri::State<SharedState>,
-    log_dir: Option<String>,
+    account: String,
+    overrides: Option<AccountOverrides>,
 ) -> Result<AppSettings, String> {
-    let updated_settings = {
+    let (updated_settings, vr_was_enabled, snapshot) = {
         let mut state = state.lock().map_err(|_| "state lock failed")?;
-        state.settings.log_dir = log_dir;
-        state.settings.clone()
+        let vr_was_enabled = state.vr_overlay_enabled();
+        match overrides.filter(|o| *o != AccountOverrides::default()) {
+            Some(overrides) => state.settings.account_overrides.insert(account, overrides),
+            None => state.settings.account_overrides.remove(&account),
+        };
+        (state.settings.clone(), vr_was_enabled, state.snapshot())
     };
     persist_settings(&app_handle, &updated_settings)?;
+    apply_account_overrides(&app_handle, vr_was_enabled);
+    let _ = app_handle.emit("state_updated", &snapshot);
     Ok(updated_settings)
 }
 
+/// ラウンド記録とログから検出したVRChatアカウント
+#[tauri::command]
+fn get_known_accounts(state: tauri::State<SharedState>) -> Result<Vec<String>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    let mut accounts: Vec<String> = state
+        .data
+        .rounds
+        .iter()
+        .filter_map(|r| r.account.clone())
+        .chain(state.current_account.clone())
+        .chain(state.settings.account_overrides.keys().cloned())
+        .collect();
+    accounts.sort();
+    accounts.dedup();
+    Ok(accounts)
+}
+
+/// 現在の設定をファイルに書き出す
+#[tauri::command]
+fn export_settings(state: tauri::State<SharedState>, path: String) -> Result<(), String> {
+    let settings = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.clone()
+    };
+    write_settings_file(Path::new(&path), &settings)
+}
+
+/// ファイルから設定を読み込んで切り替える
+#[tauri::command]
+fn import_settings(app_handle: AppHandle, path: String) -> Result<AppSettings, String> {
+    let settings = read_settings_file(Path::new(&path))?;
+    apply_settings(&app_handle, settings)
+}
+
+/// ToNSaveManagerのセーブ履歴ファイルを取り込む（記録済みのコードは取り込まない）
 #[tauri::command]
-fn set_auto_switch_tab(
+fn import_tonsavemanager(
     app_handle: AppHandle,
     state: tauri::State<SharedState>,
-    enabled: bool,
-) -> Result<AppSettings, String> {
-    let updated_settings = {
+    path: String,
+) -> Result<tonsave_history::ImportSummary, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let entries = tonsave_history::parse_entries(&content)?;
+
+    let (summary, data) = {
         let mut state = state.lock().map_err(|_| "state lock failed")?;
-        state.settings.auto_switch_tab = enabled;
-        state.settings.clone()
+        let data = &mut state.data;
+        let summary = tonsave_history::merge(&mut data.imported_history, &data.history, entries);
+        (summary, state.data.clone())
     };
-    persist_settings(&app_handle, &updated_settings)?;
-    Ok(updated_settings)
+    persist_data(&app_handle, &data)?;
+    println!(
+        "[tsst] ToNSaveManager import: {} imported, {} duplicates",
+        summary.imported, summary.duplicates
+    );
+    Ok(summary)
 }
 
-// ============ VR設定コマンド ============
+/// すべてのセーブコードをToNSaveManagerの形式で書き出す
+#[tauri::command]
+fn export_tonsavemanager(state: tauri::State<SharedState>, path: String) -> Result<usize, String> {
+    let entries = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        tonsave_history::all_codes(&state.data)
+    };
+    tonsave_history::write_file(Path::new(&path), &entries)
+}
+
+/// ToNSaveManagerから取り込んだセーブコード
+#[tauri::command]
+fn get_imported_history(state: tauri::State<SharedState>) -> Result<Vec<CodeEntry>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(state.data.imported_history.clone())
+}
 
+/// 一部の設定だけを変更する（`patch`に含めた項目だけを置き換え、オブジェクトは項目ごとに重ねる）
+///
+/// 値を検証してから、オーバーレイの起動・停止などは設定ファイルの読み込みと同じく一括で反映する
 #[tauri::command]
-fn set_vr_overlay_enabled(
+fn update_settings(
     app_handle: AppHandle,
     state: tauri::State<SharedState>,
-    vr_state: tauri::State<SharedVrState>,
-    enabled: bool,
+    patch: serde_json::Value,
 ) -> Result<AppSettings, String> {
-    let (updated_settings, current_round) = {
-        let mut state = state.lock().map_err(|_| "state lock failed")?;
-        state.settings.vr_overlay_enabled = enabled;
-        (state.settings.clone(), state.current_round.clone())
+    let serde_json::Value::Object(patch) = patch else {
+        return Err("settings patch must be an object".to_string());
     };
-    persist_settings(&app_handle, &updated_settings)?;
+    let current = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        serde_json::to_value(&state.settings).map_err(|e| e.to_string())?
+    };
+    let serde_json::Value::Object(mut merged) = current else {
+        return Err("settings are not an object".to_string());
+    };
+    for (key, value) in patch {
+        let Some(target) = merged.get_mut(&key) else {
+            return Err(format!("unknown setting: {}", key));
+        };
+        merge_settings_patch(target, value);
+    }
 
-    // VRオーバーレイの起動/停止
-    if enabled {
-        // SteamVRが起動しているかチェック
-        if is_steamvr_running() {
-            start_vr_overlay(&app_handle, vr_state.inner(), &updated_settings)?;
-            // 現在のラウンド情報があれば送信
-            if current_round.is_active && !current_round.killers.is_empty() {
-                let round_type = current_round.round_type.as_deref().unwrap_or("Classic");
-                let terror_infos: Vec<VrTerrorInfo> = get_terrors_data(&current_round.killers, round_type)
-                    .into_iter()
-                    .map(|d| d.into())
-                    .collect();
-                send_vr_command(
-                    vr_state.inner(),
-                    &VrCommand::UpdateTerrors {
-                        terrors: terror_infos,
-                        round_type: round_type.to_string(),
-                    },
-                )?;
+    let settings: AppSettings = serde_json::from_value(serde_json::Value::Object(merged))
+        .map_err(|e| format!("invalid settings: {}", e))?;
+    apply_settings(&app_handle, settings)
+}
+
+/// JSONの部分的な設定を重ねる（どちらもオブジェクトなら項目ごと、それ以外は置き換え）
+fn merge_settings_patch(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_settings_patch(target.entry(key).or_insert(serde_json::Value::Null), value);
             }
-        } else {
-            // SteamVRが起動していない場合は待機状態にする
-            let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
-            state.waiting_for_steamvr = true;
-            println!("[tsst] SteamVR not running, waiting for SteamVR to start...");
         }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// 設定に含まれていた秘密情報を資格情報ストアに移す（移したものがあれば`true`）
+fn move_secrets_to_keyring(settings: &mut AppSettings) -> Result<bool, String> {
+    let shock = settings.shock.store_secrets()?;
+    let heart_rate = settings.heart_rate.store_secrets()?;
+    Ok(shock || heart_rate)
+}
+
+/// 個別の設定コマンドと同じ検証・範囲の補正をまとめて行う
+fn validate_settings(settings: &mut AppSettings) -> Result<(), String> {
+    settings.log_dir = settings.log_dir.take().filter(|dir| !dir.trim().is_empty());
+    if let Some(dir) = &settings.log_dir {
+        if !Path::new(dir).is_absolute() {
+            return Err(format!("log dir must be an absolute path: {}", dir));
+        }
+    }
+    settings.overlay_binary_path = settings
+        .overlay_binary_path
+        .take()
+        .filter(|path| !path.trim().is_empty());
+    if let Some(path) = &settings.overlay_binary_path {
+        let binary = Path::new(path);
+        if !binary.is_file() {
+            return Err(format!("overlay binary not found: {}", path));
+        }
+        let is_exe = binary
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+        if cfg!(target_os = "windows") && !is_exe {
+            return Err("overlay binary must be an .exe file".to_string());
+        }
+    }
+    settings.vr_overlay_icon_dir = settings
+        .vr_overlay_icon_dir
+        .take()
+        .filter(|dir| !dir.trim().is_empty());
+    if let Some(dir) = &settings.vr_overlay_icon_dir {
+        if !Path::new(dir).is_dir() {
+            return Err(format!("icon folder not found: {}", dir));
+        }
+    }
+    if !settings.vr_overlay_scale.is_finite() {
+        return Err("invalid scale".to_string());
+    }
+    settings.vr_overlay_scale = settings
+        .vr_overlay_scale
+        .clamp(VR_OVERLAY_SCALE_RANGE.0, VR_OVERLAY_SCALE_RANGE.1);
+    if !settings.vr_overlay_head_pitch.is_finite() {
+        settings.vr_overlay_head_pitch = default_vr_overlay_head_pitch();
+    }
+    settings.vr_overlay_head_pitch = settings.vr_overlay_head_pitch.clamp(-90.0, 90.0);
+    settings.vr_overlay_transform.normalize();
+    for layout in [
+        &mut settings.vr_overlay_panels.stats,
+        &mut settings.vr_overlay_panels.code,
+    ] {
+        layout.pitch = if layout.pitch.is_finite() {
+            layout.pitch.clamp(-90.0, 90.0)
+        } else {
+            default_vr_overlay_head_pitch()
+        };
+        layout.transform.normalize();
+    }
+    settings.vr_haptics.strength = if settings.vr_haptics.strength.is_finite() {
+        settings.vr_haptics.strength.clamp(0.0, 1.0)
     } else {
-        // 待機状態もクリア
-        {
-            let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
-            state.waiting_for_steamvr = false;
+        VrHapticSettings::default().strength
+    };
+
+    let theme = &mut settings.vr_overlay_theme;
+    for color in [&theme.background_color, &theme.accent_color] {
+        if !is_hex_color(color) {
+            return Err(format!("invalid color: {}", color));
         }
-        stop_vr_overlay(vr_state.inner())?;
     }
+    if !theme.font_scale.is_finite() || theme.font_scale <= 0.0 {
+        return Err(format!("font scale must be positive: {}", theme.font_scale));
+    }
+    theme.font_scale = theme.font_scale.clamp(0.5, 2.0);
 
-    Ok(updated_settings)
+    let appearance = &mut settings.ui_appearance;
+    if !is_hex_color(&appearance.accent_color) {
+        return Err(format!("invalid color: {}", appearance.accent_color));
+    }
+    appearance.font_size = appearance
+        .font_size
+        .clamp(UI_FONT_SIZE_RANGE.0, UI_FONT_SIZE_RANGE.1);
+
+    if let Some(missing) = settings
+        .sounds
+        .files()
+        .find(|file| !Path::new(file).is_file())
+    {
+        return Err(format!("sound file not found: {}", missing));
+    }
+    settings.sounds.volume = if settings.sounds.volume.is_finite() {
+        settings.sounds.volume.clamp(0.0, 1.0)
+    } else {
+        SoundSettings::default().volume
+    };
+    quiet_hours::parse_time(&settings.quiet_hours.start)?;
+    quiet_hours::parse_time(&settings.quiet_hours.end)?;
+    settings.notification_templates.validate()?;
+    for webhook in &settings.webhooks {
+        webhook.validate()?;
+    }
+    if [
+        settings.event_server.port,
+        settings.rest_api.port,
+        settings.osc_chatbox.port,
+        settings.open_rgb.port,
+    ]
+    .contains(&0)
+    {
+        return Err("port must not be 0".to_string());
+    }
+    settings.osc_chatbox.min_interval_secs = settings.osc_chatbox.min_interval_secs.clamp(
+        osc_chatbox::MIN_INTERVAL_RANGE.0,
+        osc_chatbox::MIN_INTERVAL_RANGE.1,
+    );
+    settings.shock.normalize()?;
+    settings.heart_rate.validate()?;
+    settings.cloud_backup.validate()?;
+    settings.terror_data_updates.validate()?;
+    settings.log_detection.validate()?;
+    if !is_hex_color(&settings.open_rgb.idle_color) {
+        return Err(format!("invalid color: {}", settings.open_rgb.idle_color));
+    }
+    settings.hotkeys.parse()?;
+    Ok(())
 }
 
-#[tauri::command]
-fn set_vr_overlay_position(
-    app_handle: AppHandle,
-    state: tauri::State<SharedState>,
-    vr_state: tauri::State<SharedVrState>,
-    position: String,
+/// 設定の一部を変えたものを検証して返す（まだ反映しない）
+fn validated_settings(
+    state: &SharedState,
+    update: impl FnOnce(&mut AppSettings),
 ) -> Result<AppSettings, String> {
-    let pos = match position.as_str() {
-        "LeftHand" => VrOverlayPosition::LeftHand,
-        "Above" => VrOverlayPosition::Above,
-        _ => VrOverlayPosition::RightHand,
+    let mut settings = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.clone()
     };
+    update(&mut settings);
+    validate_settings(&mut settings)?;
+    Ok(settings)
+}
 
+/// 設定の一部を変え、全体を検証してから反映・保存する（検証に通らなければ何も変えない）
+fn update_validated_settings(
+    app_handle: &AppHandle,
+    update: impl FnOnce(&mut AppSettings),
+) -> Result<AppSettings, String> {
     let updated_settings = {
+        let state = app_handle.state::<SharedState>();
         let mut state = state.lock().map_err(|_| "state lock failed")?;
-        state.settings.vr_overlay_position = pos.clone();
-        state.settings.clone()
+        let mut settings = state.settings.clone();
+        update(&mut settings);
+        validate_settings(&mut settings)?;
+        move_secrets_to_keyring(&mut settings)?;
+        state.settings = settings.clone();
+        settings
     };
-    persist_settings(&app_handle, &updated_settings)?;
-
-    // VRオーバーレイに位置変更を通知
-    if updated_settings.vr_overlay_enabled {
-        send_vr_command(vr_state.inner(), &VrCommand::SetPosition { position: pos })?;
-    }
-
+    persist_settings(app_handle, &updated_settings)?;
     Ok(updated_settings)
 }
 
-// ============ テラーデータコマンド ============
-
+/// 保存済みのプロファイル名
 #[tauri::command]
-fn get_terror_info(id: u32, round_type: String) -> TerrorDataResponse {
-    let data = get_terror_data(id, &round_type);
-    data.into()
+fn list_settings_profiles(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    let Ok(entries) = fs::read_dir(profiles_dir(&app_handle)?) else {
+        return Ok(Vec::new());
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    Ok(names)
 }
 
+/// 現在の設定をプロファイルとして保存（同名は上書き）
 #[tauri::command]
-fn get_terrors_info(killer_ids: Vec<u32>, round_type: String) -> Vec<TerrorDataResponse> {
-    get_terrors_data(&killer_ids, &round_type)
-        .into_iter()
-        .map(|d| d.into())
-        .collect()
+fn save_settings_profile(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    name: String,
+) -> Result<(), String> {
+    let path = profile_path(&app_handle, &name)?;
+    let settings = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.clone()
+    };
+    write_settings_file(&path, &settings)
 }
 
-// ============ VRオーバーレイ管理 ============
+/// プロファイルに切り替える
+#[tauri::command]
+fn load_settings_profile(app_handle: AppHandle, name: String) -> Result<AppSettings, String> {
+    let path = profile_path(&app_handle, &name)?;
+    if !path.is_file() {
+        return Err(format!("profile {} not found", name));
+    }
+    let settings = read_settings_file(&path)?;
+    println!("[tsst] 設定プロファイルを適用: {}", name);
+    apply_settings(&app_handle, settings)
+}
 
-/// VRオーバーレイに送信するテラー情報
-#[derive(Debug, Clone, Serialize)]
-struct VrTerrorInfo {
-    name: String,
-    color: Option<String>,
-    abilities: Vec<VrTerrorAbility>,
+#[tauri::command]
+fn delete_settings_profile(app_handle: AppHandle, name: String) -> Result<(), String> {
+    let path = profile_path(&app_handle, &name)?;
+    fs::remove_file(path).map_err(|e| e.to_string())
 }
 
-#[derive(Debug, Clone, Serialize)]
-struct VrTerrorAbility {
-    label: String,
-    value: String,
+// ============ データファイル読み書き ============
+
+fn load_data(app_handle: &AppHandle) -> Option<AppData> {
+    let path = data_path(app_handle)?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
 }
 
-impl From<TerrorData> for VrTerrorInfo {
-    fn from(data: TerrorData) -> Self {
-        VrTerrorInfo {
-            name: data.name,
-            color: data.color,
-            abilities: data
-                .abilities
-                .into_iter()
-                .map(|a| VrTerrorAbility {
-                    label: a.label,
-                    value: a.value,
-                })
-                .collect(),
-        }
+fn persist_data(app_handle: &AppHandle, data: &AppData) -> Result<(), String> {
+    let path = data_path(app_handle).ok_or("data path not found")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
     }
+    let payload = serde_json::to_string_pretty(data).map_err(|err| err.to_string())?;
+    fs::write(path, payload).map_err(|err| err.to_string())?;
+    app_handle.state::<CloudBackup>().mark_changed();
+    Ok(())
 }
 
-#[derive(Debug, Clone, Serialize)]
-#[serde(tag = "type")]
-enum VrCommand {
-    #[serde(rename = "update_terrors")]
-    UpdateTerrors {
-        terrors: Vec<VrTerrorInfo>,
-        round_type: String,
-    },
-    #[serde(rename = "set_position")]
-    SetPosition { position: VrOverlayPosition },
-    #[serde(rename = "clear")]
-    Clear,
-    #[serde(rename = "quit")]
-    Quit,
+/// クラウドバックアップの設定を変更（`secret`を渡したら資格情報ストアに保存する）
+#[tauri::command]
+fn set_cloud_backup(
+    app_handle: AppHandle,
+    settings: CloudBackupSettings,
+    secret: Option<String>,
+) -> Result<AppSettings, String> {
+    if let Some(secret) = secret {
+        cloud_backup::store_secret(settings.provider, &secret)?;
+    }
+    let updated_settings =
+        update_validated_settings(&app_handle, |current| current.cloud_backup = settings)?;
+    if updated_settings.cloud_backup.enabled {
+        // 有効にしたらすぐに（待ち時間の経過後に）アップロードする
+        app_handle.state::<CloudBackup>().mark_changed();
+    }
+    Ok(updated_settings)
 }
 
-/// SteamVRが起動しているかどうかを確認する（vrserver.exeプロセスの存在チェック）
-#[cfg(windows)]
-fn is_steamvr_running() -> bool {
-    use std::ffi::OsStr;
-    use std::os::windows::ffi::OsStrExt;
-    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
-    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
-        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
-        TH32CS_SNAPPROCESS,
+/// クラウドのバックアップでデータを置き換える（今のデータは data.before-restore.json に残す）
+#[tauri::command]
+async fn restore_from_cloud(app_handle: AppHandle) -> Result<AppSnapshot, String> {
+    let settings = {
+        let state = app_handle.state::<SharedState>();
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.cloud_backup.clone()
     };
-
-    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
-    if snapshot == INVALID_HANDLE_VALUE {
-        return false;
+    let content = tauri::async_runtime::spawn_blocking(move || cloud_backup::download(&settings))
+        .await
+        .map_err(|e| e.to_string())??;
+    let restored: AppData =
+        serde_json::from_str(&content).map_err(|e| format!("invalid backup: {}", e))?;
+
+    let path = data_path(&app_handle).ok_or("data path not found")?;
+    if path.exists() {
+        fs::copy(&path, path.with_file_name("data.before-restore.json"))
+            .map_err(|e| e.to_string())?;
     }
+    let (data, snapshot) = {
+        let state = app_handle.state::<SharedState>();
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.data = restored;
+        (state.data.clone(), state.snapshot())
+    };
+    persist_data(&app_handle, &data)?;
+    println!("[tsst] Data restored from cloud backup");
+    let _ = app_handle.emit("state_updated", &snapshot);
+    Ok(snapshot)
+}
 
-    let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
-    entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+/// 取得したテラーデータのキャッシュ
+fn terror_data_cache_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("terror_data_cache.json"))
+}
 
-    let target_exe: Vec<u16> = OsStr::new("vrserver.exe")
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
+/// テラーデータの取得先と確認間隔を変更（無効にしたら同梱のデータに戻す）
+#[tauri::command]
+fn set_terror_data_updates(
+    app_handle: AppHandle,
+    settings: TerrorDataUpdateSettings,
+) -> Result<AppSettings, String> {
+    let updated_settings = update_validated_settings(&app_handle, |current| {
+        current.terror_data_updates = settings
+    })?;
+    if !updated_settings.terror_data_updates.enabled {
+        terror_updates::install(None);
+    }
+    Ok(updated_settings)
+}
 
-    let mut found = false;
-    unsafe {
-        if Process32FirstW(snapshot, &mut entry) != 0 {
-            loop {
-                // szExeFileをnull終端の文字列として比較
-                let exe_name_len = entry
-                    .szExeFile
-                    .iter()
-                    .position(|&c| c == 0)
-                    .unwrap_or(entry.szExeFile.len());
-                let exe_name = &entry.szExeFile[..exe_name_len];
+/// 今すぐテラーデータを取得して切り替える（取得したデータのバージョンを返す）
+#[tauri::command]
+async fn check_terror_data_update(app_handle: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || update_terror_data(&app_handle))
+        .await
+        .map_err(|e| e.to_string())?
+}
 
-                // 大文字小文字を無視して比較
-                let target_len = target_exe.len() - 1; // null終端を除く
-                if exe_name.len() == target_len {
-                    let matches = exe_name.iter().zip(target_exe.iter()).all(|(&a, &b)| {
-                        // ASCII大文字を小文字に変換して比較
-                        let a_lower = if a >= 'A' as u16 && a <= 'Z' as u16 {
-                            a + 32
-                        } else {
-                            a
-                        };
-                        let b_lower = if b >= 'A' as u16 && b <= 'Z' as u16 {
-                            b + 32
-                        } else {
-                            b
-                        };
-                        a_lower == b_lower
-                    });
-                    if matches {
-                        found = true;
-                        break;
-                    }
-                }
+/// 設定したURLからテラーデータを取得し、キャッシュしてから切り替える
+fn update_terror_data(app_handle: &AppHandle) -> Result<String, String> {
+    let url = {
+        let state = app_handle.state::<SharedState>();
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.terror_data_updates.url.clone()
+    };
+    let (dataset, body) = terror_updates::fetch(&url)?;
+    let version = dataset.version.clone();
+    if terror_updates::current_version().as_deref() != Some(version.as_str()) {
+        let path = terror_data_cache_path(app_handle).ok_or("data path not found")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        fs::write(path, body).map_err(|err| err.to_string())?;
+        terror_updates::install(Some(dataset));
+        println!("[tsst] Terror data updated: {}", version);
+        let _ = app_handle.emit("terror_data_updated", &version);
+    }
+    Ok(version)
+}
 
-                if Process32NextW(snapshot, &mut entry) == 0 {
-                    break;
+/// キャッシュしたテラーデータを読み込み、設定した間隔で更新を確認し続ける
+fn start_terror_data_updater(app_handle: AppHandle) {
+    let settings = {
+        let state = app_handle.state::<SharedState>();
+        let state = state.lock().expect("state lock");
+        state.settings.terror_data_updates.clone()
+    };
+    if settings.enabled {
+        let cached = terror_data_cache_path(&app_handle)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| terror_updates::TerrorDataset::parse(&content));
+        match cached {
+            Some(Ok(dataset)) => terror_updates::install(Some(dataset)),
+            Some(Err(e)) => println!("[tsst] Ignoring cached terror data: {}", e),
+            None => {}
+        }
+    }
+
+    std::thread::spawn(move || {
+        let mut last_checked: Option<Instant> = None;
+        loop {
+            let settings = {
+                let state = app_handle.state::<SharedState>();
+                let Ok(state) = state.lock() else {
+                    return;
+                };
+                state.settings.terror_data_updates.clone()
+            };
+            let interval = Duration::from_secs(u64::from(settings.interval_hours) * 3600);
+            if settings.enabled && last_checked.is_none_or(|at| at.elapsed() >= interval) {
+                last_checked = Some(Instant::now());
+                if let Err(e) = update_terror_data(&app_handle) {
+                    app_errors::report(&app_handle, Severity::Warning, e);
                 }
             }
+            std::thread::sleep(Duration::from_secs(60));
+        }
+    });
+}
+
+/// 変更から待ち時間がたったdata.jsonをアップロードし続ける
+fn start_cloud_backup_worker(app_handle: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(1));
+        let settings = {
+            let state = app_handle.state::<SharedState>();
+            let Ok(state) = state.lock() else {
+                continue;
+            };
+            state.settings.cloud_backup.clone()
+        };
+        let backup = app_handle.state::<CloudBackup>();
+        let debounce = Duration::from_secs(u64::from(settings.debounce_secs));
+        if !settings.enabled || !backup.take_due(debounce) {
+            continue;
+        }
+        let result = data_path(&app_handle)
+            .ok_or_else(|| "data path not found".to_string())
+            .and_then(|path| fs::read(path).map_err(|e| e.to_string()))
+            .and_then(|payload| cloud_backup::upload(&settings, &payload));
+        match result {
+            Ok(()) => println!("[tsst] Cloud backup uploaded"),
+            Err(e) => {
+                app_errors::report(
+                    &app_handle,
+                    Severity::Warning,
+                    format!("Cloud backup failed: {}", e),
+                );
+                // 次の待ち時間の経過後にやり直す
+                backup.mark_changed();
+            }
+        }
+    });
+}
+
+// ============ Tauri コマンド ============
+
+#[tauri::command]
+fn get_state(state: tauri::State<SharedState>) -> AppSnapshot {
+    let state = state.lock().expect("state lock");
+    state.snapshot()
+}
+
+/// 直近のバックエンドのエラー（古い順）
+#[tauri::command]
+fn get_recent_errors() -> Vec<app_errors::AppError> {
+    app_errors::recent()
+}
+
+/// 履歴の絞り込み条件
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+struct HistoryFilter {
+    round_type: Option<String>,
+    /// テラー名の部分一致
+    terror: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &CodeEntry) -> bool {
+        if let Some(ref round_type) = self.round_type {
+            if entry.round_type.as_ref() != Some(round_type)
+                && entry.round_type_english.as_ref() != Some(round_type)
+            {
+                return false;
+            }
+        }
+        if let Some(ref terror) = self.terror {
+            let terror = terror.to_lowercase();
+            let found = entry
+                .terror_names
+                .iter()
+                .flatten()
+                .any(|name| name.to_lowercase().contains(&terror));
+            if !found {
+                return false;
+            }
+        }
+        in_range(&entry.timestamp, self.from.as_deref(), self.to.as_deref())
+    }
+}
+
+/// 履歴のページ（履歴・取り込んだ履歴・ラウンド記録のセーブコードを新しい順に）
+#[derive(Debug, Clone, Serialize)]
+struct HistoryPage {
+    entries: Vec<CodeEntry>,
+    /// 条件に合う履歴の総数
+    total: usize,
+    offset: usize,
+    limit: usize,
+}
+
+#[tauri::command]
+fn get_history_page(
+    state: tauri::State<SharedState>,
+    offset: usize,
+    limit: usize,
+    filter: Option<HistoryFilter>,
+) -> Result<HistoryPage, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    let filter = filter.unwrap_or_default();
+    let matched: Vec<CodeEntry> = tonsave_history::all_codes(&state.data)
+        .into_iter()
+        .rev()
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    Ok(HistoryPage {
+        total: matched.len(),
+        entries: matched.into_iter().skip(offset).take(limit).collect(),
+        offset,
+        limit,
+    })
+}
+
+/// ログフォルダの確認結果
+#[derive(Debug, Clone, Serialize)]
+struct LogDirCheck {
+    path: String,
+    exists: bool,
+    /// output_logファイルの数
+    log_files: usize,
+    newest_file: Option<String>,
+    /// 最新のログが更新されてからの秒数
+    newest_age_secs: Option<u64>,
+}
+
+fn is_output_log(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("output_log") && name.ends_with(".txt"))
+}
+
+fn check_log_dir(dir: &Path) -> LogDirCheck {
+    let mut log_files = 0;
+    let mut newest: Option<(PathBuf, std::time::SystemTime)> = None;
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() || !is_output_log(&path) {
+                continue;
+            }
+            log_files += 1;
+            let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+                continue;
+            };
+            if newest.as_ref().is_none_or(|(_, last)| modified > *last) {
+                newest = Some((path, modified));
+            }
         }
-        CloseHandle(snapshot);
     }
 
+    LogDirCheck {
+        path: dir.to_string_lossy().to_string(),
+        exists: dir.is_dir(),
+        log_files,
+        newest_file: newest
+            .as_ref()
+            .and_then(|(path, _)| path.file_name())
+            .map(|name| name.to_string_lossy().to_string()),
+        newest_age_secs: newest
+            .and_then(|(_, modified)| modified.elapsed().ok())
+            .map(|age| age.as_secs()),
+    }
+}
+
+/// 指定したフォルダがVRChatのログフォルダとして使えるか確認
+#[tauri::command]
+fn validate_log_dir(path: String) -> LogDirCheck {
+    check_log_dir(Path::new(&path))
+}
+
+/// よくある場所からVRChatのログフォルダを探す（新しいログがある順）
+#[tauri::command]
+fn detect_log_dirs() -> Vec<LogDirCheck> {
+    let vrchat_dir = |base: PathBuf| base.join("LocalLow").join("VRChat").join("VRChat");
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    candidates.extend(get_default_log_dir());
+    if let Ok(profile) = env::var("USERPROFILE") {
+        candidates.push(vrchat_dir(PathBuf::from(profile).join("AppData")));
+    }
+    // Linux（Steam Proton）
+    if let Ok(home) = env::var("HOME") {
+        let steam_user = PathBuf::from(home)
+            .join(".steam/steam/steamapps/compatdata/438100/pfx/drive_c/users/steamuser");
+        candidates.push(vrchat_dir(steam_user.join("AppData")));
+    }
+    candidates.dedup();
+
+    let mut found: Vec<LogDirCheck> = candidates
+        .iter()
+        .map(|dir| check_log_dir(dir))
+        .filter(|check| check.log_files > 0)
+        .collect();
+    found.sort_by_key(|check| check.newest_age_secs.unwrap_or(u64::MAX));
     found
 }
 
-#[cfg(not(windows))]
-fn is_steamvr_running() -> bool {
-    // 非Windows環境では常にtrueを返す（未実装）
-    true
+/// イベントサーバー・REST APIの認証に使うトークン（まだなければ生成して保存する）
+fn ensure_api_token(app_handle: &AppHandle) -> Result<String, String> {
+    let state = app_handle.state::<SharedState>();
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        if !state.settings.rest_api.token.is_empty() {
+            return Ok(state.settings.rest_api.token.clone());
+        }
+        state.settings.rest_api.token = rest_api::generate_token()?;
+        state.settings.clone()
+    };
+    persist_settings(app_handle, &updated_settings)?;
+    Ok(updated_settings.rest_api.token)
 }
 
-#[cfg(windows)]
-fn assign_process_to_job_object(
-    process_handle: windows_sys::Win32::Foundation::HANDLE,
+/// イベントサーバーを設定に合わせて起動・停止する（REST APIと同じトークンで認証する）
+fn configure_event_server(
+    app_handle: &AppHandle,
+    settings: &EventServerSettings,
 ) -> Result<(), String> {
-    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
-    use windows_sys::Win32::System::JobObjects::*;
+    let token = if settings.enabled {
+        ensure_api_token(app_handle)?
+    } else {
+        String::new()
+    };
+    app_handle
+        .state::<EventServer>()
+        .configure(settings, &token)
+}
 
-    unsafe {
-        // ジョブオブジェクトを作成
-        let job_handle: HANDLE = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
-        if job_handle.is_null() || job_handle == INVALID_HANDLE_VALUE {
-            return Err("Failed to create job object".to_string());
+/// WebSocketイベントサーバーの有効/無効とポートを変更
+#[tauri::command]
+fn set_event_server(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    settings: EventServerSettings,
+) -> Result<AppSettings, String> {
+    let settings =
+        validated_settings(&state, |current| current.event_server = settings)?.event_server;
+    configure_event_server(&app_handle, &settings)?;
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.event_server = settings;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    Ok(updated_settings)
+}
+
+/// REST APIの有効/無効・ポート・LANからの接続を変更（初めて有効にしたときにトークンを生成）
+#[tauri::command]
+fn set_rest_api(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    rest_api: tauri::State<RestApi>,
+    enabled: bool,
+    port: u16,
+    allow_lan: bool,
+) -> Result<AppSettings, String> {
+    let settings = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        let current = &state.settings.rest_api;
+        RestApiSettings {
+            enabled,
+            port,
+            token: if current.token.is_empty() {
+                rest_api::generate_token()?
+            } else {
+                current.token.clone()
+            },
+            allow_lan,
+            dashboard_token: if current.dashboard_token.is_empty() {
+                rest_api::generate_token()?
+            } else {
+                current.dashboard_token.clone()
+            },
+        }
+    };
+    let settings = validated_settings(&state, |current| current.rest_api = settings)?.rest_api;
+    rest_api.configure(&settings, rest_api_handler(&app_handle))?;
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.rest_api = settings;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    Ok(updated_settings)
+}
+
+/// REST APIとダッシュボードのトークンを作り直す（以前のトークンは使えなくなる）
+#[tauri::command]
+fn regenerate_rest_api_token(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    rest_api: tauri::State<RestApi>,
+) -> Result<AppSettings, String> {
+    let settings = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        RestApiSettings {
+            token: rest_api::generate_token()?,
+            dashboard_token: rest_api::generate_token()?,
+            ..state.settings.rest_api.clone()
+        }
+    };
+    rest_api.configure(&settings, rest_api_handler(&app_handle))?;
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.rest_api = settings;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    // イベントサーバーも同じトークンで認証する
+    configure_event_server(&app_handle, &updated_settings.event_server)?;
+    Ok(updated_settings)
+}
+
+/// Webhookの一覧を変更
+#[tauri::command]
+fn set_webhooks(app_handle: AppHandle, webhooks: Vec<Webhook>) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |settings| settings.webhooks = webhooks)
+}
+
+/// サンプルの値でWebhookを送って設定を確かめる（保存はしない）
+#[tauri::command]
+async fn test_webhook(webhook: Webhook) -> Result<(), String> {
+    webhook.validate()?;
+    tauri::async_runtime::spawn_blocking(move || webhooks::send(&webhook, &EventValues::sample()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// イベントに対応する有効なWebhookを別スレッドで送る
+fn dispatch_webhooks(app_handle: &AppHandle, event: WebhookEvent, payload: &str) {
+    let targets: Vec<Webhook> = {
+        let state = app_handle.state::<SharedState>();
+        let Ok(state) = state.lock() else {
+            return;
+        };
+        state
+            .settings
+            .webhooks
+            .iter()
+            .filter(|w| w.enabled && w.events.contains(&event))
+            .cloned()
+            .collect()
+    };
+    if targets.is_empty() {
+        return;
+    }
+    let payload = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
+    let values = EventValues::from_payload(event, &payload);
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        for webhook in &targets {
+            if let Err(e) = webhooks::send(webhook, &values) {
+                app_errors::report(&app_handle, Severity::Warning, e);
+            }
+        }
+    });
+}
+
+/// 最後の生存者・スタン・ポイント獲得・アイテム購入を検出するログのパターンを変更（空なら検出しない）
+#[tauri::command]
+fn set_log_detection(
+    app_handle: AppHandle,
+    settings: LogDetectionSettings,
+) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |current| current.log_detection = settings)
+}
+
+/// VRChatのチャットボックスへの送信設定を変更
+#[tauri::command]
+fn set_osc_chatbox(
+    app_handle: AppHandle,
+    settings: OscChatboxSettings,
+) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |current| current.osc_chatbox = settings)
+}
+
+/// 出現したテラー名（と連続生存数）をチャットボックスに送る
+fn send_terror_chatbox(app_handle: &AppHandle, payload: &str) {
+    let Ok(payload) = serde_json::from_str::<TerrorsSpawnedPayload>(payload) else {
+        return;
+    };
+    let (settings, streak) = {
+        let state = app_handle.state::<SharedState>();
+        let Ok(state) = state.lock() else {
+            return;
+        };
+        (
+            state.settings.osc_chatbox.clone(),
+            current_streak(&state.data.rounds),
+        )
+    };
+    if !settings.enabled || payload.killers.is_empty() {
+        return;
+    }
+    let names: Vec<String> = payload
+        .killers
+        .iter()
+        .map(|&id| get_terror_data(id, &payload.round_type).name)
+        .collect();
+    let mut text = names.join(" & ");
+    if settings.include_streak {
+        text.push_str(&format!(" | 連続生存 {}", streak));
+    }
+    match app_handle.state::<OscChatbox>().send(&settings, &text) {
+        Ok(true) => {}
+        Ok(false) => println!("[tsst] Chatbox message skipped (rate limit): {}", text),
+        Err(e) => app_errors::report(app_handle, Severity::Warning, e),
+    }
+}
+
+/// OpenShock / PiShock連携の設定を変更（有効にするとキルスイッチも戻す）
+#[tauri::command]
+fn set_shock_settings(
+    app_handle: AppHandle,
+    shocker: tauri::State<Shocker>,
+    mut settings: ShockSettings,
+) -> Result<AppSettings, String> {
+    settings.store_secrets()?;
+    settings.check_credentials()?;
+    let enabled = settings.enabled;
+    let updated_settings =
+        update_validated_settings(&app_handle, |current| current.shock = settings)?;
+    if enabled {
+        shocker.release();
+    }
+    Ok(updated_settings)
+}
+
+/// キルスイッチ：以後の送信を止め、連携を無効にして保存する
+#[tauri::command]
+fn engage_shock_kill_switch(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    shocker: tauri::State<Shocker>,
+) -> Result<AppSettings, String> {
+    let current = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.shock.clone()
+    };
+    // 停止の送信に失敗しても無効化は必ず行う
+    if let Err(e) = shocker.kill(&current) {
+        app_errors::report(&app_handle, Severity::Warning, e);
+    }
+    println!("[tsst] Shock kill switch engaged");
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.shock.enabled = false;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    Ok(updated_settings)
+}
+
+/// 死亡時に振動・電気刺激を送る（通信するので別スレッドで）
+fn trigger_shock_on_death(app_handle: &AppHandle) {
+    let settings = {
+        let state = app_handle.state::<SharedState>();
+        let Ok(state) = state.lock() else {
+            return;
+        };
+        state.settings.shock.clone()
+    };
+    if !settings.enabled {
+        return;
+    }
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        let result = app_handle.state::<Shocker>().trigger(&settings);
+        match result {
+            Ok(true) => println!("[tsst] Shock sent ({:?})", settings.mode),
+            Ok(false) => println!("[tsst] Shock skipped (cooldown or kill switch)"),
+            Err(e) => app_errors::report(&app_handle, Severity::Warning, e),
+        }
+    });
+}
+
+/// 心拍数の受信先を変更
+#[tauri::command]
+fn set_heart_rate(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    heart_rate: tauri::State<HeartRateMonitor>,
+    mut settings: HeartRateSettings,
+) -> Result<AppSettings, String> {
+    settings.store_secrets()?;
+    let settings = validated_settings(&state, |current| current.heart_rate = settings)?.heart_rate;
+    heart_rate.configure(&settings, heart_rate_handler(&app_handle))?;
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.heart_rate = settings;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    Ok(updated_settings)
+}
+
+/// 受信した心拍数を進行中のラウンドに記録し、フロントエンドにも知らせる
+fn heart_rate_handler(app_handle: &AppHandle) -> heart_rate::SampleHandler {
+    let app_handle = app_handle.clone();
+    Arc::new(move |bpm| {
+        if let Ok(mut state) = app_handle.state::<SharedState>().lock() {
+            if state.current_round.is_active {
+                state.current_round.heart_rates.push(bpm);
+            }
+        }
+        let _ = app_handle.emit("heart_rate_updated", bpm);
+    })
+}
+
+fn average_bpm(samples: &[u32]) -> Option<u32> {
+    if samples.is_empty() {
+        return None;
+    }
+    let sum: u64 = samples.iter().map(|&bpm| u64::from(bpm)).sum();
+    Some((sum as f64 / samples.len() as f64).round() as u32)
+}
+
+/// OpenRGBとの連携設定を変更（有効にしたらラウンド外の色にする）
+#[tauri::command]
+fn set_open_rgb(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    rgb_sync: tauri::State<RgbSync>,
+    settings: OpenRgbSettings,
+) -> Result<AppSettings, String> {
+    let settings = validated_settings(&state, |current| current.open_rgb = settings)?.open_rgb;
+    if settings.enabled {
+        if let Some(idle_color) = Rgb::from_hex(&settings.idle_color) {
+            rgb_sync.set_color(&settings, idle_color);
+        }
+    }
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.open_rgb = settings;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    Ok(updated_settings)
+}
+
+/// ラウンドの進行に合わせてOpenRGBの色を変える
+fn sync_rgb_lighting(app_handle: &AppHandle, event: &str, payload: &str) {
+    let settings = {
+        let state = app_handle.state::<SharedState>();
+        let Ok(state) = state.lock() else {
+            return;
+        };
+        state.settings.open_rgb.clone()
+    };
+    if !settings.enabled {
+        return;
+    }
+    let rgb_sync = app_handle.state::<RgbSync>();
+    match event {
+        "terrors_spawned" if settings.sync_terror_color => {
+            let Ok(payload) = serde_json::from_str::<TerrorsSpawnedPayload>(payload) else {
+                return;
+            };
+            let color = payload
+                .killers
+                .first()
+                .and_then(|&id| get_terror_data(id, &payload.round_type).color)
+                .and_then(|color| Rgb::from_components(&color));
+            if let Some(color) = color {
+                rgb_sync.set_color(&settings, color);
+            }
+        }
+        "round_ended" if settings.sync_terror_color => {
+            if let Some(color) = Rgb::from_hex(&settings.idle_color) {
+                rgb_sync.set_color(&settings, color);
+            }
+        }
+        "code_found" if settings.flash_on_code => rgb_sync.flash(&settings),
+        _ => {}
+    }
+}
+
+/// REST APIへのリクエストをアプリの状態から処理する
+fn rest_api_handler(app_handle: &AppHandle) -> rest_api::Handler {
+    let app_handle = app_handle.clone();
+    Arc::new(move |request| {
+        let state = app_handle.state::<SharedState>();
+        let value = match request {
+            ApiRequest::State => {
+                let state = state.lock().map_err(|_| "state lock failed")?;
+                serde_json::to_value(state.api_snapshot())
+            }
+            ApiRequest::History => {
+                let state = state.lock().map_err(|_| "state lock failed")?;
+                serde_json::to_value(&state.data.history)
+            }
+            ApiRequest::Stats => {
+                let state = state.lock().map_err(|_| "state lock failed")?;
+                serde_json::to_value(&state.data.stats)
+            }
+            ApiRequest::CopyCode {
+                timestamp: Some(timestamp),
+            } => {
+                let code = copy_code(state, timestamp)?;
+                Ok(serde_json::json!({ "code": code }))
+            }
+            ApiRequest::CopyCode { timestamp: None } => {
+                let latest = state
+                    .lock()
+                    .map_err(|_| "state lock failed")?
+                    .data
+                    .history
+                    .last()
+                    .map(|entry| entry.code.clone())
+                    .ok_or("no save code recorded")?;
+                copy_code_to_clipboard(state.inner(), latest.clone())?;
+                Ok(serde_json::json!({ "code": latest }))
+            }
+            ApiRequest::ToggleOverlay => {
+                let enabled = !state
+                    .lock()
+                    .map_err(|_| "state lock failed")?
+                    .vr_overlay_enabled();
+                enable_vr_overlay(&app_handle, enabled)?;
+                if let Ok(state) = state.lock() {
+                    let _ = app_handle.emit("state_updated", &state.snapshot());
+                }
+                Ok(serde_json::json!({ "vr_overlay_enabled": enabled }))
+            }
+            ApiRequest::ToggleMonitoring => {
+                let paused = toggle_monitoring_paused(&app_handle)?;
+                Ok(serde_json::json!({ "monitoring_paused": paused }))
+            }
+            ApiRequest::StatsText => {
+                let state = state.lock().map_err(|_| "state lock failed")?;
+                Ok(serde_json::Value::String(export::stats_summary_text(
+                    &state.data,
+                    state.session_started_at.as_deref(),
+                )))
+            }
+            ApiRequest::Metrics => Ok(serde_json::Value::String(metrics::render())),
+            ApiRequest::Dashboard => {
+                let state = state.lock().map_err(|_| "state lock failed")?;
+                let round = &state.current_round;
+                let round_type = round
+                    .round_type
+                    .clone()
+                    .unwrap_or_else(|| "Classic".to_string());
+                let terrors: Vec<serde_json::Value> = get_terrors_data(&round.killers, &round_type)
+                    .iter()
+                    .map(|t| serde_json::json!({ "name": t.name, "color": t.color }))
+                    .collect();
+                let stats = &state.data.stats;
+                // LANの端末に返すため、表示に使う分だけをまとめる
+                Ok(serde_json::json!({
+                    "current_round": {
+                        "is_active": round.is_active,
+                        "round_type": round.round_type,
+                        "map_name": round.map_name,
+                        "is_dead": round.is_dead,
+                        "terrors": terrors,
+                    },
+                    "history": state.data.history.iter().rev().collect::<Vec<_>>(),
+                    "stats": {
+                        "total_rounds": stats.total_rounds,
+                        "survivals": stats.survivals,
+                        "deaths": stats.deaths,
+                    },
+                }))
+            }
+        };
+        value.map_err(|e| e.to_string())
+    })
+}
+
+/// アプリ画面の配色・強調色・文字サイズを変更
+#[tauri::command]
+fn set_ui_appearance(
+    app_handle: AppHandle,
+    appearance: UiAppearance,
+) -> Result<AppSettings, String> {
+    let updated_settings =
+        update_validated_settings(&app_handle, |settings| settings.ui_appearance = appearance)?;
+    let _ = app_handle.emit("ui_appearance_changed", &updated_settings.ui_appearance);
+    Ok(updated_settings)
+}
+
+// ============ 統計コマンド ============
+
+/// 統計にラウンド1件分の結果を加算する
+fn add_round_to_stats(stats: &mut RoundStats, record: &RoundRecord) {
+    let round_stats = stats
+        .round_types
+        .entry(record.round_type.clone())
+        .or_default();
+    if record.is_dead {
+        stats.deaths += 1;
+        round_stats.deaths += 1;
+    } else {
+        stats.survivals += 1;
+        round_stats.survivals += 1;
+    }
+    if record.last_survivor {
+        stats.last_survivals += 1;
+        round_stats.last_survivals += 1;
+    }
+    stats.stuns += record.stuns;
+    round_stats.stuns += record.stuns;
+    update_stun_average(round_stats);
+}
+
+fn update_stun_average(round_stats: &mut RoundTypeStats) {
+    let rounds = round_stats.survivals + round_stats.deaths;
+    round_stats.avg_stuns = if rounds == 0 {
+        0.0
+    } else {
+        round_stats.stuns as f64 / rounds as f64
+    };
+}
+
+/// 統計をリセットする（archive=trueの場合はリセット前の統計をアーカイブに保存）
+#[tauri::command]
+fn reset_stats(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    archive: bool,
+) -> Result<RoundStats, String> {
+    let (data_clone, snapshot) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        let now = now_timestamp();
+        let stats = std::mem::take(&mut state.data.stats);
+        if archive {
+            state.data.stats_archives.push(StatsArchive {
+                started_at: stats.since.clone(),
+                ended_at: now.clone(),
+                stats,
+            });
+        }
+        state.data.stats.since = Some(now);
+        (state.data.clone(), state.snapshot())
+    };
+    persist_data(&app_handle, &data_clone)?;
+    let _ = app_handle.emit("state_updated", &snapshot);
+    Ok(data_clone.stats)
+}
+
+#[tauri::command]
+fn get_round_records(state: tauri::State<SharedState>) -> Result<Vec<RoundRecord>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(state.data.rounds.clone())
+}
+
+/// ラウンド記録から統計を再構築する（集計期間・経済統計は引き継ぐ）
+fn rebuild_stats(stats: &RoundStats, data: &AppData) -> RoundStats {
+    let mut rebuilt = RoundStats {
+        since: stats.since.clone(),
+        ..Default::default()
+    };
+    for record in data
+        .rounds
+        .iter()
+        .filter(|r| !r.excluded && in_range(&r.ended_at, stats.since.as_deref(), None))
+    {
+        add_round_to_stats(&mut rebuilt, record);
+    }
+    for event in data
+        .economy_events
+        .iter()
+        .filter(|e| in_range(&e.timestamp, stats.since.as_deref(), None))
+    {
+        rebuilt.economy.add(event);
+    }
+    refresh_duration_stats(&mut rebuilt, &data.rounds);
+    rebuilt
+}
+
+/// ラウンド記録・ポイントの記録から統計を再計算する
+///
+/// 記録が導入される前に集計された分は記録が存在しないため失われる。
+#[tauri::command]
+fn recompute_stats(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+) -> Result<RoundStats, String> {
+    let (data_clone, snapshot) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.data.stats = rebuild_stats(&state.data.stats, &state.data);
+        (state.data.clone(), state.snapshot())
+    };
+    persist_data(&app_handle, &data_clone)?;
+    let _ = app_handle.emit("state_updated", &snapshot);
+    Ok(data_clone.stats)
+}
+
+/// ラウンドを無効化し、残りのラウンド記録から統計を再構築する
+///
+/// リセット前のラウンドは現在の集計期間に含まれないので、無効化しても統計は変わらない。
+#[tauri::command]
+fn invalidate_round(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    id: u64,
+) -> Result<RoundStats, String> {
+    let (data_clone, snapshot) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        let record = state
+            .data
+            .rounds
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| format!("round {} not found", id))?;
+        if record.excluded {
+            return Ok(state.data.stats.clone());
+        }
+        record.excluded = true;
+        state.data.stats = rebuild_stats(&state.data.stats, &state.data);
+        (state.data.clone(), state.snapshot())
+    };
+    persist_data(&app_handle, &data_clone)?;
+    let _ = app_handle.emit("state_updated", &snapshot);
+    Ok(data_clone.stats)
+}
+
+/// 条件に合うラウンドのみを集計する
+#[tauri::command]
+fn query_stats(
+    state: tauri::State<SharedState>,
+    filter: StatsFilter,
+) -> Result<StatsQueryResult, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(query_rounds(&state.data.rounds, &filter))
+}
+
+/// 2つの期間の統計を比較する（差分は b - a）
+#[tauri::command]
+fn compare_periods(
+    state: tauri::State<SharedState>,
+    a_range: DateRange,
+    b_range: DateRange,
+) -> Result<PeriodComparison, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(compare_period_stats(&state.data.rounds, &a_range, &b_range))
+}
+
+/// セッションの詳細（ラウンド・コード・マップ・集計）
+#[derive(Debug, Clone, Serialize)]
+struct SessionDetail {
+    session: SessionRecord,
+    rounds: Vec<RoundRecord>,
+    codes: Vec<String>,
+    maps: Vec<String>,
+    summary: StatsQueryResult,
+}
+
+#[tauri::command]
+fn get_sessions(state: tauri::State<SharedState>) -> Result<Vec<SessionRecord>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(state.data.sessions.clone())
+}
+
+#[tauri::command]
+fn get_session(state: tauri::State<SharedState>, id: u64) -> Result<SessionDetail, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    let session = state
+        .data
+        .sessions
+        .iter()
+        .find(|s| s.id == id)
+        .cloned()
+        .ok_or_else(|| format!("session {} not found", id))?;
+    Ok(session_detail(&state.data, session))
+}
+
+/// セッション終了時のまとめ
+#[derive(Debug, Clone, Serialize)]
+struct SessionSummary {
+    session_id: u64,
+    started_at: String,
+    ended_at: Option<String>,
+    rounds: u32,
+    survivals: u32,
+    deaths: u32,
+    survival_rate: f64,
+    codes_saved: usize,
+    best_streak: u32,
+}
+
+fn session_summary(data: &AppData, session: SessionRecord) -> SessionSummary {
+    let detail = session_detail(data, session);
+    SessionSummary {
+        session_id: detail.session.id,
+        started_at: detail.session.started_at,
+        ended_at: detail.session.ended_at,
+        rounds: detail.summary.rounds,
+        survivals: detail.summary.survivals,
+        deaths: detail.summary.deaths,
+        survival_rate: detail.summary.survival_rate,
+        codes_saved: detail.codes.len(),
+        best_streak: best_streak(&detail.rounds),
+    }
+}
+
+/// 現在のセッションを終了し、ラウンドがあればまとめを返す
+fn end_current_session(state: &mut AppState, ended_at: String) -> Option<SessionSummary> {
+    let session_id = state.current_session_id.take()?;
+    let session = state
+        .data
+        .sessions
+        .iter_mut()
+        .find(|s| s.id == session_id)?;
+    session.ended_at = Some(ended_at);
+    let session = session.clone();
+    println!("[tsst] セッション終了: {}", session_id);
+
+    let summary = session_summary(&state.data, session);
+    (summary.rounds > 0).then_some(summary)
+}
+
+fn session_detail(data: &AppData, session: SessionRecord) -> SessionDetail {
+    let rounds: Vec<RoundRecord> = data
+        .rounds
+        .iter()
+        .filter(|r| r.session_id == Some(session.id))
+        .cloned()
+        .collect();
+    let codes = rounds.iter().filter_map(|r| r.save_code.clone()).collect();
+    let mut maps: Vec<String> = Vec::new();
+    for map_name in rounds.iter().filter_map(|r| r.map_name.as_ref()) {
+        if !maps.contains(map_name) {
+            maps.push(map_name.clone());
+        }
+    }
+    let summary = query_rounds(&rounds, &StatsFilter::default());
+    SessionDetail {
+        session,
+        rounds,
+        codes,
+        maps,
+        summary,
+    }
+}
+
+/// 統計をテキストにまとめてクリップボードにコピーする
+#[tauri::command]
+fn copy_stats_summary(state: tauri::State<SharedState>) -> Result<String, String> {
+    let text = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        export::stats_summary_text(&state.data, state.session_started_at.as_deref())
+    };
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_text(text.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+/// ラウンド記録をCSVに出力し、出力した行数を返す
+#[tauri::command]
+fn export_rounds_csv(state: tauri::State<SharedState>, path: String) -> Result<usize, String> {
+    let rounds = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.data.rounds.clone()
+    };
+    export::write_rounds_csv(Path::new(&path), &rounds)
+}
+
+/// テラー別の死亡ランキングを取得
+#[tauri::command]
+fn get_death_causes(state: tauri::State<SharedState>) -> Result<Vec<DeathCause>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(death_causes(&state.data.rounds))
+}
+
+/// ポイント獲得・アイテム購入の集計（累計とインスタンス内）
+#[derive(Debug, Clone, Serialize)]
+struct EconomyResponse {
+    total: EconomyStats,
+    session: EconomyStats,
+}
+
+/// 複数テラーの組み合わせ別の遭遇・生存数を取得
+#[tauri::command]
+fn get_combo_stats(state: tauri::State<SharedState>) -> Result<Vec<ComboStats>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(combo_stats(&state.data.rounds))
+}
+
+#[tauri::command]
+fn get_economy_stats(state: tauri::State<SharedState>) -> Result<EconomyResponse, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(EconomyResponse {
+        total: state.data.stats.economy.clone(),
+        session: state.session_economy.clone(),
+    })
+}
+
+// ============ 目標コマンド ============
+
+/// 目標を追加する
+#[tauri::command]
+fn set_goal(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    title: String,
+    kind: GoalKind,
+) -> Result<GoalProgress, String> {
+    let (data_clone, progress) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        let id = state.data.goals.iter().map(|g| g.id).max().unwrap_or(0) + 1;
+        let goal = Goal::new(id, title, kind);
+        let progress = goal.progress(&state.data.rounds);
+        state.data.goals.push(goal);
+        (state.data.clone(), progress)
+    };
+    persist_data(&app_handle, &data_clone)?;
+    Ok(progress)
+}
+
+#[tauri::command]
+fn delete_goal(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    id: u64,
+) -> Result<(), String> {
+    let data_clone = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.data.goals.retain(|g| g.id != id);
+        state.data.clone()
+    };
+    persist_data(&app_handle, &data_clone)
+}
+
+#[tauri::command]
+fn get_goals(state: tauri::State<SharedState>) -> Result<Vec<GoalProgress>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(state
+        .data
+        .goals
+        .iter()
+        .map(|g| g.progress(&state.data.rounds))
+        .collect())
+}
+
+#[tauri::command]
+fn get_milestones(state: tauri::State<SharedState>) -> Result<Vec<Milestone>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(state.data.milestones.clone())
+}
+
+/// 期間の統計をPNGカードとして描画し、保存先のパスを返す
+#[tauri::command]
+fn render_summary_image(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    range: DateRange,
+) -> Result<String, String> {
+    let rounds = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.data.rounds.clone()
+    };
+    let font_path = app_handle
+        .path()
+        .resolve("assets/NotoSansJP-Regular.ttf", BaseDirectory::Resource)
+        .map_err(|e| e.to_string())?;
+    let output_path = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("summaries")
+        .join(format!(
+            "summary-{}.png",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+    summary_image::render_summary_card(&font_path, &output_path, &rounds, &range)?;
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// 履歴・ラウンド記録・統計をJSONでエクスポートする
+#[tauri::command]
+fn export_data(
+    state: tauri::State<SharedState>,
+    path: String,
+    anonymize: bool,
+) -> Result<(), String> {
+    let data = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.data.clone()
+    };
+    export::write_data_json(Path::new(&path), &data, anonymize)
+}
+
+#[tauri::command]
+fn get_stats_archives(state: tauri::State<SharedState>) -> Result<Vec<StatsArchive>, String> {
+    let state = state.lock().map_err(|_| "state lock failed")?;
+    Ok(state.data.stats_archives.clone())
+}
+
+// ============ VR設定コマンド ============
+
+/// オーバーレイの停止を待つ間メインスレッドを止めないよう、別スレッドで切り替える
+#[tauri::command]
+async fn set_vr_overlay_enabled(
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || enable_vr_overlay(&app_handle, enabled))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// VRオーバーレイの有効・無効を切り替えて起動・停止する
+fn enable_vr_overlay(app_handle: &AppHandle, enabled: bool) -> Result<AppSettings, String> {
+    let state = app_handle.state::<SharedState>();
+    let vr_state = app_handle.state::<SharedVrState>();
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        // アカウントの上書きが効いていればそちらを切り替える
+        let account_override = state.current_account.clone().and_then(|account| {
+            state
+                .settings
+                .account_overrides
+                .get_mut(&account)?
+                .vr_overlay_enabled
+                .as_mut()
+        });
+        match account_override {
+            Some(overridden) => *overridden = enabled,
+            None => state.settings.vr_overlay_enabled = enabled,
+        }
+        state.settings.clone()
+    };
+    persist_settings(app_handle, &updated_settings)?;
+
+    // VRオーバーレイの起動/停止（SteamVRが起動していなければ起動は待機状態になる）
+    // 現在のラウンド情報は接続できたときに送られる
+    if enabled {
+        start_vr_overlay(app_handle, vr_state.inner(), &updated_settings)?;
+    } else {
+        // 待機状態もクリア
+        {
+            let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+            state.waiting_for_steamvr = false;
+        }
+        stop_vr_overlay(vr_state.inner())?;
+    }
+
+    Ok(updated_settings)
+}
+
+#[tauri::command]
+fn set_vr_overlay_position(
+    app_handle: AppHandle,
+    vr_state: tauri::State<SharedVrState>,
+    position: String,
+    pitch: Option<f32>,
+) -> Result<AppSettings, String> {
+    let pos = match position.as_str() {
+        "LeftHand" => VrOverlayPosition::LeftHand,
+        "Above" => VrOverlayPosition::Above,
+        "Head" => VrOverlayPosition::Head,
+        _ => VrOverlayPosition::RightHand,
+    };
+
+    let updated_settings = update_validated_settings(&app_handle, |settings| {
+        settings.vr_overlay_position = pos.clone();
+        if let Some(pitch) = pitch.filter(|p| p.is_finite()) {
+            settings.vr_overlay_head_pitch = pitch;
+        }
+    })?;
+
+    // VRオーバーレイに位置変更を通知
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(
+            vr_state.inner(),
+            &VrCommand::SetPosition {
+                position: pos,
+                pitch: updated_settings.vr_overlay_head_pitch,
+            },
+        )?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// VRオーバーレイのバイナリパスを設定（Noneでバンドル版に戻す）
+#[tauri::command]
+fn set_overlay_binary_path(
+    app_handle: AppHandle,
+    vr_state: tauri::State<SharedVrState>,
+    path: Option<String>,
+) -> Result<AppSettings, String> {
+    let updated_settings =
+        update_validated_settings(&app_handle, |settings| settings.overlay_binary_path = path)?;
+
+    // 起動中なら新しいバイナリで再起動
+    let running = vr_state
+        .lock()
+        .map_err(|_| "vr state lock failed")?
+        .process
+        .is_some();
+    if vr_overlay_active(&app_handle) && running {
+        stop_vr_overlay(vr_state.inner())?;
+        start_vr_overlay(&app_handle, vr_state.inner(), &updated_settings)?;
+    }
+
+    Ok(updated_settings)
+}
+
+#[tauri::command]
+fn set_vr_overlay_transform(
+    app_handle: AppHandle,
+    vr_state: tauri::State<SharedVrState>,
+    transform: VrOverlayTransform,
+) -> Result<AppSettings, String> {
+    let updated_settings = update_validated_settings(&app_handle, |settings| {
+        settings.vr_overlay_transform = transform
+    })?;
+
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(
+            vr_state.inner(),
+            &VrCommand::SetTransform {
+                transform: updated_settings.vr_overlay_transform.clone(),
+            },
+        )?;
+    }
+
+    Ok(updated_settings)
+}
+
+#[tauri::command]
+fn set_vr_overlay_scale(
+    app_handle: AppHandle,
+    vr_state: tauri::State<SharedVrState>,
+    scale: f32,
+) -> Result<AppSettings, String> {
+    let updated_settings =
+        update_validated_settings(&app_handle, |settings| settings.vr_overlay_scale = scale)?;
+
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(
+            vr_state.inner(),
+            &VrCommand::SetScale {
+                scale: updated_settings.vr_overlay_scale,
+            },
+        )?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// 腕時計モード（手首を見たときだけ表示）の切り替え
+#[tauri::command]
+fn set_vr_overlay_gaze_mode(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.vr_overlay_gaze_activation = enabled;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(vr_state.inner(), &VrCommand::SetGazeMode { enabled })?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// 統計パネルの表示切り替え
+#[tauri::command]
+fn set_vr_overlay_stats_panel(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    let (updated_settings, stats) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.vr_overlay_stats_panel = enabled;
+        (state.settings.clone(), vr_stats_command(&state))
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(vr_state.inner(), &VrCommand::SetStatsPanel { enabled })?;
+        if enabled {
+            send_vr_command(vr_state.inner(), &stats)?;
+        }
+    }
+
+    Ok(updated_settings)
+}
+
+/// セーブコード記録時のオーバーレイ表示の切り替え
+#[tauri::command]
+fn set_vr_overlay_show_code(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    let (updated_settings, latest) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.vr_overlay_show_code = enabled;
+        (
+            state.settings.clone(),
+            state.data.history.last().map(vr_code_command),
+        )
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(vr_state.inner(), &VrCommand::SetCodePanel { enabled })?;
+        if let Some(latest) = latest.filter(|_| enabled) {
+            send_vr_command(vr_state.inner(), &latest)?;
+        }
+    }
+
+    Ok(updated_settings)
+}
+
+/// ラウンドタイマー表示の切り替え
+#[tauri::command]
+fn set_vr_overlay_round_timer(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.vr_overlay_round_timer = enabled;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    if vr_overlay_active(&app_handle) && !enabled {
+        send_vr_command(
+            vr_state.inner(),
+            &VrCommand::RoundTimer { elapsed_secs: None },
+        )?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// テラーのアイコン画像のフォルダを設定（Noneでアイコンを表示しない）
+#[tauri::command]
+fn set_vr_overlay_icon_dir(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    dir: Option<String>,
+) -> Result<AppSettings, String> {
+    let updated_settings =
+        update_validated_settings(&app_handle, |settings| settings.vr_overlay_icon_dir = dir)?;
+    let current_round = state
+        .lock()
+        .map_err(|_| "state lock failed")?
+        .current_round
+        .clone();
+
+    if vr_overlay_active(&app_handle) {
+        let options = vr_terror_options(&updated_settings);
+        send_current_round_to_vr(vr_state.inner(), &current_round, &options)?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// ウォッチリストを置き換える
+#[tauri::command]
+fn set_terror_watchlist(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    terror_ids: Vec<u32>,
+) -> Result<AppSettings, String> {
+    let mut terror_ids = terror_ids;
+    terror_ids.sort_unstable();
+    terror_ids.dedup();
+
+    let (updated_settings, current_round) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.terror_watchlist = terror_ids;
+        (state.settings.clone(), state.current_round.clone())
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    // 表示中のテラーの強調を更新
+    if vr_overlay_active(&app_handle) {
+        let options = vr_terror_options(&updated_settings);
+        send_current_round_to_vr(vr_state.inner(), &current_round, &options)?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// ネタバレ防止モードを切り替え（表示中のテラーにも反映）
+#[tauri::command]
+fn set_spoiler_mode(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    let (updated_settings, current_round) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.spoiler_mode = enabled;
+        (state.settings.clone(), state.current_round.clone())
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    if vr_overlay_active(&app_handle) {
+        let options = vr_terror_options(&updated_settings);
+        send_current_round_to_vr(vr_state.inner(), &current_round, &options)?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// 敵出現時の振動設定を変更（有効時は確認用に一度振動させる）
+#[tauri::command]
+fn set_vr_haptics(
+    app_handle: AppHandle,
+    vr_state: tauri::State<SharedVrState>,
+    haptics: VrHapticSettings,
+) -> Result<AppSettings, String> {
+    let updated_settings =
+        update_validated_settings(&app_handle, |settings| settings.vr_haptics = haptics)?;
+
+    if vr_overlay_active(&app_handle) {
+        if let Some(command) = haptic_command(&updated_settings) {
+            send_vr_command(vr_state.inner(), &command)?;
+        }
+    }
+
+    Ok(updated_settings)
+}
+
+/// 振動が有効であればHapticコマンドを作成
+fn haptic_command(settings: &AppSettings) -> Option<VrCommand> {
+    settings.vr_haptics.enabled.then(|| VrCommand::Haptic {
+        strength: settings.vr_haptics.strength,
+        pattern: settings.vr_haptics.pattern.clone(),
+    })
+}
+
+/// オーバーレイの配色・文字の大きさを変更
+#[tauri::command]
+fn set_vr_overlay_theme(
+    app_handle: AppHandle,
+    vr_state: tauri::State<SharedVrState>,
+    theme: VrOverlayTheme,
+) -> Result<AppSettings, String> {
+    let updated_settings =
+        update_validated_settings(&app_handle, |settings| settings.vr_overlay_theme = theme)?;
+
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(vr_state.inner(), &theme_command(&updated_settings))?;
+    }
+
+    Ok(updated_settings)
+}
+
+fn theme_command(settings: &AppSettings) -> VrCommand {
+    let theme = &settings.vr_overlay_theme;
+    VrCommand::SetTheme {
+        background_color: theme.background_color.clone(),
+        accent_color: theme.accent_color.clone(),
+        font_scale: theme.font_scale,
+    }
+}
+
+/// 統計・セーブコードパネルの配置を変更
+#[tauri::command]
+fn set_vr_overlay_panel(
+    app_handle: AppHandle,
+    vr_state: tauri::State<SharedVrState>,
+    panel: VrPanel,
+    layout: VrPanelSettings,
+) -> Result<AppSettings, String> {
+    let updated_settings = update_validated_settings(&app_handle, |settings| {
+        *settings.vr_overlay_panels.get_mut(panel) = layout;
+    })?;
+
+    if vr_overlay_active(&app_handle) {
+        let layout = updated_settings.vr_overlay_panels.get(panel);
+        send_vr_command(vr_state.inner(), &panel_layout_command(panel, layout))?;
+    }
+
+    Ok(updated_settings)
+}
+
+fn panel_layout_command(panel: VrPanel, layout: &VrPanelSettings) -> VrCommand {
+    VrCommand::SetPanelLayout {
+        panel,
+        separate: layout.separate,
+        position: layout.position.clone(),
+        pitch: layout.pitch,
+        transform: layout.transform.clone(),
+    }
+}
+
+#[tauri::command]
+fn set_vr_overlay_auto_hide(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    vr_state: tauri::State<SharedVrState>,
+    seconds: Option<u32>,
+) -> Result<AppSettings, String> {
+    // 0秒は無効扱い
+    let seconds = seconds.filter(|&s| s > 0);
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.vr_overlay_auto_hide_secs = seconds;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    if vr_overlay_active(&app_handle) {
+        send_vr_command(vr_state.inner(), &VrCommand::SetAutoHide { seconds })?;
+    }
+
+    Ok(updated_settings)
+}
+
+/// 自動で隠れたテラーパネルを再表示
+#[tauri::command]
+fn show_vr_overlay_panel(vr_state: tauri::State<SharedVrState>) -> Result<(), String> {
+    send_vr_command(vr_state.inner(), &VrCommand::Show)
+}
+
+/// メッセージの既定の表示秒数
+const VR_MESSAGE_DEFAULT_SECS: u32 = 5;
+
+/// VR内に短いメッセージを表示（フロントエンド・外部連携用）
+#[tauri::command]
+fn show_vr_message(
+    vr_state: tauri::State<SharedVrState>,
+    text: String,
+    duration_secs: Option<u32>,
+) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("message is empty".to_string());
+    }
+    send_vr_command(vr_state.inner(), &vr_message_command(text, duration_secs))
+}
+
+fn vr_message_command(text: impl Into<String>, duration_secs: Option<u32>) -> VrCommand {
+    VrCommand::ShowMessage {
+        text: text.into(),
+        duration_secs: duration_secs
+            .filter(|&s| s > 0)
+            .unwrap_or(VR_MESSAGE_DEFAULT_SECS),
+    }
+}
+
+/// VRオーバーレイの状態
+#[derive(Debug, Clone, Serialize)]
+struct VrOverlayStatus {
+    running: bool,
+    waiting_for_steamvr: bool,
+    pid: Option<u32>,
+    uptime_secs: Option<u64>,
+    restart_attempts: u32,
+    last_command: Option<String>,
+    last_error: Option<String>,
+}
+
+#[tauri::command]
+fn get_vr_overlay_status(vr_state: tauri::State<SharedVrState>) -> Result<VrOverlayStatus, String> {
+    let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+    let running = match state.process.as_mut() {
+        Some(child) => matches!(child.try_wait(), Ok(None)),
+        None => false,
+    };
+    Ok(VrOverlayStatus {
+        running,
+        waiting_for_steamvr: state.waiting_for_steamvr,
+        pid: state.process.as_ref().filter(|_| running).map(|c| c.id()),
+        uptime_secs: state
+            .last_started
+            .filter(|_| running)
+            .map(|t| t.elapsed().as_secs()),
+        restart_attempts: state.restart_attempts,
+        last_command: state.last_command.clone(),
+        last_error: state.last_error.clone(),
+    })
+}
+
+/// プレビュー用のサンプルテラー情報
+fn vr_preview_terrors() -> Vec<VrTerrorInfo> {
+    let ability = |label: &str, value: &str| VrTerrorAbility {
+        label: label.to_string(),
+        value: value.to_string(),
+    };
+    vec![
+        VrTerrorInfo {
+            name: "サンプルテラー A".to_string(),
+            color: Some("255, 80, 80".to_string()),
+            abilities: vec![
+                ability("速度", "速い"),
+                ability("特徴", "プレビュー表示用のサンプルです"),
+            ],
+            icon: None,
+            highlighted: false,
+        },
+        VrTerrorInfo {
+            name: "サンプルテラー B".to_string(),
+            color: Some("120, 200, 255".to_string()),
+            abilities: vec![
+                ability("速度", "普通"),
+                ability("スタン", "有効"),
+                ability(
+                    "対策",
+                    "長い説明文がどのように折り返されるかを確認するための文章です",
+                ),
+            ],
+            icon: None,
+            highlighted: false,
+        },
+        VrTerrorInfo {
+            name: "サンプルテラー C".to_string(),
+            color: None,
+            abilities: Vec::new(),
+            icon: None,
+            highlighted: false,
+        },
+    ]
+}
+
+/// サンプルデータをVRオーバーレイに表示（位置・サイズ調整用）
+#[tauri::command]
+fn preview_vr_overlay(vr_state: tauri::State<SharedVrState>) -> Result<(), String> {
+    {
+        let state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+        if state.process.is_none() {
+            return Err("VR overlay is not running".to_string());
+        }
+    }
+    send_vr_command(
+        vr_state.inner(),
+        &VrCommand::UpdateTerrors {
+            terrors: vr_preview_terrors(),
+            round_type: "Classic".to_string(),
+        },
+    )
+}
+
+// ============ テラーデータコマンド ============
+
+#[tauri::command]
+fn get_terror_info(id: u32, round_type: String) -> TerrorDataResponse {
+    let data = get_terror_data(id, &round_type);
+    data.into()
+}
+
+#[tauri::command]
+fn get_terrors_info(killer_ids: Vec<u32>, round_type: String) -> Vec<TerrorDataResponse> {
+    get_terrors_data(&killer_ids, &round_type)
+        .into_iter()
+        .map(|d| d.into())
+        .collect()
+}
+
+/// 図鑑で一覧にするラウンドタイプ（敵の表が異なるもの）
+const CATALOGUE_ROUND_TYPES: &[&str] = &[
+    "クラシック",
+    "オルタネイト",
+    "アンバウンド",
+    "ミスティックムーン",
+    "ブラッドムーン",
+    "トワイライト",
+    "ソルスティス",
+];
+/// 図鑑で調べる敵IDの上限
+const MAX_TERROR_ID: u32 = 255;
+
+/// 図鑑の1項目
+#[derive(Debug, Clone, Serialize)]
+struct TerrorCatalogEntry {
+    id: u32,
+    round_type: String,
+    #[serde(flatten)]
+    data: TerrorDataResponse,
+}
+
+/// ラウンドタイプの敵をすべて取得（未登録のIDは除く）
+fn terror_catalogue(round_type: &str) -> Vec<TerrorCatalogEntry> {
+    // Moon系は固定の1体のみ
+    if let Some(moon_id) = get_moon_terror_index(round_type) {
+        return vec![TerrorCatalogEntry {
+            id: moon_id,
+            round_type: round_type.to_string(),
+            data: get_terror_data(moon_id, round_type).into(),
+        }];
+    }
+    // 範囲外のIDで返る名前を未登録の目印にする
+    let unknown = get_terror_data(u32::MAX, round_type).name;
+    (0..=MAX_TERROR_ID)
+        .map(|id| (id, get_terror_data(id, round_type)))
+        .filter(|(_, data)| !data.name.is_empty() && data.name != unknown)
+        .map(|(id, data)| TerrorCatalogEntry {
+            id,
+            round_type: round_type.to_string(),
+            data: data.into(),
+        })
+        .collect()
+}
+
+/// テラー図鑑（ラウンドタイプ未指定なら全種類、searchは名前の部分一致）
+#[tauri::command]
+fn get_all_terrors(round_type: Option<String>, search: Option<String>) -> Vec<TerrorCatalogEntry> {
+    let round_types: Vec<&str> = match round_type.as_deref() {
+        Some(round_type) => vec![round_type],
+        None => CATALOGUE_ROUND_TYPES.to_vec(),
+    };
+    let search = search
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+
+    round_types
+        .into_iter()
+        .flat_map(terror_catalogue)
+        .filter(|entry| {
+            search
+                .as_ref()
+                .is_none_or(|s| entry.data.name.to_lowercase().contains(s))
+        })
+        .collect()
+}
+
+// ============ アップデート確認 ============
+
+/// 起動してから最初に確認するまでの待ち時間
+const UPDATE_CHECK_STARTUP_DELAY: Duration = Duration::from_secs(10);
+/// 毎日確認する場合の間隔
+const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 公開されている新しいバージョン
+#[derive(Debug, Clone, Serialize)]
+struct UpdateInfo {
+    version: String,
+    current_version: String,
+    body: Option<String>,
+    date: Option<String>,
+}
+
+/// アップデートを確認し、新しいバージョンがあれば`update_available`を発行する
+async fn check_update(app_handle: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let update = app_handle
+        .updater()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?;
+    let info = update.map(|update| UpdateInfo {
+        version: update.version,
+        current_version: update.current_version,
+        body: update.body,
+        date: update.date.map(|date| date.to_string()),
+    });
+    if let Some(info) = &info {
+        println!("[tsst] Update available: {}", info.version);
+        let _ = app_handle.emit("update_available", info);
+    }
+    Ok(info)
+}
+
+#[tauri::command]
+async fn check_for_updates(app_handle: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    check_update(&app_handle).await
+}
+
+#[tauri::command]
+fn set_auto_update_check(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    schedule: AutoUpdateCheck,
+) -> Result<AppSettings, String> {
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.auto_update_check = schedule;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    Ok(updated_settings)
+}
+
+/// 設定に従ってアップデートを確認するスレッドを開始
+fn start_update_checker(app_handle: AppHandle, state: SharedState) {
+    std::thread::spawn(move || {
+        std::thread::sleep(UPDATE_CHECK_STARTUP_DELAY);
+        let mut checked_at: Option<Instant> = None;
+        loop {
+            let schedule = {
+                let state = state.lock().expect("state lock");
+                state.settings.auto_update_check
+            };
+            let due = match schedule {
+                AutoUpdateCheck::OnStart => checked_at.is_none(),
+                AutoUpdateCheck::Daily => {
+                    checked_at.is_none_or(|t| t.elapsed() >= UPDATE_CHECK_INTERVAL)
+                }
+                AutoUpdateCheck::Never => false,
+            };
+            if due {
+                checked_at = Some(Instant::now());
+                // 開発ビルドなどでは確認できないので、ログに出すだけにする
+                if let Err(e) = tauri::async_runtime::block_on(check_update(&app_handle)) {
+                    println!("[tsst] Update check failed: {}", e);
+                }
+            }
+            std::thread::sleep(Duration::from_secs(60));
+        }
+    });
+}
+
+// ============ フォルダを開く ============
+
+/// フォルダをエクスプローラーで開く（なければ作成する）
+fn open_dir(app_handle: &AppHandle, dir: PathBuf) -> Result<(), String> {
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    app_handle
+        .opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+/// data.jsonのあるフォルダを開く
+#[tauri::command]
+fn open_data_dir(app_handle: AppHandle) -> Result<(), String> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    open_dir(&app_handle, dir)
+}
+
+/// settings.jsonのあるフォルダを開く
+#[tauri::command]
+fn open_config_dir(app_handle: AppHandle) -> Result<(), String> {
+    let dir = app_handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| e.to_string())?;
+    open_dir(&app_handle, dir)
+}
+
+/// 監視しているVRChatのログフォルダを開く
+#[tauri::command]
+fn open_vrchat_log_dir(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+) -> Result<(), String> {
+    let dir = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        get_effective_log_dir(&state.settings).ok_or("log dir not found")?
+    };
+    if !dir.is_dir() {
+        return Err(format!("log dir does not exist: {}", dir.display()));
+    }
+    app_handle
+        .opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+// ============ ログ診断 ============
+
+/// tail_vrchat_logで返す最大行数
+const MAX_TAIL_LINES: usize = 1000;
+/// 1行あたりに見込むバイト数（末尾から読む範囲の目安）
+const TAIL_BYTES_PER_LINE: u64 = 1024;
+
+/// 最新のVRChatログの末尾の行を返す（検出の不具合を調べる用）
+#[tauri::command]
+fn tail_vrchat_log(state: tauri::State<SharedState>, lines: usize) -> Result<Vec<String>, String> {
+    let lines = lines.clamp(1, MAX_TAIL_LINES);
+    let path = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        let dir = get_effective_log_dir(&state.settings).ok_or("log dir not found")?;
+        find_latest_log_file(&dir).ok_or("log file not found")?
+    };
+
+    let mut file = File::open(&path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let start = len.saturating_sub(lines as u64 * TAIL_BYTES_PER_LINE);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| e.to_string())?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
+
+    let content = String::from_utf8_lossy(&buffer);
+    let mut all: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    // 途中から読んだ場合、先頭の行は欠けているので捨てる
+    if start > 0 && !all.is_empty() {
+        all.remove(0);
+    }
+    let skip = all.len().saturating_sub(lines);
+    Ok(all[skip..].iter().map(|l| l.to_string()).collect())
+}
+
+/// 検出対象のログ行を`raw_log_line`イベントで配信するか切り替える
+#[tauri::command]
+fn set_raw_log_stream(state: tauri::State<SharedState>, enabled: bool) -> Result<bool, String> {
+    let mut state = state.lock().map_err(|_| "state lock failed")?;
+    state.raw_log_stream = enabled;
+    Ok(enabled)
+}
+
+// ============ デスクトップオーバーレイ ============
+
+const DESKTOP_OVERLAY_LABEL: &str = "desktop-overlay";
+
+/// デスクトップオーバーレイのウィンドウを開く（開いていれば設定を反映する）
+fn open_desktop_overlay(
+    app_handle: &AppHandle,
+    settings: &DesktopOverlaySettings,
+) -> Result<(), String> {
+    let window = match app_handle.get_webview_window(DESKTOP_OVERLAY_LABEL) {
+        Some(window) => window,
+        None => tauri::WebviewWindowBuilder::new(
+            app_handle,
+            DESKTOP_OVERLAY_LABEL,
+            tauri::WebviewUrl::App("index.html#/desktop-overlay".into()),
+        )
+        .title("ToN Terror Overlay")
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .shadow(false)
+        .visible(false)
+        .build()
+        .map_err(|e| e.to_string())?,
+    };
+
+    window
+        .set_size(PhysicalSize::new(settings.width, settings.height))
+        .map_err(|e| e.to_string())?;
+    let positioned = match (settings.x, settings.y) {
+        (Some(x), Some(y)) => window.set_position(PhysicalPosition::new(x, y)),
+        _ => window.center(),
+    };
+    positioned.map_err(|e| e.to_string())?;
+    window
+        .set_ignore_cursor_events(settings.click_through)
+        .map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn show_desktop_overlay(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+) -> Result<AppSettings, String> {
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.desktop_overlay.enabled = true;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    open_desktop_overlay(&app_handle, &updated_settings.desktop_overlay)?;
+    Ok(updated_settings)
+}
+
+#[tauri::command]
+fn hide_desktop_overlay(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+) -> Result<AppSettings, String> {
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.desktop_overlay.enabled = false;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    if let Some(window) = app_handle.get_webview_window(DESKTOP_OVERLAY_LABEL) {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    Ok(updated_settings)
+}
+
+/// クリック透過を切り替え（オフの間はドラッグで移動・サイズ変更できる）
+#[tauri::command]
+fn set_desktop_overlay_click_through(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.desktop_overlay.click_through = enabled;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    if let Some(window) = app_handle.get_webview_window(DESKTOP_OVERLAY_LABEL) {
+        window
+            .set_ignore_cursor_events(enabled)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(updated_settings)
+}
+
+// ============ ウィンドウの位置・サイズ ============
+
+/// 移動・サイズ変更が続いている間は保存せず、止まってからこの時間後に保存する
+const WINDOW_BOUNDS_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// 位置とサイズを覚えておくウィンドウの設定
+trait WindowBounds {
+    fn set_bounds(&mut self, position: PhysicalPosition<i32>, size: PhysicalSize<u32>);
+}
+
+impl WindowBounds for DesktopOverlaySettings {
+    fn set_bounds(&mut self, position: PhysicalPosition<i32>, size: PhysicalSize<u32>) {
+        self.x = Some(position.x);
+        self.y = Some(position.y);
+        self.width = size.width;
+        self.height = size.height;
+    }
+}
+
+impl WindowBounds for StatsWindowSettings {
+    fn set_bounds(&mut self, position: PhysicalPosition<i32>, size: PhysicalSize<u32>) {
+        self.x = Some(position.x);
+        self.y = Some(position.y);
+        self.width = size.width;
+        self.height = size.height;
+    }
+}
+
+impl WindowBounds for CompactModeSettings {
+    fn set_bounds(&mut self, position: PhysicalPosition<i32>, size: PhysicalSize<u32>) {
+        self.x = Some(position.x);
+        self.y = Some(position.y);
+        self.width = size.width;
+        self.height = size.height;
+    }
+}
+
+/// ウィンドウのラベルに対応する位置・サイズの設定（覚えておかないウィンドウは`None`）
+fn window_bounds<'a>(
+    settings: &'a mut AppSettings,
+    label: &str,
+) -> Option<&'a mut dyn WindowBounds> {
+    match label {
+        DESKTOP_OVERLAY_LABEL => Some(&mut settings.desktop_overlay),
+        STATS_WINDOW_LABEL => Some(&mut settings.stats_window),
+        // メインウィンドウはコンパクト表示中の位置とサイズだけを覚える
+        "main" if settings.compact_mode.enabled => Some(&mut settings.compact_mode),
+        _ => None,
+    }
+}
+
+/// 移動・サイズ変更されたウィンドウの位置とサイズを設定に反映し、少し待ってから保存する
+fn save_window_bounds(window: &tauri::Window) {
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let app_handle = window.app_handle().clone();
+    let changes = {
+        let state = app_handle.state::<SharedState>();
+        let Ok(mut state) = state.lock() else {
+            return;
+        };
+        let Some(bounds) = window_bounds(&mut state.settings, window.label()) else {
+            return;
+        };
+        bounds.set_bounds(position, size);
+        state.window_bounds_changes += 1;
+        state.window_bounds_changes
+    };
+    std::thread::spawn(move || {
+        std::thread::sleep(WINDOW_BOUNDS_SAVE_DELAY);
+        let updated_settings = {
+            let state = app_handle.state::<SharedState>();
+            let Ok(state) = state.lock() else {
+                return;
+            };
+            // 待っている間にまた動いた場合は、その変更の保存に任せる
+            if state.window_bounds_changes != changes {
+                return;
+            }
+            state.settings.clone()
+        };
+        if let Err(e) = persist_settings(&app_handle, &updated_settings) {
+            app_errors::report(
+                &app_handle,
+                Severity::Error,
+                format!("Failed to save settings: {}", e),
+            );
+        }
+    });
+}
+
+// ============ 統計ウィンドウ ============
+
+const STATS_WINDOW_LABEL: &str = "stats-window";
+
+/// 統計ウィンドウを開く（メインウィンドウと同じ状態イベントで更新される）
+#[tauri::command]
+fn open_stats_window(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+) -> Result<(), String> {
+    let settings = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.stats_window.clone()
+    };
+
+    let window = match app_handle.get_webview_window(STATS_WINDOW_LABEL) {
+        Some(window) => window,
+        None => {
+            let window = tauri::WebviewWindowBuilder::new(
+                &app_handle,
+                STATS_WINDOW_LABEL,
+                tauri::WebviewUrl::App("index.html#/stats".into()),
+            )
+            .title("ToN Stats")
+            .visible(false)
+            .build()
+            .map_err(|e| e.to_string())?;
+            window
+                .set_size(PhysicalSize::new(settings.width, settings.height))
+                .map_err(|e| e.to_string())?;
+            let positioned = match (settings.x, settings.y) {
+                (Some(x), Some(y)) => window.set_position(PhysicalPosition::new(x, y)),
+                _ => window.center(),
+            };
+            positioned.map_err(|e| e.to_string())?;
+            window
+        }
+    };
+
+    window
+        .set_always_on_top(settings.always_on_top)
+        .map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+// ============ コンパクト表示 ============
+
+/// 通常表示に戻すときの既定サイズ（tauri.conf.jsonと同じ）
+const MAIN_WINDOW_SIZE: tauri::LogicalSize<f64> = tauri::LogicalSize {
+    width: 1000.0,
+    height: 680.0,
+};
+
+/// メインウィンドウをコンパクト表示／通常表示に切り替える
+fn apply_compact_mode(
+    window: &tauri::WebviewWindow,
+    settings: &CompactModeSettings,
+    normal_bounds: Option<(PhysicalPosition<i32>, PhysicalSize<u32>)>,
+) -> Result<(), String> {
+    if settings.enabled {
+        window.set_always_on_top(true).map_err(|e| e.to_string())?;
+        window
+            .set_size(PhysicalSize::new(settings.width, settings.height))
+            .map_err(|e| e.to_string())?;
+        let position = match (settings.x, settings.y) {
+            (Some(x), Some(y)) => Some(PhysicalPosition::new(x, y)),
+            // 初回は画面右上に置く
+            _ => window.current_monitor().ok().flatten().map(|monitor| {
+                let monitor_position = monitor.position();
+                let monitor_size = monitor.size();
+                PhysicalPosition::new(
+                    monitor_position.x + monitor_size.width as i32 - settings.width as i32 - 16,
+                    monitor_position.y + 16,
+                )
+            }),
+        };
+        if let Some(position) = position {
+            window.set_position(position).map_err(|e| e.to_string())?;
+        }
+    } else {
+        window.set_always_on_top(false).map_err(|e| e.to_string())?;
+        match normal_bounds {
+            Some((position, size)) => {
+                window.set_size(size).map_err(|e| e.to_string())?;
+                window.set_position(position).map_err(|e| e.to_string())?;
+            }
+            None => {
+                window
+                    .set_size(MAIN_WINDOW_SIZE)
+                    .map_err(|e| e.to_string())?;
+                window.center().map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// コンパクト表示を切り替え、切り替え後の設定を返す
+#[tauri::command]
+fn toggle_compact_mode(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+) -> Result<AppSettings, String> {
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or("main window not found")?;
+
+    let (updated_settings, normal_bounds) = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        let enabled = !state.settings.compact_mode.enabled;
+        if enabled {
+            // 戻すときのために通常表示の位置とサイズを覚えておく
+            if let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) {
+                state.normal_window_bounds = Some((position, size));
+            }
+        }
+        state.settings.compact_mode.enabled = enabled;
+        (state.settings.clone(), state.normal_window_bounds)
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+
+    apply_compact_mode(&window, &updated_settings.compact_mode, normal_bounds)?;
+    let enabled = updated_settings.compact_mode.enabled;
+    let _ = app_handle.emit("compact_mode_changed", enabled);
+    Ok(updated_settings)
+}
+
+// ============ VRオーバーレイ管理 ============
+
+/// VRオーバーレイに送信するテラー情報
+#[derive(Debug, Clone, Serialize)]
+struct VrTerrorInfo {
+    name: String,
+    color: Option<String>,
+    abilities: Vec<VrTerrorAbility>,
+    /// PNGアイコン（base64）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    /// ウォッチリストに登録されたテラー
+    highlighted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct VrTerrorAbility {
+    label: String,
+    value: String,
+}
+
+impl From<TerrorData> for VrTerrorInfo {
+    fn from(data: TerrorData) -> Self {
+        VrTerrorInfo {
+            name: data.name,
+            color: data.color,
+            abilities: data
+                .abilities
+                .into_iter()
+                .map(|a| VrTerrorAbility {
+                    label: a.label,
+                    value: a.value,
+                })
+                .collect(),
+            icon: None,
+            highlighted: false,
+        }
+    }
+}
+
+/// UpdateTerrorsコマンドの作成に使う設定
+struct VrTerrorOptions {
+    icon_dir: Option<PathBuf>,
+    watchlist: Vec<u32>,
+    spoiler_mode: bool,
+}
+
+fn vr_terror_options(settings: &AppSettings) -> VrTerrorOptions {
+    VrTerrorOptions {
+        icon_dir: settings.vr_overlay_icon_dir.as_ref().map(PathBuf::from),
+        watchlist: settings.terror_watchlist.clone(),
+        spoiler_mode: settings.spoiler_mode,
+    }
+}
+
+/// テラーを特定できる情報（名前・能力・アイコン）を伏せる
+fn redact_terror_info(info: &mut VrTerrorInfo, index: usize) {
+    info.name = format!("??? #{}", index + 1);
+    info.abilities.clear();
+    info.icon = None;
+}
+
+/// 1枚あたりのアイコンの上限サイズ（IPCフレームを肥大化させない）
+const MAX_TERROR_ICON_BYTES: u64 = 256 * 1024;
+
+/// アイコンのフォルダの`<ラウンドタイプ>/<ID>.png`、なければ`<ID>.png`を読み込む
+/// （特殊ラウンドは同じIDでも別のテラーになるため）
+///
+/// 画像は同梱していないため、ユーザーが用意したものを使う
+fn load_terror_icon(dir: &Path, id: u32, round_type: &str) -> Option<String> {
+    let file_name = format!("{}.png", id);
+    [
+        dir.join(round_type_to_english(round_type)).join(&file_name),
+        dir.join(&file_name),
+    ]
+    .into_iter()
+    .find(|path| {
+        fs::metadata(path)
+            .map(|m| m.is_file() && m.len() <= MAX_TERROR_ICON_BYTES)
+            .unwrap_or(false)
+    })
+    .and_then(|path| fs::read(path).ok())
+    .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+enum VrCommand {
+    #[serde(rename = "update_terrors")]
+    UpdateTerrors {
+        terrors: Vec<VrTerrorInfo>,
+        round_type: String,
+    },
+    #[serde(rename = "set_position")]
+    SetPosition {
+        position: VrOverlayPosition,
+        pitch: f32,
+    },
+    #[serde(rename = "set_transform")]
+    SetTransform { transform: VrOverlayTransform },
+    #[serde(rename = "set_scale")]
+    SetScale { scale: f32 },
+    #[serde(rename = "set_gaze_mode")]
+    SetGazeMode { enabled: bool },
+    #[serde(rename = "set_stats_panel")]
+    SetStatsPanel { enabled: bool },
+    #[serde(rename = "update_stats")]
+    UpdateStats {
+        current_streak: u32,
+        session_survival_rate: f64,
+        session_rounds: u32,
+    },
+    /// 記録したセーブコード（先頭のみ）を表示
+    #[serde(rename = "show_code")]
+    ShowCode { code: String, timestamp: String },
+    /// セーブコードの表示の切り替え
+    #[serde(rename = "set_code_panel")]
+    SetCodePanel { enabled: bool },
+    /// ラウンド経過秒数（Noneでタイマー非表示）
+    #[serde(rename = "round_timer")]
+    RoundTimer { elapsed_secs: Option<u32> },
+    /// コントローラーを振動させる
+    #[serde(rename = "haptic")]
+    Haptic {
+        strength: f32,
+        pattern: HapticPattern,
+    },
+    /// 統計・セーブコードパネルの配置
+    #[serde(rename = "set_panel_layout")]
+    SetPanelLayout {
+        panel: VrPanel,
+        separate: bool,
+        position: VrOverlayPosition,
+        pitch: f32,
+        transform: VrOverlayTransform,
+    },
+    /// 配色と文字の大きさ
+    #[serde(rename = "set_theme")]
+    SetTheme {
+        background_color: String,
+        accent_color: String,
+        font_scale: f32,
+    },
+    #[serde(rename = "set_auto_hide")]
+    SetAutoHide { seconds: Option<u32> },
+    /// 一定時間だけ表示する通知メッセージ
+    #[serde(rename = "show_message")]
+    ShowMessage { text: String, duration_secs: u32 },
+    /// 自動で隠したパネルを再表示
+    #[serde(rename = "show")]
+    Show,
+    /// 表示中なら隠し、隠れていれば現在の内容を表示する
+    #[serde(rename = "toggle_visibility")]
+    ToggleVisibility,
+    #[serde(rename = "clear")]
+    Clear,
+    #[serde(rename = "quit")]
+    Quit,
+}
+
+impl VrCommand {
+    /// コマンドの種類名（sidecarに送る`type`と同じ）
+    fn kind(&self) -> &'static str {
+        match self {
+            VrCommand::UpdateTerrors { .. } => "update_terrors",
+            VrCommand::SetPosition { .. } => "set_position",
+            VrCommand::SetTransform { .. } => "set_transform",
+            VrCommand::SetScale { .. } => "set_scale",
+            VrCommand::SetGazeMode { .. } => "set_gaze_mode",
+            VrCommand::SetStatsPanel { .. } => "set_stats_panel",
+            VrCommand::UpdateStats { .. } => "update_stats",
+            VrCommand::ShowCode { .. } => "show_code",
+            VrCommand::SetCodePanel { .. } => "set_code_panel",
+            VrCommand::RoundTimer { .. } => "round_timer",
+            VrCommand::Haptic { .. } => "haptic",
+            VrCommand::SetPanelLayout { .. } => "set_panel_layout",
+            VrCommand::SetTheme { .. } => "set_theme",
+            VrCommand::SetAutoHide { .. } => "set_auto_hide",
+            VrCommand::ShowMessage { .. } => "show_message",
+            VrCommand::Show => "show",
+            VrCommand::ToggleVisibility => "toggle_visibility",
+            VrCommand::Clear => "clear",
+            VrCommand::Quit => "quit",
+        }
+    }
+}
+
+/// SteamVRが起動しているかどうかを確認する（vrserver.exeプロセスの存在チェック）
+fn is_steamvr_running() -> bool {
+    is_process_running("vrserver.exe")
+}
+
+/// VRChatが起動しているかどうかを確認する
+fn is_vrchat_running() -> bool {
+    is_process_running("VRChat.exe")
+}
+
+/// 指定した実行ファイル名のプロセスが存在するか（大文字小文字を無視）
+#[cfg(windows)]
+fn is_process_running(exe: &str) -> bool {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        return false;
+    }
+
+    let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+    entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+
+    let target_exe: Vec<u16> = OsStr::new(exe)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut found = false;
+    unsafe {
+        if Process32FirstW(snapshot, &mut entry) != 0 {
+            loop {
+                // szExeFileをnull終端の文字列として比較
+                let exe_name_len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                let exe_name = &entry.szExeFile[..exe_name_len];
+
+                // 大文字小文字を無視して比較
+                let target_len = target_exe.len() - 1; // null終端を除く
+                if exe_name.len() == target_len {
+                    let matches = exe_name.iter().zip(target_exe.iter()).all(|(&a, &b)| {
+                        // ASCII大文字を小文字に変換して比較
+                        let a_lower = if a >= 'A' as u16 && a <= 'Z' as u16 {
+                            a + 32
+                        } else {
+                            a
+                        };
+                        let b_lower = if b >= 'A' as u16 && b <= 'Z' as u16 {
+                            b + 32
+                        } else {
+                            b
+                        };
+                        a_lower == b_lower
+                    });
+                    if matches {
+                        found = true;
+                        break;
+                    }
+                }
+
+                if Process32NextW(snapshot, &mut entry) == 0 {
+                    break;
+                }
+            }
+        }
+        CloseHandle(snapshot);
+    }
+
+    found
+}
+
+#[cfg(not(windows))]
+fn is_process_running(_exe: &str) -> bool {
+    // 非Windows環境では常にtrueを返す（未実装）
+    true
+}
+
+#[cfg(windows)]
+fn assign_process_to_job_object(
+    process_handle: windows_sys::Win32::Foundation::HANDLE,
+) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::JobObjects::*;
+
+    unsafe {
+        // ジョブオブジェクトを作成
+        let job_handle: HANDLE = CreateJobObjectW(std::ptr::null_mut(), std::ptr::null());
+        if job_handle.is_null() || job_handle == INVALID_HANDLE_VALUE {
+            return Err("Failed to create job object".to_string());
+        }
+
+        // ジョブオブジェクトの制限を設定（親プロセスが終了したら子プロセスも終了）
+        let job_info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION {
+            BasicLimitInformation: JOBOBJECT_BASIC_LIMIT_INFORMATION {
+                LimitFlags: JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+                ..std::mem::zeroed()
+            },
+            ..std::mem::zeroed()
+        };
+
+        let result = SetInformationJobObject(
+            job_handle,
+            JobObjectExtendedLimitInformation,
+            &job_info as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        );
+
+        if result == 0 {
+            CloseHandle(job_handle);
+            return Err("Failed to set job object information".to_string());
+        }
+
+        // プロセスをジョブオブジェクトに割り当て
+        let result = AssignProcessToJobObject(job_handle, process_handle);
+        if result == 0 {
+            CloseHandle(job_handle);
+            return Err("Failed to assign process to job object".to_string());
+        }
+
+        // ジョブハンドルは意図的にクローズしない
+        // （プログラム終了時に自動的にクリーンアップされ、その際にプロセスがkillされる）
+        // CloseHandle(job_handle);
+
+        println!("[tsst] VR overlay process assigned to job object");
+    }
+
+    Ok(())
+}
+
+fn get_vr_overlay_path(app_handle: &AppHandle, settings: &AppSettings) -> Option<PathBuf> {
+    // 設定で指定されたバイナリを優先
+    if let Some(ref custom) = settings.overlay_binary_path {
+        let custom_path = PathBuf::from(custom);
+        if custom_path.is_file() {
+            println!("[tsst] Using custom VR overlay at: {:?}", custom_path);
+            return Some(custom_path);
+        }
+        println!(
+            "[tsst] Custom VR overlay not found at: {:?}, falling back to bundled binary",
+            custom_path
+        );
+    }
+
+    // ビルド時: アプリと同じディレクトリにvr-overlay.exeとして配置される
+    // 開発時: target/debug/vr-overlay.exe または binaries/vr-overlay-xxx.exe
+
+    // まずアプリの実行ファイルと同じディレクトリを確認
+    if let Ok(exe_path) = std::env::current_exe() {
+        if let Some(exe_dir) = exe_path.parent() {
+            let prod_path = exe_dir.join("vr-overlay.exe");
+            if prod_path.exists() {
+                println!("[tsst] Found VR overlay at: {:?}", prod_path);
+                return Some(prod_path);
+            }
+        }
+    }
+
+    // バンドル/開発共通: resource_dir 直下と resource_dir/binaries を確認
+    if let Ok(resource_dir) = app_handle.path().resource_dir() {
+        let candidates = if cfg!(target_os = "windows") {
+            vec![
+                resource_dir.join("vr-overlay.exe"),
+                resource_dir.join("binaries").join("vr-overlay.exe"),
+                resource_dir
+                    .join("binaries")
+                    .join("vr-overlay-x86_64-pc-windows-msvc.exe"),
+            ]
+        } else {
+            vec![
+                resource_dir.join("vr-overlay"),
+                resource_dir.join("binaries").join("vr-overlay"),
+            ]
+        };
+
+        for candidate in candidates {
+            if candidate.exists() {
+                println!("[tsst] Found VR overlay at: {:?}", candidate);
+                return Some(candidate);
+            } else {
+                println!("[tsst] VR overlay not found at: {:?}", candidate);
+            }
+        }
+    }
+
+    // 念のため: BaseDirectory::Resource で解決
+    if let Ok(resolved) = app_handle
+        .path()
+        .resolve("vr-overlay.exe", BaseDirectory::Resource)
+    {
+        if resolved.exists() {
+            println!("[tsst] Found VR overlay at: {:?}", resolved);
+            return Some(resolved);
+        }
+    }
+
+    println!("[tsst] VR overlay binary not found");
+    None
+}
+
+/// 起動したオーバーレイからのIPC接続待ち
+struct PendingVrConnection {
+    listener: std::net::TcpListener,
+    ipc_port: u16,
+    generation: u64,
+}
+
+/// VRオーバーレイを起動する（接続は別スレッドで待ち、完了したら`vr_overlay_connected`を発行する）
+fn start_vr_overlay(
+    app_handle: &AppHandle,
+    vr_state: &SharedVrState,
+    settings: &AppSettings,
+) -> Result<(), String> {
+    let Some(pending) = spawn_vr_overlay(app_handle, vr_state, settings)? else {
+        return Ok(());
+    };
+    let app_handle = app_handle.clone();
+    let vr_state = vr_state.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = connect_vr_overlay(&app_handle, &vr_state, pending) {
+            app_errors::report(
+                &app_handle,
+                Severity::Error,
+                format!("Failed to start VR overlay: {}", e),
+            );
+        }
+    });
+    Ok(())
+}
+
+/// VRオーバーレイを起動し、接続が完了するまで待つ（監視スレッドから使う）
+fn start_vr_overlay_blocking(
+    app_handle: &AppHandle,
+    vr_state: &SharedVrState,
+    settings: &AppSettings,
+) -> Result<(), String> {
+    match spawn_vr_overlay(app_handle, vr_state, settings)? {
+        Some(pending) => connect_vr_overlay(app_handle, vr_state, pending),
+        None => Ok(()),
+    }
+}
+
+/// オーバーレイのプロセスを起動する（既に起動している・SteamVRを待つ場合は`None`）
+fn spawn_vr_overlay(
+    app_handle: &AppHandle,
+    vr_state: &SharedVrState,
+    settings: &AppSettings,
+) -> Result<Option<PendingVrConnection>, String> {
+    let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+
+    // 既に起動している場合は何もしない
+    if state.process.is_some() {
+        return Ok(None);
+    }
+
+    // デスクトップモードでは起動せず、SteamVRの起動を待つ（start_steamvr_monitorが再試行する）
+    if !is_steamvr_running() {
+        if !state.waiting_for_steamvr {
+            println!("[tsst] SteamVR not running, waiting for SteamVR to start...");
+        }
+        state.waiting_for_steamvr = true;
+        return Ok(None);
+    }
+    state.waiting_for_steamvr = false;
+
+    let Some(binary_path) = get_vr_overlay_path(app_handle, settings) else {
+        let error = "VR overlay binary not found".to_string();
+        state.last_error = Some(error.clone());
+        return Err(error);
+    };
+
+    let position_arg = match settings.vr_overlay_position {
+        VrOverlayPosition::RightHand => "right",
+        VrOverlayPosition::LeftHand => "left",
+        VrOverlayPosition::Above => "above",
+        VrOverlayPosition::Head => "head",
+    };
+
+    println!(
+        "[tsst] Starting VR overlay: {:?} --position {}",
+        binary_path, position_arg
+    );
+
+    let listener = match vr_ipc::bind() {
+        Ok(listener) => listener,
+        Err(error) => {
+            state.last_error = Some(error.clone());
+            return Err(error);
+        }
+    };
+    let ipc_port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to bind VR overlay IPC: {}", e))?
+        .port();
+
+    // sidecarと同じディレクトリをカレントディレクトリに設定（DLLを見つけるため）
+    let working_dir = binary_path.parent().unwrap_or(Path::new("."));
+
+    let mut command = Command::new(&binary_path);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = match command
+        .current_dir(working_dir)
+        .arg("--position")
+        .arg(position_arg)
+        .arg("--ipc-port")
+        .arg(ipc_port.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            let error = format!("Failed to start VR overlay: {}", e);
+            state.last_error = Some(error.clone());
+            return Err(error);
+        }
+    };
+
+    // Windowsの場合、子プロセスをジョブオブジェクトに割り当てる
+    // これにより、親プロセス（Tauriアプリ）がクラッシュやタスクキルされても
+    // 子プロセス（VRオーバーレイ）が自動的に終了する
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsRawHandle;
+        let process_handle = child.as_raw_handle() as windows_sys::Win32::Foundation::HANDLE;
+        if let Err(e) = assign_process_to_job_object(process_handle) {
+            println!("[tsst] Warning: Failed to assign to job object: {}", e);
+            // 失敗してもプロセスは起動しているので、継続する
+        }
+    }
+
+    // stdout/stderrは同じファイルに書くのでローテーションも共有する
+    let log = Arc::new(Mutex::new(RotatingLog::open(overlay_log_path(app_handle))));
+    if let Some(stdout) = child.stdout.take() {
+        spawn_overlay_log_reader(app_handle.clone(), log.clone(), stdout, "stdout");
+    }
+    if let Some(stderr) = child.stderr.take() {
+        spawn_overlay_log_reader(app_handle.clone(), log, stderr, "stderr");
+    }
+    state.process = Some(child);
+    state.generation += 1;
+    state.last_started = Some(Instant::now());
+    let generation = state.generation;
+    drop(state);
+
+    spawn_vr_overlay_watchdog(app_handle.clone(), vr_state.clone(), generation);
+
+    Ok(Some(PendingVrConnection {
+        listener,
+        ipc_port,
+        generation,
+    }))
+}
+
+/// 起動したオーバーレイの接続を待ち、設定と現在のラウンドを送る（完了まで戻らない）
+fn connect_vr_overlay(
+    app_handle: &AppHandle,
+    vr_state: &SharedVrState,
+    pending: PendingVrConnection,
+) -> Result<(), String> {
+    // 接続待ちの間は他のコマンド送信をブロックしないようにロックを持たない
+    let event_handle = app_handle.clone();
+    let connected = vr_ipc::accept(&pending.listener, VR_CONNECT_TIMEOUT, move |event| {
+        handle_vr_overlay_event(&event_handle, event)
+    })
+    .and_then(|mut ipc| {
+        let version = ipc.handshake()?;
+        if version == Some(vr_ipc::PROTOCOL_VERSION) {
+            return Ok(ipc);
+        }
+        let _ = app_handle.emit(
+            "vr_overlay_version_mismatch",
+            VrOverlayVersionMismatchPayload {
+                expected: vr_ipc::PROTOCOL_VERSION,
+                actual: version,
+            },
+        );
+        Err(match version {
+            Some(v) => format!(
+                "VR overlay protocol version mismatch (app: {}, overlay: {})",
+                vr_ipc::PROTOCOL_VERSION,
+                v
+            ),
+            None => "VR overlay did not report a protocol version".to_string(),
+        })
+    });
+
+    let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+    // 接続待ちの間に停止・再起動された場合は、そちらの処理に任せる
+    if state.generation != pending.generation {
+        println!("[tsst] VR overlay was stopped during startup");
+        return Ok(());
+    }
+    match connected {
+        Ok(ipc) => state.ipc = Some(ipc),
+        Err(error) => {
+            // 接続できない・バージョンが合わないプロセスは使えないので停止する
+            state.generation += 1;
+            if let Some(mut child) = state.process.take() {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            println!("[tsst] {}", error);
+            state.last_error = Some(error.clone());
+            return Err(error);
+        }
+    }
+
+    drop(state);
+
+    println!("[tsst] VR overlay started (IPC port {})", pending.ipc_port);
+
+    // 接続待ちの間に変わっているかもしれないので、設定とラウンドは今のものを送る
+    let (settings, current_round) = {
+        let state = app_handle.state::<SharedState>();
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        (state.active_settings(), state.current_round.clone())
+    };
+    // 起動引数で渡せない設定を送信
+    for command in initial_vr_commands(&settings) {
+        if let Err(e) = send_vr_command(vr_state, &command) {
+            println!("[tsst] Failed to send initial VR settings: {}", e);
+        }
+    }
+    let options = vr_terror_options(&settings);
+    send_current_round_to_vr(vr_state, &current_round, &options)?;
+
+    let _ = app_handle.emit("vr_overlay_connected", ());
+    Ok(())
+}
+
+/// 起動直後にオーバーレイへ送る設定コマンド
+fn initial_vr_commands(settings: &AppSettings) -> Vec<VrCommand> {
+    vec![
+        VrCommand::SetPosition {
+            position: settings.vr_overlay_position.clone(),
+            pitch: settings.vr_overlay_head_pitch,
+        },
+        VrCommand::SetTransform {
+            transform: settings.vr_overlay_transform.clone(),
+        },
+        VrCommand::SetScale {
+            scale: settings.vr_overlay_scale,
+        },
+        VrCommand::SetGazeMode {
+            enabled: settings.vr_overlay_gaze_activation,
+        },
+        theme_command(settings),
+        panel_layout_command(VrPanel::Stats, &settings.vr_overlay_panels.stats),
+        panel_layout_command(VrPanel::Code, &settings.vr_overlay_panels.code),
+        VrCommand::SetStatsPanel {
+            enabled: settings.vr_overlay_stats_panel,
+        },
+        VrCommand::SetCodePanel {
+            enabled: settings.vr_overlay_show_code,
+        },
+        VrCommand::SetAutoHide {
+            seconds: settings.vr_overlay_auto_hide_secs,
+        },
+    ]
+}
+
+/// オーバーレイに表示するセーブコードの最大文字数
+const VR_CODE_PREVIEW_CHARS: usize = 16;
+
+/// セーブコードを先頭だけに切り詰めたShowCodeコマンドを作成
+fn vr_code_command(entry: &CodeEntry) -> VrCommand {
+    let mut code: String = entry.code.chars().take(VR_CODE_PREVIEW_CHARS).collect();
+    if entry.code.chars().count() > VR_CODE_PREVIEW_CHARS {
+        code.push('…');
+    }
+    VrCommand::ShowCode {
+        code,
+        timestamp: entry.timestamp.clone(),
+    }
+}
+
+/// ラウンド中であれば経過時間をオーバーレイに送信（監視ループから毎秒呼ばれる）
+fn send_vr_round_timer(state: &SharedState, vr_state: &SharedVrState) {
+    let elapsed_secs = {
+        let state = state.lock().expect("state lock");
+        if !state.vr_overlay_enabled() || !state.settings.vr_overlay_round_timer {
+            return;
+        }
+        if !state.current_round.is_active {
+            return;
+        }
+        state
+            .current_round
+            .started_at
+            .as_deref()
+            .and_then(|started_at| duration_between(started_at, &now_timestamp()))
+    };
+    if elapsed_secs.is_some() {
+        let _ = send_vr_command(vr_state, &VrCommand::RoundTimer { elapsed_secs });
+    }
+}
+
+/// 現在のセッションの（生存数, ラウンド数）
+fn session_round_counts(state: &AppState) -> (u32, u32) {
+    let session_rounds: Vec<&RoundRecord> = match state.current_session_id {
+        Some(id) => state
+            .data
+            .rounds
+            .iter()
+            .filter(|r| !r.excluded && r.session_id == Some(id))
+            .collect(),
+        None => Vec::new(),
+    };
+    let survivals = session_rounds.iter().filter(|r| !r.is_dead).count() as u32;
+    (survivals, session_rounds.len() as u32)
+}
+
+/// 現在のセッションの統計をUpdateStatsコマンドにまとめる
+fn vr_stats_command(state: &AppState) -> VrCommand {
+    let (survivals, rounds) = session_round_counts(state);
+
+    VrCommand::UpdateStats {
+        current_streak: current_streak(&state.data.rounds),
+        session_survival_rate: survival_rate(survivals, rounds),
+        session_rounds: rounds,
+    }
+}
+
+/// 最新のセーブコードをクリップボードにコピー
+fn copy_latest_code(app_handle: &AppHandle) {
+    let state = app_handle.state::<SharedState>();
+    let code = state
+        .lock()
+        .ok()
+        .and_then(|s| s.data.history.last().map(|entry| entry.code.clone()));
+    match code {
+        Some(code) => {
+            if let Err(e) = copy_code_to_clipboard(&state, code) {
+                app_errors::report(
+                    app_handle,
+                    Severity::Error,
+                    format!("Failed to copy code: {}", e),
+                );
+            }
+        }
+        None => println!("[tsst] No save code to copy"),
+    }
+}
+
+/// コントローラーに割り当てたアクションを実行する
+fn handle_vr_input_action(app_handle: &AppHandle, action: InputAction) -> Result<(), String> {
+    match action {
+        InputAction::CopyCode => copy_latest_code(app_handle),
+        InputAction::ToggleOverlay => {
+            let vr_state = app_handle.state::<SharedVrState>();
+            send_vr_command(vr_state.inner(), &VrCommand::ToggleVisibility)?;
+        }
+        // 追加パネル（統計・セーブコード）を「なし→統計→コード→両方」の順に切り替える
+        InputAction::CyclePanels => {
+            let current = {
+                let state = app_handle.state::<SharedState>();
+                let state = state.lock().map_err(|_| "state lock failed")?;
+                (
+                    state.settings.vr_overlay_stats_panel,
+                    state.settings.vr_overlay_show_code,
+                )
+            };
+            let (stats, code) = match current {
+                (false, false) => (true, false),
+                (true, false) => (false, true),
+                (false, true) => (true, true),
+                (true, true) => (false, false),
+            };
+            set_vr_overlay_stats_panel(
+                app_handle.clone(),
+                app_handle.state::<SharedState>(),
+                app_handle.state::<SharedVrState>(),
+                stats,
+            )?;
+            set_vr_overlay_show_code(
+                app_handle.clone(),
+                app_handle.state::<SharedState>(),
+                app_handle.state::<SharedVrState>(),
+                code,
+            )?;
+            let state = app_handle.state::<SharedState>();
+            let snapshot = state.lock().map_err(|_| "state lock failed")?.snapshot();
+            let _ = app_handle.emit("state_updated", &snapshot);
+        }
+        // 不調なオーバーレイをヘッドセットを外さずに止められるようにする
+        InputAction::DisableOverlay => {
+            enable_vr_overlay(app_handle, false)?;
+            let state = app_handle.state::<SharedState>();
+            let snapshot = state.lock().map_err(|_| "state lock failed")?.snapshot();
+            let _ = app_handle.emit("state_updated", &snapshot);
+        }
+    }
+    Ok(())
+}
+
+/// オーバーレイ上の操作をフロントエンドに通知し、必要なバックエンド処理を行う
+fn handle_vr_overlay_event(app_handle: &AppHandle, event: OverlayEvent) {
+    println!("[tsst] VR overlay event: {:?}", event);
+
+    match event {
+        OverlayEvent::CopyCode => copy_latest_code(app_handle),
+        OverlayEvent::InputAction { action } => {
+            // IPCの受信スレッドから呼ばれるので、応答を待つコマンド送信は別スレッドで行う
+            let app_handle = app_handle.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = handle_vr_input_action(&app_handle, action) {
+                    println!("[tsst] VR input action {:?} failed: {}", action, e);
+                }
+            });
+        }
+        _ => {}
+    }
+
+    let _ = app_handle.emit("vr_overlay_event", event);
+}
+
+#[derive(Clone, Serialize)]
+struct VrOverlayVersionMismatchPayload {
+    expected: u32,
+    actual: Option<u32>,
+}
+
+/// オーバーレイがIPCに接続するまでの待機時間
+const VR_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 再起動の最大待機秒数
+const VR_RESTART_MAX_DELAY_SECS: u64 = 60;
+/// この秒数以上動作していれば再起動の試行回数をリセットする
+const VR_STABLE_UPTIME_SECS: u64 = 60;
+
+#[derive(Clone, Serialize)]
+struct VrOverlayCrashedPayload {
+    exit_code: Option<i32>,
+    restart_in_secs: Option<u64>,
+}
+
+/// VRオーバーレイプロセスを監視し、予期せず終了した場合は再起動する
+fn spawn_vr_overlay_watchdog(app_handle: AppHandle, vr_state: SharedVrState, generation: u64) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(1));
+
+        let (exit_code, delay) = {
+            let mut state = vr_state.lock().expect("vr state lock");
+            // 停止・再起動済みならこのウォッチドッグは終了
+            if state.generation != generation {
+                return;
+            }
+            let Some(child) = state.process.as_mut() else {
+                return;
+            };
+            let status = match child.try_wait() {
+                Ok(Some(status)) => status,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("[tsst] VR overlay watchdog error: {}", e);
+                    continue;
+                }
+            };
+
+            state.process = None;
+            state.ipc = None;
+            state.last_error = Some(format!(
+                "VR overlay exited unexpectedly (code: {:?})",
+                status.code()
+            ));
+
+            let stable = state
+                .last_started
+                .is_some_and(|t| t.elapsed() >= Duration::from_secs(VR_STABLE_UPTIME_SECS));
+            if stable {
+                state.restart_attempts = 0;
+            }
+            let delay = 2u64
+                .saturating_pow(state.restart_attempts)
+                .min(VR_RESTART_MAX_DELAY_SECS);
+            state.restart_attempts = state.restart_attempts.saturating_add(1);
+            (status.code(), delay)
+        };
+
+        println!(
+            "[tsst] VR overlay exited unexpectedly (code: {:?}), restarting in {}s",
+            exit_code, delay
+        );
+
+        let app_state = app_handle.state::<SharedState>();
+        let vr_enabled = app_state
+            .lock()
+            .map(|s| s.vr_overlay_enabled())
+            .unwrap_or(false);
+
+        let _ = app_handle.emit(
+            "vr_overlay_crashed",
+            VrOverlayCrashedPayload {
+                exit_code,
+                restart_in_secs: vr_enabled.then_some(delay),
+            },
+        );
+
+        if !vr_enabled {
+            return;
+        }
+
+        let mut delay = delay;
+        loop {
+            std::thread::sleep(Duration::from_secs(delay));
+
+            let settings = app_state.lock().expect("state lock").active_settings();
+            if !settings.vr_overlay_enabled {
+                return;
+            }
+
+            {
+                let mut state = vr_state.lock().expect("vr state lock");
+                // 待機中に停止・再起動された場合は何もしない
+                if state.generation != generation || state.process.is_some() {
+                    return;
+                }
+                if !is_steamvr_running() {
+                    state.waiting_for_steamvr = true;
+                    println!("[tsst] SteamVR not running, waiting before restarting VR overlay...");
+                    return;
+                }
+            }
+
+            match start_vr_overlay_blocking(&app_handle, &vr_state, &settings) {
+                Ok(()) => {
+                    let _ = send_vr_command(
+                        &vr_state,
+                        &vr_message_command("VRオーバーレイが異常終了したため再起動しました", None),
+                    );
+                    return;
+                }
+                Err(e) => {
+                    let mut state = vr_state.lock().expect("vr state lock");
+                    delay = 2u64
+                        .saturating_pow(state.restart_attempts)
+                        .min(VR_RESTART_MAX_DELAY_SECS);
+                    state.restart_attempts = state.restart_attempts.saturating_add(1);
+                    app_errors::report(
+                        &app_handle,
+                        Severity::Warning,
+                        format!(
+                            "Failed to restart VR overlay: {}, retrying in {}s",
+                            e, delay
+                        ),
+                    );
+                }
+            }
+        }
+    });
+}
+
+fn overlay_log_path(app_handle: &AppHandle) -> PathBuf {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("logs").join("vr-overlay.log"))
+        .unwrap_or_else(|| PathBuf::from("vr-overlay.log"))
+}
+
+/// フロントエンドのコンソール表示用に送るオーバーレイの出力行
+#[derive(Clone, Serialize)]
+struct VrOverlayLogPayload {
+    stream: &'static str,
+    line: String,
+}
+
+fn spawn_overlay_log_reader(
+    app_handle: AppHandle,
+    log: Arc<Mutex<RotatingLog>>,
+    stream: impl Read + Send + 'static,
+    label: &'static str,
+) {
+    std::thread::spawn(move || {
+        let write = |line: String| {
+            if let Ok(mut log) = log.lock() {
+                log.write_line(&line);
+            }
+        };
+
+        write(format!("[tsst] log start ({})", label));
+        let reader = BufReader::new(stream);
+        for line in reader.lines().map_while(Result::ok) {
+            write(format!("[{}] {}", label, line));
+            let _ = app_handle.emit(
+                "vr_overlay_log",
+                VrOverlayLogPayload {
+                    stream: label,
+                    line,
+                },
+            );
+        }
+        write(format!("[tsst] log end ({})", label));
+    });
+}
+
+/// VRオーバーレイのログの末尾を取得
+#[tauri::command]
+fn get_overlay_log_tail(app_handle: AppHandle, lines: Option<usize>) -> Vec<String> {
+    overlay_log::tail(&overlay_log_path(&app_handle), lines.unwrap_or(200))
+}
+
+/// Quit送信後、オーバーレイが自分で終了するのを待つ最大時間
+const VR_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn stop_vr_overlay(vr_state: &Mutex<VrOverlayState>) -> Result<(), String> {
+    let (mut ipc, child) = {
+        let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+        // 意図的な停止なのでウォッチドッグを無効化
+        state.generation += 1;
+        state.restart_attempts = 0;
+        (state.ipc.take(), state.process.take())
+    };
+
+    // 終了待ちの間はロックを持たない（接続は終了するまで保持する）
+    let acked = ipc
+        .as_mut()
+        .is_some_and(|ipc| ipc.send(&VrCommand::Quit).is_ok());
+
+    if let Some(mut child) = child {
+        // OpenVRのハンドルを解放して終了するのを待ち、間に合わなければ強制終了
+        let deadline = Instant::now() + VR_SHUTDOWN_TIMEOUT;
+        let exited = loop {
+            match child.try_wait() {
+                Ok(Some(_)) => break true,
+                Ok(None) if acked && Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                _ => break false,
+            }
+        };
+        if !exited {
+            println!("[tsst] VR overlay did not exit in time, killing");
+            let _ = child.kill();
+        }
+        let _ = child.wait();
+    }
+    drop(ipc);
+
+    println!("[tsst] VR overlay stopped");
+    Ok(())
+}
+
+fn send_vr_command(vr_state: &Mutex<VrOverlayState>, command: &VrCommand) -> Result<(), String> {
+    let pending = {
+        let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+        let Some(ref mut ipc) = state.ipc else {
+            return Ok(());
+        };
+        ipc.start_send(command)
+    };
+
+    // 応答待ちの間はロックを持たない（他のスレッドの送信や状態の参照を止めない）
+    let result = pending.and_then(|pending| pending.wait());
+
+    let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+    match &result {
+        Ok(()) => {
+            println!("[tsst] Sent VR command: {}", command.kind());
+            state.last_command = Some(command.kind().to_string());
+        }
+        Err(e) => state.last_error = Some(e.clone()),
+    }
+    result
+}
+
+/// キラーIDからUpdateTerrorsコマンドを作成
+fn update_terrors_command(
+    killers: &[u32],
+    round_type: &str,
+    options: &VrTerrorOptions,
+) -> VrCommand {
+    let terrors: Vec<VrTerrorInfo> = killers
+        .iter()
+        .enumerate()
+        .map(|(index, &id)| {
+            let mut info: VrTerrorInfo = get_terror_data(id, round_type).into();
+            info.icon = options
+                .icon_dir
+                .as_deref()
+                .and_then(|dir| load_terror_icon(dir, id, round_type));
+            info.highlighted = options.watchlist.contains(&id);
+            if options.spoiler_mode {
+                redact_terror_info(&mut info, index);
+            }
+            info
+        })
+        .collect();
+    VrCommand::UpdateTerrors {
+        terrors,
+        round_type: round_type.to_string(),
+    }
+}
+
+/// ラウンド中で敵が判明していれば、その情報をVRオーバーレイに送信
+fn send_current_round_to_vr(
+    vr_state: &Mutex<VrOverlayState>,
+    current_round: &CurrentRoundInfo,
+    options: &VrTerrorOptions,
+) -> Result<(), String> {
+    if !current_round.is_active || current_round.killers.is_empty() {
+        return Ok(());
+    }
+    let round_type = current_round.round_type.as_deref().unwrap_or("Classic");
+    send_vr_command(
+        vr_state,
+        &update_terrors_command(&current_round.killers, round_type, options),
+    )
+}
+
+// ============ ログファイル処理 ============
+
+fn find_latest_log_file(dir: &Path) -> Option<PathBuf> {
+    let mut latest: Option<(PathBuf, std::time::SystemTime)> = None;
+    let entries = fs::read_dir(dir).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let metadata = entry.metadata().ok()?;
+        let modified = metadata.modified().ok()?;
+        match &latest {
+            Some((_, last_modified)) if modified <= *last_modified => {}
+            _ => latest = Some((path, modified)),
+        }
+    }
+    latest.map(|(path, _)| path)
+}
+
+/// ログ行の先頭から日時を取得（"2026.01.21 20:14:23 ..." 形式）
+fn parse_log_timestamp(line: &str) -> String {
+    let mut parts = line.split_whitespace();
+    let date = parts.next().unwrap_or_default();
+    let time = parts.next().unwrap_or_default();
+    if !date.is_empty() && !time.is_empty() {
+        format!("{} {}", date, time)
+    } else {
+        "".to_string()
+    }
+}
+
+/// 読んだログ行と、その日時からの監視の遅れをメトリクスに記録する
+/// （空行以外で日時が読めなければ読み取りエラー）
+fn record_log_line_metrics(line: &str) {
+    metrics::record_line_read();
+    if line.trim().is_empty() {
+        return;
+    }
+    match NaiveDateTime::parse_from_str(&parse_log_timestamp(line), "%Y.%m.%d %H:%M:%S") {
+        Ok(logged_at) => {
+            let lag = Local::now().naive_local() - logged_at;
+            metrics::set_monitor_lag_ms(lag.num_milliseconds().max(0) as u64);
+        }
+        Err(_) => metrics::record_parse_error(),
+    }
+}
+
+/// ログ行の日時を取得（取得できない場合は現在日時）
+/// ポイント獲得・アイテム購入を記録し、統計と現在のセッションに加える
+fn record_economy_event(state: &mut AppState, event: EconomyEvent) {
+    state.data.stats.economy.add(&event);
+    state.session_economy.add(&event);
+    state.data.economy_events.push(event);
+}
+
+fn log_timestamp_or_now(line: &str) -> String {
+    let timestamp = parse_log_timestamp(line);
+    if timestamp.is_empty() {
+        now_timestamp()
+    } else {
+        timestamp
+    }
+}
+
+/// 現在日時をログと同じ形式で取得
+fn now_timestamp() -> String {
+    Local::now().format("%Y.%m.%d %H:%M:%S").to_string()
+}
+
+/// ログ処理結果
+#[derive(Debug, Clone)]
+enum LogEvent {
+    None,
+    StateChanged,
+    RoundStarted,
+    RoundEnded,
+}
+
+/// 正規表現パターン
+struct LogPatterns {
+    code_re: Regex,
+    round_start_re: Regex,
+    killers_re: Regex,
+    death_re: Regex,
+    reborn_re: Regex,
+    survival_re: Regex,
+    respawn_re: Regex,
+    round_end_re: Regex,
+    /// 設定したパターン（監視中に設定が変わったら作り直す）
+    detection: LogDetectionPatterns,
+    left_room_re: Regex,
+    joining_re: Regex,
+    player_joined_re: Regex,
+    player_left_re: Regex,
+    account_re: Regex,
+}
+
+impl LogPatterns {
+    fn new() -> Self {
+        Self {
+            code_re: Regex::new(r"\[START\]([0-9_,]+)\[END\]").expect("code regex"),
+            round_start_re: Regex::new(
+                r"This round is taking place at (.+?) and the round type is (.+)$",
+            )
+            .expect("round start regex"),
+            // Format: "Killers have been set - X X X // Round type is Y"
+            killers_re: Regex::new(
+                r"Killers have been set - (\d+) (\d+) (\d+)(?: // Round type is (.+))?",
+            )
+            .expect("killers regex"),
+            death_re: Regex::new(r"You died\.").expect("death regex"),
+            reborn_re: Regex::new(r"LOL JK, REBORN!").expect("reborn regex"),
+            survival_re: Regex::new(r"Lived in round\.").expect("survival regex"),
+            respawn_re: Regex::new(r"Respawned\? Coward\.").expect("respawn regex"),
+            round_end_re: Regex::new(r"Verified Round End").expect("round end regex"),
+            detection: LogDetectionPatterns::default(),
+            // ワールド移動検出（OnLeftRoom または Joining wrld_）
+            left_room_re: Regex::new(r"OnLeftRoom|Joining wrld_").expect("left room regex"),
+            joining_re: Regex::new(r"Joining (wrld_[0-9a-fA-F-]+)(?::(\S+))?")
+                .expect("joining regex"),
+            player_joined_re: Regex::new(r"OnPlayerJoined (.+?)(?: \(usr_[0-9a-fA-F-]+\))?\s*$")
+                .expect("player joined regex"),
+            player_left_re: Regex::new(r"OnPlayerLeft (.+?)(?: \(usr_[0-9a-fA-F-]+\))?\s*$")
+                .expect("player left regex"),
+            account_re: Regex::new(r"User Authenticated: (.+?) \(usr_[0-9a-fA-F-]+\)")
+                .expect("account regex"),
+        }
+    }
+
+    /// いずれかの検出パターンに一致する行か（診断用のログ配信の絞り込み）
+    fn is_relevant(&self, line: &str) -> bool {
+        [
+            &self.code_re,
+            &self.round_start_re,
+            &self.killers_re,
+            &self.death_re,
+            &self.reborn_re,
+            &self.survival_re,
+            &self.respawn_re,
+            &self.round_end_re,
+            &self.left_room_re,
+            &self.joining_re,
+            &self.player_joined_re,
+            &self.player_left_re,
+            &self.account_re,
+        ]
+        .iter()
+        .any(|re| re.is_match(line))
+            || self.detection.is_match(line)
+    }
+}
+
+/// ログ行を処理し、コードが見つかったらデータに記録
+fn process_log_line(line: &str, patterns: &LogPatterns, state: &mut AppState) -> LogEvent {
+    let mut event = LogEvent::None;
+
+    // ログインしたアカウントを検出
+    if let Some(caps) = patterns.account_re.captures(line) {
+        let account = caps.get(1).map(|m| m.as_str().trim().to_string());
+        println!("[tsst] アカウント検出: {:?}", account);
+        if state.current_account != account {
+            state.current_account = account;
+            event = LogEvent::StateChanged;
+        }
+    }
+
+    // インスタンスへのプレイヤー参加・退出を検出
+    if let Some(name) = patterns
+        .player_joined_re
+        .captures(line)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().trim().to_string())
+    {
+        if !state.instance_players.contains(&name) {
+            state.instance_players.push(name.clone());
+        }
+        if let Some(session_id) = state.current_session_id {
+            if let Some(session) = state.data.sessions.iter_mut().find(|s| s.id == session_id) {
+                if !session.players.contains(&name) {
+                    session.players.push(name);
+                }
+            }
+        }
+    }
+    if let Some(name) = patterns
+        .player_left_re
+        .captures(line)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().trim().to_string())
+    {
+        state.instance_players.retain(|p| p != &name);
+    }
+
+    // ラウンド開始を検出（マップ名とラウンドタイプを抽出）
+    if let Some(caps) = patterns.round_start_re.captures(line) {
+        let map_name = caps.get(1).map(|m| m.as_str().trim().to_string());
+        let round_type = caps.get(2).map(|m| m.as_str().trim().to_string());
+
+        // 前のラウンドが未決着の場合はログ出力
+        if state.current_round.is_active {
+            println!("[tsst] 前のラウンドが未決着のまま次のラウンドへ");
+        }
+
+        // 現在のラウンド情報を設定
+        state.current_round = CurrentRoundInfo {
+            is_active: true,
+            map_name: map_name.clone(),
+            round_type: round_type.clone(),
+            started_at: Some(parse_log_timestamp(line)).filter(|t| !t.is_empty()),
+            player_count: Some(state.instance_players.len() as u32).filter(|&n| n > 0),
+            ..Default::default()
+        };
+        state.current_round_type = round_type.clone();
+
+        println!("[tsst] ラウンド開始: {:?} at {:?}", round_type, map_name);
+
+        // ラウンドタイプのエントリを作成
+        if let Some(ref rt) = round_type {
+            state.data.stats.round_types.entry(rt.clone()).or_default();
+        }
+
+        event = LogEvent::RoundStarted;
+    }
+
+    // 敵スポーンを検出 ("Killers have been set - X X X // Round type is Y")
+    if let Some(caps) = patterns.killers_re.captures(line) {
+        let k1: u32 = caps
+            .get(1)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        let k2: u32 = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        let k3: u32 = caps
+            .get(3)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+
+        // ラウンドタイプが含まれている場合は更新
+        if let Some(rt_match) = caps.get(4) {
+            let round_type = rt_match.as_str().trim().to_string();
+            if state.current_round.round_type.is_none() {
+                state.current_round.round_type = Some(round_type.clone());
+                state.current_round_type = Some(round_type.clone());
+                println!("[tsst] ラウンドタイプ更新: {}", round_type);
+            }
+        }
+
+        // Moon系ラウンドの場合、ラウンドタイプから固定のキラーIDを決定
+        // (ログでは "0 0 0" と記録されるため)
+        let round_type = state.current_round.round_type.as_deref();
+        let killers: Vec<u32> = if let Some(rt) = round_type {
+            if let Some(moon_id) = get_moon_terror_index(rt) {
+                // Moon系ラウンドは固定の1体のみ
+                vec![moon_id]
+            } else {
+                // 通常ラウンド: 0以外の敵コードをリストに追加
+                [k1, k2, k3].into_iter().filter(|&k| k != 0).collect()
+            }
+        } else {
+            // ラウンドタイプ不明の場合は通常処理
+            [k1, k2, k3].into_iter().filter(|&k| k != 0).collect()
+        };
+        state.current_round.killers = killers.clone();
+
+        println!("[tsst] 敵スポーン: {:?}", killers);
+        event = LogEvent::StateChanged;
+    }
+
+    // 死亡を検出
+    if patterns.death_re.is_match(line) {
+        state.current_round.is_dead = true;
+        println!("[tsst] 死亡検出");
+        event = LogEvent::StateChanged;
+    }
+
+    // 復活を検出（死亡をキャンセル）
+    if patterns.reborn_re.is_match(line) {
+        state.current_round.is_dead = false;
+        println!("[tsst] 復活検出（死亡取消）");
+        event = LogEvent::StateChanged;
+    }
+
+    // 生存を検出
+    if patterns.survival_re.is_match(line) {
+        println!("[tsst] 生存検出");
+        // 統計は round_end で更新するため、ここではフラグのみ
+        event = LogEvent::StateChanged;
+    }
+
+    // 最後の生存者（MVP）を検出
+    let last_survivor = patterns
+        .detection
+        .last_survivor
+        .as_ref()
+        .is_some_and(|re| re.is_match(line));
+    if last_survivor && state.current_round.is_active {
+        state.current_round.is_last_survivor = true;
+        println!("[tsst] 最後の生存者検出");
+        event = LogEvent::StateChanged;
+    }
+
+    // テラーのスタンを検出
+    let stunned = patterns
+        .detection
+        .stun
+        .as_ref()
+        .is_some_and(|re| re.is_match(line));
+    if stunned && state.current_round.is_active {
+        state.current_round.stuns += 1;
+        println!("[tsst] スタン検出: {}回目", state.current_round.stuns);
+        event = LogEvent::StateChanged;
+    }
+
+    // ポイント獲得を検出
+    if let Some(points) = patterns
+        .detection
+        .points
+        .as_ref()
+        .and_then(|re| re.captures(line))
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse::<u64>().ok())
+    {
+        record_economy_event(
+            state,
+            EconomyEvent {
+                timestamp: log_timestamp_or_now(line),
+                points_earned: points,
+                points_spent: 0,
+                items_bought: 0,
+            },
+        );
+        println!("[tsst] ポイント獲得: {}", points);
+        event = LogEvent::StateChanged;
+    }
+
+    // アイテム購入を検出
+    if let Some(caps) = patterns
+        .detection
+        .purchase
+        .as_ref()
+        .and_then(|re| re.captures(line))
+    {
+        let item = caps.get(1).map(|m| m.as_str().trim().to_string());
+        let cost: u64 = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        record_economy_event(
+            state,
+            EconomyEvent {
+                timestamp: log_timestamp_or_now(line),
+                points_earned: 0,
+                points_spent: cost,
+                items_bought: 1,
+            },
+        );
+        println!("[tsst] アイテム購入: {:?} ({}ポイント)", item, cost);
+        event = LogEvent::StateChanged;
+    }
+
+    // リスポーンを検出（ラウンドを無効化）
+    if patterns.respawn_re.is_match(line) {
+        if state.current_round.is_active {
+            println!("[tsst] リスポーン検出（ラウンド無効化）");
+            // ラウンドをリセット（統計に含めない）
+            state.current_round = CurrentRoundInfo::default();
+            state.current_round_type = None;
+            // リセット後は他のパターンをチェックしない
+            return LogEvent::RoundEnded;
+        }
+    }
+
+    // ワールド移動を検出（ラウンドを無効化）
+    if patterns.left_room_re.is_match(line) {
+        // 現在のセッションを終了
+        if let Some(summary) = end_current_session(state, log_timestamp_or_now(line)) {
+            state.pending_session_summaries.push(summary);
+        }
+
+        // ToNワールドへの参加であれば新しいセッションを開始
+        if let Some(caps) = patterns.joining_re.captures(line) {
+            if caps.get(1).map(|m| m.as_str()) == Some(WORLD_ID) {
+                let id = state.data.sessions.last().map(|s| s.id + 1).unwrap_or(1);
+                state.data.sessions.push(SessionRecord {
+                    id,
+                    started_at: log_timestamp_or_now(line),
+                    ended_at: None,
+                    instance_id: caps.get(2).map(|m| m.as_str().to_string()),
+                    players: Vec::new(),
+                });
+                state.current_session_id = Some(id);
+                println!("[tsst] セッション開始: {}", id);
+            }
+        }
+
+        // Joining wrld_ の場合はインスタンスカウンターをリセット
+        if line.contains("Joining wrld_") {
+            println!("[tsst] インスタンス変更検出（カウンターリセット）");
+            state.instance_round_counts.clear();
+            state.session_economy = EconomyStats::default();
+            state.instance_round_sequence.clear();
+            state.instance_players.clear();
+            state.predicted_next_round = None;
+            state.session_started_at = Some(log_timestamp_or_now(line));
+        }
+        if state.current_round.is_active {
+            println!("[tsst] ワールド移動検出（ラウンド無効化）");
+            // ラウンドをリセット（統計に含めない）
+            state.current_round = CurrentRoundInfo::default();
+            state.current_round_type = None;
+            // リセット後は他のパターンをチェックしない
+            return LogEvent::RoundEnded;
         }
+    }
 
-        // ジョブオブジェクトの制限を設定（親プロセスが終了したら子プロセスも終了）
-        let job_info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION {
-            BasicLimitInformation: JOBOBJECT_BASIC_LIMIT_INFORMATION {
-                LimitFlags: JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
-                ..std::mem::zeroed()
-            },
-            ..std::mem::zeroed()
+    // ラウンド終了を検出（ラウンドがアクティブな場合のみ）
+    if patterns.round_end_re.is_match(line) && state.current_round.is_active {
+        let round_type = state
+            .current_round_type
+            .take()
+            .unwrap_or_else(|| "Unknown".to_string());
+        let is_dead = state.current_round.is_dead;
+
+        // ラウンド記録を作成
+        let ended_at = log_timestamp_or_now(line);
+        let record = RoundRecord {
+            id: state.data.rounds.last().map(|r| r.id + 1).unwrap_or(1),
+            started_at: state.current_round.started_at.clone(),
+            duration_secs: state
+                .current_round
+                .started_at
+                .as_deref()
+                .and_then(|started_at| duration_between(started_at, &ended_at)),
+            ended_at,
+            map_name: state.current_round.map_name.clone(),
+            round_type: round_type.clone(),
+            killers: state.current_round.killers.clone(),
+            is_dead,
+            session_id: state.current_session_id,
+            player_count: state.current_round.player_count,
+            last_survivor: !is_dead && state.current_round.is_last_survivor,
+            stuns: state.current_round.stuns,
+            save_code: state.current_round.save_code.clone(),
+            account: state.current_account.clone(),
+            excluded: false,
+            max_bpm: state.current_round.heart_rates.iter().copied().max(),
+            avg_bpm: average_bpm(&state.current_round.heart_rates),
         };
 
-        let result = SetInformationJobObject(
-            job_handle,
-            JobObjectExtendedLimitInformation,
-            &job_info as *const _ as *const std::ffi::c_void,
-            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        // 統計を更新
+        if state.data.stats.since.is_none() {
+            state.data.stats.since = Some(record.ended_at.clone());
+        }
+        add_round_to_stats(&mut state.data.stats, &record);
+        println!(
+            "[tsst] ラウンド終了（{}）: {} (生存: {}, 死亡: {})",
+            if is_dead { "死亡" } else { "生存" },
+            round_type,
+            state.data.stats.survivals,
+            state.data.stats.deaths
         );
 
-        if result == 0 {
-            CloseHandle(job_handle);
-            return Err("Failed to set job object information".to_string());
-        }
+        state.data.rounds.push(record.clone());
+        refresh_duration_stats(&mut state.data.stats, &state.data.rounds);
 
-        // プロセスをジョブオブジェクトに割り当て
-        let result = AssignProcessToJobObject(job_handle, process_handle);
-        if result == 0 {
-            CloseHandle(job_handle);
-            return Err("Failed to assign process to job object".to_string());
-        }
+        // マイルストーンの達成をチェック
+        let unlocked = check_milestones(&mut state.data, &record);
+        state.pending_milestones.extend(unlocked);
 
-        // ジョブハンドルは意図的にクローズしない
-        // （プログラム終了時に自動的にクリーンアップされ、その際にプロセスがkillされる）
-        // CloseHandle(job_handle);
+        // 目標の達成をチェック
+        let data = &mut state.data;
+        let completed = check_goals(&mut data.goals, &data.rounds);
+        state.pending_goals.extend(completed);
 
-        println!("[tsst] VR overlay process assigned to job object");
+        // インスタンス内ラウンドタイプカウンターを更新
+        *state.instance_round_counts.entry(round_type.clone()).or_insert(0) += 1;
+        println!(
+            "[tsst] インスタンスカウンター更新: {} = {}",
+            round_type,
+            state.instance_round_counts.get(&round_type).unwrap_or(&0)
+        );
+
+        // 次のラウンドを予測
+        state.instance_round_sequence.push(round_type.clone());
+        state.predicted_next_round = predict_next_round(&state.instance_round_sequence);
+        println!("[tsst] 次のラウンド予測: {:?}", state.predicted_next_round);
+
+        // ラウンド情報をリセット
+        state.current_round = CurrentRoundInfo::default();
+        event = LogEvent::RoundEnded;
     }
 
-    Ok(())
-}
+    // 新規コードが見つかったらデータに記録
+    if let Some(caps) = patterns.code_re.captures(line) {
+        if let Some(code_match) = caps.get(1) {
+            let timestamp = parse_log_timestamp(line);
 
-fn get_vr_overlay_path(app_handle: &AppHandle) -> Option<PathBuf> {
-    // ビルド時: アプリと同じディレクトリにvr-overlay.exeとして配置される
-    // 開発時: target/debug/vr-overlay.exe または binaries/vr-overlay-xxx.exe
+            let code = code_match.as_str().to_string();
+            let round_type = state.current_round_type.clone();
+            println!(
+                "[tsst] 新規コード発見: {} (ラウンド: {:?})",
+                code, round_type
+            );
 
-    // まずアプリの実行ファイルと同じディレクトリを確認
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            let prod_path = exe_dir.join("vr-overlay.exe");
-            if prod_path.exists() {
-                println!("[tsst] Found VR overlay at: {:?}", prod_path);
-                return Some(prod_path);
+            // ラウンド中の場合、テラー名とラウンドタイプ（英語）を取得
+            let (terror_names, round_type_english) = if state.current_round.is_active {
+                let rt = round_type.as_deref().unwrap_or("Classic");
+                // キラーIDからテラー名を取得
+                let names: Vec<String> = get_terrors_data(&state.current_round.killers, rt)
+                    .into_iter()
+                    .map(|d| d.name)
+                    .collect();
+                let terror_names = if names.is_empty() { None } else { Some(names) };
+                // ラウンドタイプを英語に変換
+                let rt_eng = round_type.as_ref().map(|rt| round_type_to_english(rt));
+                (terror_names, rt_eng)
+            } else {
+                (None, None)
+            };
+
+            // ラウンド中の場合、セーブコードを記録
+            if state.current_round.is_active {
+                state.current_round.save_code = Some(code.clone());
             }
-        }
-    }
 
-    // バンドル/開発共通: resource_dir 直下と resource_dir/binaries を確認
-    if let Ok(resource_dir) = app_handle.path().resource_dir() {
-        let candidates = if cfg!(target_os = "windows") {
-            vec![
-                resource_dir.join("vr-overlay.exe"),
-                resource_dir.join("binaries").join("vr-overlay.exe"),
-                resource_dir
-                    .join("binaries")
-                    .join("vr-overlay-x86_64-pc-windows-msvc.exe"),
-            ]
-        } else {
-            vec![
-                resource_dir.join("vr-overlay"),
-                resource_dir.join("binaries").join("vr-overlay"),
-            ]
-        };
+            state.data.history.push(CodeEntry {
+                code,
+                timestamp,
+                round_type,
+                terror_names,
+                round_type_english,
+            });
+            state.last_code_captured_at = Some(Instant::now());
 
-        for candidate in candidates {
-            if candidate.exists() {
-                println!("[tsst] Found VR overlay at: {:?}", candidate);
-                return Some(candidate);
-            } else {
-                println!("[tsst] VR overlay not found at: {:?}", candidate);
+            // 最大履歴数を超えたら古いものを削除
+            while state.data.history.len() > MAX_HISTORY {
+                state.data.history.remove(0);
             }
-        }
-    }
 
-    // 念のため: BaseDirectory::Resource で解決
-    if let Ok(resolved) = app_handle
-        .path()
-        .resolve("vr-overlay.exe", BaseDirectory::Resource)
-    {
-        if resolved.exists() {
-            println!("[tsst] Found VR overlay at: {:?}", resolved);
-            return Some(resolved);
+            if matches!(event, LogEvent::None) {
+                event = LogEvent::StateChanged;
+            }
         }
     }
 
-    println!("[tsst] VR overlay binary not found");
-    None
+    event
 }
 
-fn start_vr_overlay(
-    app_handle: &AppHandle,
-    vr_state: &Mutex<VrOverlayState>,
-    settings: &AppSettings,
-) -> Result<(), String> {
-    let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
-
-    // 既に起動している場合は何もしない
-    if state.process.is_some() {
-        return Ok(());
+fn maybe_copy_latest_code(line: &str, state: &mut AppState) {
+    if !state.auto_copy() || !line.contains(WORLD_ID) {
+        return;
     }
+    let latest_code = state.data.history.last().map(|entry| entry.code.clone());
+    if let Some(code) = latest_code {
+        if state.last_copied_code.as_deref() == Some(code.as_str()) {
+            return;
+        }
+        if state.settings.confirm_copy {
+            state.pending_copy_prompt = state.data.history.last().cloned();
+            return;
+        }
+        if let Ok(mut clipboard) = Clipboard::new() {
+            let _ = clipboard.set_text(code.clone());
+            println!("[tsst] クリップボードにコピー: {}", code);
+            state.last_copied_code = Some(code);
+        }
+    }
+}
 
-    let binary_path = get_vr_overlay_path(app_handle).ok_or("VR overlay binary not found")?;
-
-    let position_arg = match settings.vr_overlay_position {
-        VrOverlayPosition::RightHand => "right",
-        VrOverlayPosition::LeftHand => "left",
-        VrOverlayPosition::Above => "above",
-    };
-
+/// コピーしてよいか確認する（トーストのボタン・ホットキー・オーバーレイのコピーで確定）
+fn prompt_copy(app_handle: &AppHandle, vr_state: &SharedVrState, entry: &CodeEntry) {
     println!(
-        "[tsst] Starting VR overlay: {:?} --position {}",
-        binary_path, position_arg
+        "[tsst] Waiting for confirmation to copy: {}",
+        entry.timestamp
     );
-
-    // sidecarと同じディレクトリをカレントディレクトリに設定（DLLを見つけるため）
-    let working_dir = binary_path.parent().unwrap_or(Path::new("."));
-
-    let mut command = Command::new(&binary_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        command.creation_flags(CREATE_NO_WINDOW);
+    let _ = app_handle.emit("copy_confirmation_requested", entry);
+    if vr_overlay_active(app_handle) {
+        let _ = send_vr_command(vr_state, &vr_code_command(entry));
+    }
+    if is_quiet(app_handle) {
+        return;
     }
 
-    let mut child = command
-        .current_dir(working_dir)
-        .arg("--position")
-        .arg(position_arg)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to start VR overlay: {}", e))?;
-
-    // Windowsの場合、子プロセスをジョブオブジェクトに割り当てる
-    // これにより、親プロセス（Tauriアプリ）がクラッシュやタスクキルされても
-    // 子プロセス（VRオーバーレイ）が自動的に終了する
+    let title = "セーブコードをコピーしますか？";
     #[cfg(windows)]
     {
-        use std::os::windows::io::AsRawHandle;
-        let process_handle = child.as_raw_handle() as windows_sys::Win32::Foundation::HANDLE;
-        if let Err(e) = assign_process_to_job_object(process_handle) {
-            println!("[tsst] Warning: Failed to assign to job object: {}", e);
-            // 失敗してもプロセスは起動しているので、継続する
+        let body = "「コピー」を押すとクリップボードにコピーします";
+        match show_copy_toast(app_handle, title, body, entry.code.clone()) {
+            Ok(()) => return,
+            Err(e) => println!("[tsst] Failed to show toast with copy action: {}", e),
         }
     }
 
-    let stdin = child.stdin.take();
-    if let Some(stdout) = child.stdout.take() {
-        spawn_overlay_log_reader(app_handle.clone(), stdout, "stdout");
-    }
-    if let Some(stderr) = child.stderr.take() {
-        spawn_overlay_log_reader(app_handle.clone(), stderr, "stderr");
-    }
-    state.process = Some(child);
-    state.stdin_writer = stdin;
-
-    println!("[tsst] VR overlay started");
-    Ok(())
+    notify(
+        app_handle,
+        title,
+        "ホットキーかオーバーレイのコピーでクリップボードにコピーします",
+    );
 }
 
-fn spawn_overlay_log_reader(
-    app_handle: AppHandle,
-    stream: impl Read + Send + 'static,
-    label: &'static str,
-) {
-    std::thread::spawn(move || {
-        let log_dir = app_handle
-            .path()
-            .app_data_dir()
-            .ok()
-            .map(|dir| dir.join("logs"));
-
-        if let Some(ref dir) = log_dir {
-            let _ = fs::create_dir_all(dir);
-        }
-
-        let log_path = log_dir
-            .map(|dir| dir.join("vr-overlay.log"))
-            .unwrap_or_else(|| PathBuf::from("vr-overlay.log"));
-
-        let mut file = match fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)
-        {
-            Ok(f) => f,
-            Err(_) => return,
-        };
+/// 現在の通知設定（ロックできなければ既定値）
+fn notification_settings(app_handle: &AppHandle) -> NotificationSettings {
+    app_handle
+        .state::<SharedState>()
+        .lock()
+        .map(|s| s.notifications())
+        .unwrap_or_default()
+}
 
-        let _ = writeln!(file, "[tsst] log start ({})", label);
-        let reader = BufReader::new(stream);
-        for line in reader.lines().flatten() {
-            let _ = writeln!(file, "[{}] {}", label, line);
-        }
-        let _ = writeln!(file, "[tsst] log end ({})", label);
-    });
+/// 現在のアカウントでVRオーバーレイが有効か
+fn vr_overlay_active(app_handle: &AppHandle) -> bool {
+    app_handle
+        .state::<SharedState>()
+        .lock()
+        .map(|s| s.vr_overlay_enabled())
+        .unwrap_or(false)
 }
 
-fn stop_vr_overlay(vr_state: &Mutex<VrOverlayState>) -> Result<(), String> {
-    let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+/// イベントごとの通知設定を変更
+#[tauri::command]
+fn set_notification_settings(
+    app_handle: AppHandle,
+    notifications: NotificationSettings,
+) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |s| s.notifications = notifications)
+}
 
-    if let Some(ref mut stdin) = state.stdin_writer {
-        let cmd = serde_json::to_string(&VrCommand::Quit).unwrap_or_default();
-        let _ = writeln!(stdin, "{}", cmd);
-        let _ = stdin.flush();
-    }
+/// 通知文のテンプレートを変更
+#[tauri::command]
+fn set_notification_templates(
+    app_handle: AppHandle,
+    templates: NotificationTemplates,
+) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |settings| {
+        settings.notification_templates = templates
+    })
+}
 
-    if let Some(mut child) = state.process.take() {
-        // プロセスが終了するのを少し待つ
-        std::thread::sleep(Duration::from_millis(100));
-        let _ = child.kill();
-        let _ = child.wait();
-    }
+/// 通知文のテンプレートと現在のマップ名（ロックできなければ既定値）
+fn current_templates(app_handle: &AppHandle) -> (NotificationTemplates, String) {
+    app_handle
+        .state::<SharedState>()
+        .lock()
+        .map(|s| {
+            (
+                s.settings.notification_templates.clone(),
+                s.current_round.map_name.clone().unwrap_or_default(),
+            )
+        })
+        .unwrap_or_default()
+}
 
-    state.stdin_writer = None;
-    println!("[tsst] VR overlay stopped");
-    Ok(())
+/// イベントごとの効果音と音量を変更
+#[tauri::command]
+fn set_sound_settings(app_handle: AppHandle, sounds: SoundSettings) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |settings| settings.sounds = sounds)
 }
 
-fn send_vr_command(vr_state: &Mutex<VrOverlayState>, command: &VrCommand) -> Result<(), String> {
-    let mut state = vr_state.lock().map_err(|_| "vr state lock failed")?;
+/// テラーごとの警報音を設定（Noneで解除）
+#[tauri::command]
+fn set_terror_sound(
+    app_handle: AppHandle,
+    terror_id: u32,
+    path: Option<String>,
+) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |settings| {
+        let terror_sounds = &mut settings.sounds.terror_sounds;
+        match path {
+            Some(path) => {
+                terror_sounds.insert(terror_id, path);
+            }
+            None => {
+                terror_sounds.remove(&terror_id);
+            }
+        }
+    })
+}
 
-    if let Some(ref mut stdin) = state.stdin_writer {
-        let cmd_bytes = serde_json::to_vec(command)
-            .map_err(|e| format!("Failed to serialize VR command: {}", e))?;
-        let encoded = base64::engine::general_purpose::STANDARD.encode(&cmd_bytes);
-        let line = format!("b64:{}", encoded);
-        writeln!(stdin, "{}", line).map_err(|e| format!("Failed to write VR command: {}", e))?;
-        stdin
-            .flush()
-            .map_err(|e| format!("Failed to flush VR command: {}", e))?;
-        println!("[tsst] Sent VR command (b64, {} bytes)", cmd_bytes.len());
-    }
+/// 設定済みの効果音を試しに鳴らす（無効設定でも鳴らす）
+#[tauri::command]
+fn play_test_sound(state: tauri::State<SharedState>, event: SoundEvent) -> Result<(), String> {
+    let sounds = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.sounds.clone()
+    };
+    let file = sounds
+        .file_for(event)
+        .ok_or_else(|| format!("no sound set for {:?}", event))?;
+    audio::play_file(file, sounds.volume);
+    Ok(())
+}
 
+/// セーブコードをクリップボードにコピー（自動コピーで同じコードを再コピーしないよう記録する）
+fn copy_code_to_clipboard(state: &Mutex<AppState>, code: String) -> Result<(), String> {
+    Clipboard::new()
+        .and_then(|mut c| c.set_text(code.clone()))
+        .map_err(|e| e.to_string())?;
+    println!("[tsst] クリップボードにコピー: {}", code);
+    let mut state = state.lock().map_err(|_| "state lock failed")?;
+    state.last_copied_code = Some(code);
     Ok(())
 }
 
-// ============ ログファイル処理 ============
+/// 履歴のセーブコードを記録時刻で指定してコピー
+#[tauri::command]
+fn copy_code(state: tauri::State<SharedState>, timestamp: String) -> Result<String, String> {
+    let code = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state
+            .data
+            .history
+            .iter()
+            .rev()
+            .find(|entry| entry.timestamp == timestamp)
+            .map(|entry| entry.code.clone())
+            .ok_or_else(|| format!("code at {} not found", timestamp))?
+    };
+    copy_code_to_clipboard(state.inner(), code.clone())?;
+    Ok(code)
+}
 
-fn find_latest_log_file(dir: &Path) -> Option<PathBuf> {
-    let mut latest: Option<(PathBuf, std::time::SystemTime)> = None;
-    let entries = fs::read_dir(dir).ok()?;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-        let metadata = entry.metadata().ok()?;
-        let modified = metadata.modified().ok()?;
-        match &latest {
-            Some((_, last_modified)) if modified <= *last_modified => {}
-            _ => latest = Some((path, modified)),
+/// ログ監視の一時停止・再開（停止中のログ行は読み飛ばす）
+#[tauri::command]
+fn set_monitoring_paused(app_handle: AppHandle, paused: bool) -> Result<bool, String> {
+    {
+        let state = app_handle.state::<SharedState>();
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        if state.monitoring_paused == paused {
+            return Ok(paused);
         }
+        state.monitoring_paused = paused;
     }
-    latest.map(|(path, _)| path)
+    println!(
+        "[tsst] ログ監視を{}",
+        if paused { "一時停止" } else { "再開" }
+    );
+    let _ = app_handle.emit("monitoring_paused_changed", paused);
+    refresh_jump_list(&app_handle);
+    Ok(paused)
 }
 
-/// ログ処理結果
-#[derive(Debug, Clone)]
-enum LogEvent {
-    None,
-    StateChanged,
-    RoundStarted,
-    RoundEnded,
+fn toggle_monitoring_paused(app_handle: &AppHandle) -> Result<bool, String> {
+    let paused = {
+        let state = app_handle.state::<SharedState>();
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.monitoring_paused
+    };
+    set_monitoring_paused(app_handle.clone(), !paused)
 }
 
-/// 正規表現パターン
-struct LogPatterns {
-    code_re: Regex,
-    round_start_re: Regex,
-    killers_re: Regex,
-    death_re: Regex,
-    reborn_re: Regex,
-    survival_re: Regex,
-    respawn_re: Regex,
-    round_end_re: Regex,
-    left_room_re: Regex,
+/// ホットキーの割り当てを変更（重複や他のアプリとの競合があればエラー）
+#[tauri::command]
+fn set_hotkeys(
+    app_handle: AppHandle,
+    state: tauri::State<SharedState>,
+    hotkeys: HotkeySettings,
+) -> Result<AppSettings, String> {
+    let previous = {
+        let state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.hotkeys.clone()
+    };
+    if let Err(e) = hotkeys::register(&app_handle, &hotkeys) {
+        // 登録できなかった場合は元の割り当てに戻す
+        let _ = hotkeys::register(&app_handle, &previous);
+        return Err(e);
+    }
+
+    let updated_settings = {
+        let mut state = state.lock().map_err(|_| "state lock failed")?;
+        state.settings.hotkeys = hotkeys;
+        state.settings.clone()
+    };
+    persist_settings(&app_handle, &updated_settings)?;
+    Ok(updated_settings)
 }
 
-impl LogPatterns {
-    fn new() -> Self {
-        Self {
-            code_re: Regex::new(r"\[START\]([0-9_,]+)\[END\]").expect("code regex"),
-            round_start_re: Regex::new(
-                r"This round is taking place at (.+?) and the round type is (.+)$",
-            )
-            .expect("round start regex"),
-            // Format: "Killers have been set - X X X // Round type is Y"
-            killers_re: Regex::new(
-                r"Killers have been set - (\d+) (\d+) (\d+)(?: // Round type is (.+))?",
-            )
-            .expect("killers regex"),
-            death_re: Regex::new(r"You died\.").expect("death regex"),
-            reborn_re: Regex::new(r"LOL JK, REBORN!").expect("reborn regex"),
-            survival_re: Regex::new(r"Lived in round\.").expect("survival regex"),
-            respawn_re: Regex::new(r"Respawned\? Coward\.").expect("respawn regex"),
-            round_end_re: Regex::new(r"Verified Round End").expect("round end regex"),
-            // ワールド移動検出（OnLeftRoom または Joining wrld_）
-            left_room_re: Regex::new(r"OnLeftRoom|Joining wrld_").expect("left room regex"),
+/// 押されたホットキーの操作を実行する
+fn handle_hotkey(app_handle: &AppHandle, shortcut: &tauri_plugin_global_shortcut::Shortcut) {
+    let state = app_handle.state::<SharedState>();
+    let (action, latest_code, vr_enabled, desktop_overlay_enabled) = {
+        let Ok(state) = state.lock() else {
+            return;
+        };
+        let Some(action) = state.settings.hotkeys.action_for(shortcut) else {
+            return;
+        };
+        (
+            action,
+            state.data.history.last().map(|entry| entry.code.clone()),
+            state.vr_overlay_enabled(),
+            state.settings.desktop_overlay.enabled,
+        )
+    };
+    println!("[tsst] Hotkey pressed: {:?}", action);
+
+    let result = match action {
+        HotkeyAction::CopyLatestCode => match latest_code {
+            Some(code) => copy_code_to_clipboard(state.inner(), code),
+            None => Ok(()),
+        },
+        HotkeyAction::ToggleVrOverlay => enable_vr_overlay(app_handle, !vr_enabled).map(|_| ()),
+        HotkeyAction::ToggleDesktopOverlay if desktop_overlay_enabled => {
+            hide_desktop_overlay(app_handle.clone(), app_handle.state::<SharedState>()).map(|_| ())
+        }
+        HotkeyAction::ToggleDesktopOverlay => {
+            show_desktop_overlay(app_handle.clone(), app_handle.state::<SharedState>()).map(|_| ())
         }
+        HotkeyAction::ToggleMonitoring => toggle_monitoring_paused(app_handle).map(|_| ()),
+        HotkeyAction::ShockKillSwitch => engage_shock_kill_switch(
+            app_handle.clone(),
+            app_handle.state::<SharedState>(),
+            app_handle.state::<Shocker>(),
+        )
+        .map(|_| ()),
+    };
+    if let Err(e) = result {
+        println!("[tsst] Hotkey {:?} failed: {}", action, e);
+        return;
     }
-}
-
-/// ログ行を処理し、コードが見つかったらデータに記録
-fn process_log_line(line: &str, patterns: &LogPatterns, state: &mut AppState) -> LogEvent {
-    let mut event = LogEvent::None;
-
-    // ラウンド開始を検出（マップ名とラウンドタイプを抽出）
-    if let Some(caps) = patterns.round_start_re.captures(line) {
-        let map_name = caps.get(1).map(|m| m.as_str().trim().to_string());
-        let round_type = caps.get(2).map(|m| m.as_str().trim().to_string());
 
-        // 前のラウンドが未決着の場合はログ出力
-        if state.current_round.is_active {
-            println!("[tsst] 前のラウンドが未決着のまま次のラウンドへ");
+    // 設定が変わった操作はフロントエンドの表示を更新する
+    if matches!(
+        action,
+        HotkeyAction::ToggleVrOverlay
+            | HotkeyAction::ToggleDesktopOverlay
+            | HotkeyAction::ShockKillSwitch
+    ) {
+        if let Ok(state) = state.lock() {
+            let _ = app_handle.emit("state_updated", &state.snapshot());
         }
+    }
+}
 
-        // 現在のラウンド情報を設定
-        state.current_round = CurrentRoundInfo {
-            is_active: true,
-            map_name: map_name.clone(),
-            round_type: round_type.clone(),
-            killers: vec![],
-            is_dead: false,
-            save_code: None,
-        };
-        state.current_round_type = round_type.clone();
-
-        println!("[tsst] ラウンド開始: {:?} at {:?}", round_type, map_name);
+/// ジャンプリストに載せるセーブコードの数
+const JUMP_LIST_CODES: usize = 5;
 
-        // ラウンドタイプのエントリを作成
-        if let Some(ref rt) = round_type {
-            state.data.stats.round_types.entry(rt.clone()).or_default();
-        }
+/// 最近のセーブコードと監視状態でジャンプリストを作り直す
+fn refresh_jump_list(app_handle: &AppHandle) {
+    let (codes, paused) = {
+        let state = app_handle.state::<SharedState>();
+        let Ok(state) = state.lock() else {
+            return;
+        };
+        let codes: Vec<jump_list::RecentCode> = state
+            .data
+            .history
+            .iter()
+            .rev()
+            .take(JUMP_LIST_CODES)
+            .map(|entry| jump_list::RecentCode {
+                title: match &entry.round_type {
+                    Some(round_type) => format!("{} ({})", entry.timestamp, round_type),
+                    None => entry.timestamp.clone(),
+                },
+                timestamp: entry.timestamp.clone(),
+            })
+            .collect();
+        (codes, state.monitoring_paused)
+    };
+    // COMの初期化を呼び出し元のスレッドに持ち込まないよう別スレッドで更新する
+    std::thread::spawn(move || jump_list::update(&codes, paused));
+}
 
-        event = LogEvent::RoundStarted;
+/// 設定画面を前面に出す
+fn open_settings_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = app_handle.emit("open_settings", ());
     }
+}
 
-    // 敵スポーンを検出 ("Killers have been set - X X X // Round type is Y")
-    if let Some(caps) = patterns.killers_re.captures(line) {
-        let k1: u32 = caps
-            .get(1)
-            .and_then(|m| m.as_str().parse().ok())
-            .unwrap_or(0);
-        let k2: u32 = caps
-            .get(2)
-            .and_then(|m| m.as_str().parse().ok())
-            .unwrap_or(0);
-        let k3: u32 = caps
-            .get(3)
-            .and_then(|m| m.as_str().parse().ok())
-            .unwrap_or(0);
+/// メインウィンドウを前面に出して履歴タブを開く（新しいセーブコードの記録時）
+fn show_history_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = app_handle.emit("show_history", ());
+    }
+}
 
-        // ラウンドタイプが含まれている場合は更新
-        if let Some(rt_match) = caps.get(4) {
-            let round_type = rt_match.as_str().trim().to_string();
-            if state.current_round.round_type.is_none() {
-                state.current_round.round_type = Some(round_type.clone());
-                state.current_round_type = Some(round_type.clone());
-                println!("[tsst] ラウンドタイプ更新: {}", round_type);
+/// ジャンプリストから渡された起動引数を処理する（処理した引数があればtrue）
+fn handle_launch_args(app_handle: &AppHandle, args: &[String]) -> bool {
+    let mut handled = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let result = match arg.as_str() {
+            jump_list::ARG_COPY_CODE => match args.next() {
+                Some(timestamp) => {
+                    copy_code(app_handle.state::<SharedState>(), timestamp.clone()).map(|_| ())
+                }
+                None => Err(format!("{} requires a timestamp", arg)),
+            },
+            jump_list::ARG_OPEN_SETTINGS => {
+                open_settings_window(app_handle);
+                Ok(())
             }
+            jump_list::ARG_TOGGLE_MONITORING => toggle_monitoring_paused(app_handle).map(|_| ()),
+            _ => continue,
+        };
+        if let Err(e) = result {
+            println!("[tsst] Failed to handle launch argument {}: {}", arg, e);
         }
+        handled = true;
+    }
+    handled
+}
 
-        // Moon系ラウンドの場合、ラウンドタイプから固定のキラーIDを決定
-        // (ログでは "0 0 0" と記録されるため)
-        let round_type = state.current_round.round_type.as_deref();
-        let killers: Vec<u32> = if let Some(rt) = round_type {
-            if let Some(moon_id) = get_moon_terror_index(rt) {
-                // Moon系ラウンドは固定の1体のみ
-                vec![moon_id]
-            } else {
-                // 通常ラウンド: 0以外の敵コードをリストに追加
-                [k1, k2, k3].into_iter().filter(|&k| k != 0).collect()
-            }
-        } else {
-            // ラウンドタイプ不明の場合は通常処理
-            [k1, k2, k3].into_iter().filter(|&k| k != 0).collect()
-        };
-        state.current_round.killers = killers.clone();
+/// おやすみモードの設定を変更
+#[tauri::command]
+fn set_quiet_hours(
+    app_handle: AppHandle,
+    quiet_hours: QuietHoursSettings,
+) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |settings| settings.quiet_hours = quiet_hours)
+}
 
-        println!("[tsst] 敵スポーン: {:?}", killers);
-        event = LogEvent::StateChanged;
-    }
+/// おやすみモード中か（ロックできなければ抑制しない）
+fn is_quiet(app_handle: &AppHandle) -> bool {
+    app_handle
+        .state::<SharedState>()
+        .lock()
+        .map(|s| s.settings.quiet_hours.clone())
+        .is_ok_and(|quiet_hours| quiet_hours.is_active())
+}
 
-    // 死亡を検出
-    if patterns.death_re.is_match(line) {
-        state.current_round.is_dead = true;
-        println!("[tsst] 死亡検出");
-        event = LogEvent::StateChanged;
+/// メインウィンドウが前面になければタスクバーを点滅させる
+fn request_attention(app_handle: &AppHandle) {
+    if is_quiet(app_handle) {
+        return;
     }
-
-    // 復活を検出（死亡をキャンセル）
-    if patterns.reborn_re.is_match(line) {
-        state.current_round.is_dead = false;
-        println!("[tsst] 復活検出（死亡取消）");
-        event = LogEvent::StateChanged;
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+    if window.is_focused().unwrap_or(false) {
+        return;
     }
+    if let Err(e) = window.request_user_attention(Some(tauri::UserAttentionType::Informational)) {
+        println!("[tsst] Failed to request user attention: {}", e);
+    }
+}
 
-    // 生存を検出
-    if patterns.survival_re.is_match(line) {
-        println!("[tsst] 生存検出");
-        // 統計は round_end で更新するため、ここではフラグのみ
-        event = LogEvent::StateChanged;
+/// セーブコードの記録を通知（トレイ常駐中でも記録されたことが分かるように）
+fn notify_code_captured(app_handle: &AppHandle, entry: &CodeEntry) {
+    if is_quiet(app_handle) {
+        return;
     }
+    let (title, body) = code_captured_text(app_handle, entry);
 
-    // リスポーンを検出（ラウンドを無効化）
-    if patterns.respawn_re.is_match(line) {
-        if state.current_round.is_active {
-            println!("[tsst] リスポーン検出（ラウンド無効化）");
-            // ラウンドをリセット（統計に含めない）
-            state.current_round = CurrentRoundInfo::default();
-            state.current_round_type = None;
-            // リセット後は他のパターンをチェックしない
-            return LogEvent::RoundEnded;
+    // Windowsでは「コピー」ボタン付きのトーストにする（失敗したら通常の通知）
+    #[cfg(windows)]
+    {
+        match show_copy_toast(app_handle, &title, &body, entry.code.clone()) {
+            Ok(()) => return,
+            Err(e) => println!("[tsst] Failed to show toast with copy action: {}", e),
         }
     }
 
-    // ワールド移動を検出（ラウンドを無効化）
-    if patterns.left_room_re.is_match(line) {
-        // Joining wrld_ の場合はインスタンスカウンターをリセット
-        if line.contains("Joining wrld_") {
-            println!("[tsst] インスタンス変更検出（カウンターリセット）");
-            state.instance_round_counts.clear();
+    notify(app_handle, &title, &body);
+}
+
+/// セーブコード記録時の通知のタイトルと本文（テンプレートから作る）
+fn code_captured_text(app_handle: &AppHandle, entry: &CodeEntry) -> (String, String) {
+    // "2026.01.21 23:14:05" → "23:14"
+    let time = entry
+        .timestamp
+        .split_whitespace()
+        .nth(1)
+        .and_then(|t| t.get(..5))
+        .unwrap_or(&entry.timestamp);
+    let (templates, map) = current_templates(app_handle);
+    let terrors = entry.terror_names.as_deref().unwrap_or_default().join(", ");
+    let values = TemplateValues {
+        code: &entry.code,
+        terrors: &terrors,
+        round_type: entry.round_type.as_deref().unwrap_or_default(),
+        map: &map,
+        time,
+    };
+    (
+        notification_templates::render(&templates.code_captured_title, &values),
+        notification_templates::render(&templates.code_captured_body, &values),
+    )
+}
+
+/// XSOverlay / OVR Toolkitの通知設定を変更
+#[tauri::command]
+fn set_vr_notifications(
+    app_handle: AppHandle,
+    settings: VrNotificationSettings,
+) -> Result<AppSettings, String> {
+    update_validated_settings(&app_handle, |s| s.vr_notifications = settings)
+}
+
+/// テラーの出現とセーブコードの記録をXSOverlay / OVR Toolkitにも通知する
+fn bridge_vr_notification(app_handle: &AppHandle, event: &str, payload: &str) {
+    let settings = {
+        let state = app_handle.state::<SharedState>();
+        let Ok(state) = state.lock() else {
+            return;
+        };
+        state.settings.vr_notifications.clone()
+    };
+    if !settings.enabled || is_quiet(app_handle) {
+        return;
+    }
+    let (title, body) = match event {
+        "terrors_spawned" if settings.terrors_spawned => {
+            let Ok(payload) = serde_json::from_str::<TerrorsSpawnedPayload>(payload) else {
+                return;
+            };
+            let names: Vec<String> = get_terrors_data(&payload.killers, &payload.round_type)
+                .into_iter()
+                .map(|data| data.name)
+                .collect();
+            if names.is_empty() {
+                return;
+            }
+            ("テラー出現".to_string(), names.join(" & "))
         }
-        if state.current_round.is_active {
-            println!("[tsst] ワールド移動検出（ラウンド無効化）");
-            // ラウンドをリセット（統計に含めない）
-            state.current_round = CurrentRoundInfo::default();
-            state.current_round_type = None;
-            // リセット後は他のパターンをチェックしない
-            return LogEvent::RoundEnded;
+        "code_found" if settings.code_captured => {
+            let Ok(entry) = serde_json::from_str::<CodeEntry>(payload) else {
+                return;
+            };
+            code_captured_text(app_handle, &entry)
         }
-    }
+        _ => return,
+    };
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = vr_notifications::send(settings.target, &title, &body) {
+            app_errors::report(&app_handle, Severity::Warning, e);
+        }
+    });
+}
 
-    // ラウンド終了を検出（ラウンドがアクティブな場合のみ）
-    if patterns.round_end_re.is_match(line) && state.current_round.is_active {
-        let round_type = state
-            .current_round_type
-            .take()
-            .unwrap_or_else(|| "Unknown".to_string());
-        let is_dead = state.current_round.is_dead;
+#[cfg(windows)]
+fn show_copy_toast(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    code: String,
+) -> Result<(), String> {
+    use tauri_winrt_notification::Toast;
 
-        // 統計を更新
-        if is_dead {
-            state.data.stats.deaths += 1;
-            let round_stats = state
-                .data
-                .stats
-                .round_types
-                .entry(round_type.clone())
-                .or_default();
-            round_stats.deaths += 1;
-            println!(
-                "[tsst] ラウンド終了（死亡）: {} (生存: {}, 死亡: {})",
-                round_type, state.data.stats.survivals, state.data.stats.deaths
-            );
-        } else {
-            state.data.stats.survivals += 1;
-            let round_stats = state
-                .data
-                .stats
-                .round_types
-                .entry(round_type.clone())
-                .or_default();
-            round_stats.survivals += 1;
-            println!(
-                "[tsst] ラウンド終了（生存）: {} (生存: {}, 死亡: {})",
-                round_type, state.data.stats.survivals, state.data.stats.deaths
-            );
-        }
+    // 開発ビルドはAUMIDが登録されていないのでPowerShellとして表示する
+    let app_id = if tauri::is_dev() {
+        Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app_handle.config().identifier.clone()
+    };
+    let handle = app_handle.clone();
+    Toast::new(&app_id)
+        .title(title)
+        .text1(body)
+        .add_button("コピー", "copy")
+        .on_activated(move |action| {
+            if action.as_deref() == Some("copy") {
+                let state = handle.state::<SharedState>();
+                if let Err(e) = copy_code_to_clipboard(&state, code.clone()) {
+                    app_errors::report(
+                        &handle,
+                        Severity::Error,
+                        format!("Failed to copy code: {}", e),
+                    );
+                }
+            }
+            Ok(())
+        })
+        .show()
+        .map_err(|e| e.to_string())
+}
 
-        // インスタンス内ラウンドタイプカウンターを更新
-        *state.instance_round_counts.entry(round_type.clone()).or_insert(0) += 1;
-        println!(
-            "[tsst] インスタンスカウンター更新: {} = {}",
-            round_type,
-            state.instance_round_counts.get(&round_type).unwrap_or(&0)
+/// デスクトップ通知を表示（おやすみモード中は表示しない）
+fn notify(app_handle: &AppHandle, title: &str, body: &str) {
+    if is_quiet(app_handle) {
+        println!("[tsst] Notification suppressed (quiet hours): {}", title);
+        return;
+    }
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        app_errors::report(
+            app_handle,
+            Severity::Warning,
+            format!("Failed to show notification: {}", e),
         );
-
-        // ラウンド情報をリセット
-        state.current_round = CurrentRoundInfo::default();
-        event = LogEvent::RoundEnded;
     }
+}
 
-    // 新規コードが見つかったらデータに記録
-    if let Some(caps) = patterns.code_re.captures(line) {
-        if let Some(code_match) = caps.get(1) {
-            let mut parts = line.split_whitespace();
-            let date = parts.next().unwrap_or_default();
-            let time = parts.next().unwrap_or_default();
-            let timestamp = if !date.is_empty() && !time.is_empty() {
-                format!("{} {}", date, time)
-            } else {
-                "".to_string()
-            };
+/// `round_started`イベントの内容
+#[derive(Debug, Clone, Serialize)]
+struct RoundStartedPayload {
+    round_type: String,
+    map_name: Option<String>,
+}
 
-            let code = code_match.as_str().to_string();
-            let round_type = state.current_round_type.clone();
-            println!(
-                "[tsst] 新規コード発見: {} (ラウンド: {:?})",
-                code, round_type
-            );
+/// `terrors_spawned`イベントの内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TerrorsSpawnedPayload {
+    killers: Vec<u32>,
+    round_type: String,
+}
 
-            // ラウンド中の場合、テラー名とラウンドタイプ（英語）を取得
-            let (terror_names, round_type_english) = if state.current_round.is_active {
-                let rt = round_type.as_deref().unwrap_or("Classic");
-                // キラーIDからテラー名を取得
-                let names: Vec<String> = get_terrors_data(&state.current_round.killers, rt)
-                    .into_iter()
-                    .map(|d| d.name)
-                    .collect();
-                let terror_names = if names.is_empty() { None } else { Some(names) };
-                // ラウンドタイプを英語に変換
-                let rt_eng = round_type.as_ref().map(|rt| round_type_to_english(rt));
-                (terror_names, rt_eng)
-            } else {
-                (None, None)
-            };
+/// `player_died`イベントの内容
+#[derive(Debug, Clone, Serialize)]
+struct PlayerDiedPayload {
+    round_type: String,
+    map_name: Option<String>,
+}
 
-            // ラウンド中の場合、セーブコードを記録
-            if state.current_round.is_active {
-                state.current_round.save_code = Some(code.clone());
-            }
+#[derive(Debug, Clone, Serialize)]
+struct WatchlistAlertPayload {
+    terror_id: u32,
+    name: String,
+    round_type: String,
+}
 
-            state.data.history.push(CodeEntry {
-                code,
-                timestamp,
+/// ウォッチリストのテラーが出現したら通知してイベントを発行
+fn alert_watchlisted_terrors(
+    app_handle: &AppHandle,
+    killers: &[u32],
+    round_type: &str,
+    watchlist: &[u32],
+) {
+    for &id in killers.iter().filter(|id| watchlist.contains(id)) {
+        let name = get_terror_data(id, round_type).name;
+        println!("[tsst] ウォッチリストのテラーが出現: {}", name);
+        if notification_settings(app_handle).watchlist {
+            let (templates, map) = current_templates(app_handle);
+            let values = TemplateValues {
+                terrors: &name,
                 round_type,
-                terror_names,
-                round_type_english,
-            });
-
-            // 最大履歴数を超えたら古いものを削除
-            while state.data.history.len() > MAX_HISTORY {
-                state.data.history.remove(0);
-            }
-
-            if matches!(event, LogEvent::None) {
-                event = LogEvent::StateChanged;
-            }
+                map: &map,
+                time: &Local::now().format("%H:%M").to_string(),
+                ..Default::default()
+            };
+            notify(
+                app_handle,
+                &notification_templates::render(&templates.watchlist_title, &values),
+                &notification_templates::render(&templates.watchlist_body, &values),
+            );
         }
+        let _ = app_handle.emit(
+            "watchlist_terror_spawned",
+            WatchlistAlertPayload {
+                terror_id: id,
+                name,
+                round_type: round_type.to_string(),
+            },
+        );
     }
-
-    event
 }
 
-fn maybe_copy_latest_code(line: &str, state: &mut AppState) {
-    if !line.contains(WORLD_ID) {
+fn emit_session_ended(app_handle: &AppHandle, summary: &SessionSummary) {
+    println!(
+        "[tsst] セッションまとめ: {}ラウンド (生存率 {:.1}%)",
+        summary.rounds,
+        summary.survival_rate * 100.0
+    );
+    let _ = app_handle.emit("session_ended", summary);
+    if !notification_settings(app_handle).session_ended {
         return;
     }
-    let latest_code = state.data.history.last().map(|entry| entry.code.clone());
-    if let Some(code) = latest_code {
-        if state.last_copied_code.as_deref() == Some(code.as_str()) {
-            return;
-        }
-        if let Ok(mut clipboard) = Clipboard::new() {
-            let _ = clipboard.set_text(code.clone());
-            println!("[tsst] クリップボードにコピー: {}", code);
-            state.last_copied_code = Some(code);
+    notify(
+        app_handle,
+        "セッション終了",
+        &format!(
+            "{}ラウンド / 生存率 {:.1}% / コード {}件 / 最長連続生存 {}",
+            summary.rounds,
+            summary.survival_rate * 100.0,
+            summary.codes_saved,
+            summary.best_streak
+        ),
+    );
+}
+
+/// VRChatの終了を監視し、終了時にセッションを締める
+fn start_vrchat_monitor(app_handle: AppHandle, state: SharedState) {
+    std::thread::spawn(move || {
+        let mut was_running = is_vrchat_running();
+
+        loop {
+            std::thread::sleep(Duration::from_secs(10));
+
+            let is_running = is_vrchat_running();
+            if was_running && !is_running {
+                println!("[tsst] VRChat stopped");
+                let (summary, data_clone) = {
+                    let mut state = state.lock().expect("state lock");
+                    let summary = end_current_session(&mut state, now_timestamp());
+                    (summary, state.data.clone())
+                };
+                if let Err(e) = persist_data(&app_handle, &data_clone) {
+                    app_errors::report(
+                        &app_handle,
+                        Severity::Error,
+                        format!("Failed to save data: {}", e),
+                    );
+                }
+                if let Some(summary) = summary {
+                    emit_session_ended(&app_handle, &summary);
+                }
+
+                let exit_with_vrchat = state
+                    .lock()
+                    .map(|s| s.settings.exit_with_vrchat)
+                    .unwrap_or(false);
+                if exit_with_vrchat && !has_visible_window(&app_handle) {
+                    println!("[tsst] Exiting with VRChat");
+                    quit_app(&app_handle);
+                }
+            }
+            was_running = is_running;
         }
-    }
+    });
+}
+
+/// 利用者が見ているウィンドウがあるか（デスクトップオーバーレイは数えない）
+fn has_visible_window(app_handle: &AppHandle) -> bool {
+    app_handle
+        .webview_windows()
+        .iter()
+        .filter(|(label, _)| label.as_str() != DESKTOP_OVERLAY_LABEL)
+        .any(|(_, window)| window.is_visible().unwrap_or(false))
+}
+
+/// VRオーバーレイを停止してアプリを終了する
+fn quit_app(app_handle: &AppHandle) {
+    let vr_state = app_handle.state::<SharedVrState>();
+    let _ = stop_vr_overlay(vr_state.inner());
+    app_handle.exit(0);
 }
 
+/// SteamVRの起動状態を確認する間隔
+const STEAMVR_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
 /// SteamVRの状態を監視し、起動/終了に応じてVRオーバーレイを起動/停止する
 fn start_steamvr_monitor(app_handle: AppHandle, state: SharedState, vr_state: SharedVrState) {
     std::thread::spawn(move || {
         let mut was_running = is_steamvr_running();
 
         loop {
-            std::thread::sleep(Duration::from_secs(60));
+            std::thread::sleep(STEAMVR_POLL_INTERVAL);
 
             let is_running = is_steamvr_running();
             let (vr_enabled, settings) = {
                 let state = state.lock().expect("state lock");
-                (state.settings.vr_overlay_enabled, state.settings.clone())
+                (state.vr_overlay_enabled(), state.active_settings())
             };
 
             // VRオーバーレイが有効な場合のみ処理
@@ -1119,38 +6309,17 @@ fn start_steamvr_monitor(app_handle: AppHandle, state: SharedState, vr_state: Sh
                 (vr_state.process.is_some(), vr_state.waiting_for_steamvr)
             };
 
-            // SteamVRが起動した場合
             if is_running && !was_running {
                 println!("[tsst] SteamVR started");
-                if is_waiting {
-                    // 待機状態からVRオーバーレイを起動
-                    {
-                        let mut vr_state = vr_state.lock().expect("vr state lock");
-                        vr_state.waiting_for_steamvr = false;
-                    }
-                    if let Err(e) = start_vr_overlay(&app_handle, &vr_state, &settings) {
-                        println!("[tsst] Failed to start VR overlay: {}", e);
-                    } else {
-                        // 現在のラウンド情報があれば送信
-                        let current_round = {
-                            let state = state.lock().expect("state lock");
-                            state.current_round.clone()
-                        };
-                        if current_round.is_active && !current_round.killers.is_empty() {
-                            let round_type =
-                                current_round.round_type.as_deref().unwrap_or("Classic");
-                            let terror_infos: Vec<VrTerrorInfo> = get_terrors_data(&current_round.killers, round_type)
-                                .into_iter()
-                                .map(|d| d.into())
-                                .collect();
-                            let _ = send_vr_command(
-                                &vr_state,
-                                &VrCommand::UpdateTerrors {
-                                    terrors: terror_infos,
-                                    round_type: round_type.to_string(),
-                                },
-                            );
-                        }
+            }
+
+            // SteamVRが起動していて待機中なら起動を試みる（失敗しても次回また試す）
+            if is_running && is_waiting && !has_process {
+                // 監視スレッドなので接続まで待ち、失敗したら待機状態に戻す
+                if let Err(e) = start_vr_overlay_blocking(&app_handle, &vr_state, &settings) {
+                    println!("[tsst] Failed to start VR overlay: {}", e);
+                    if let Ok(mut vr_state) = vr_state.lock() {
+                        vr_state.waiting_for_steamvr = true;
                     }
                 }
             }
@@ -1174,11 +6343,17 @@ fn start_steamvr_monitor(app_handle: AppHandle, state: SharedState, vr_state: Sh
 
 fn start_log_monitor(app_handle: AppHandle, state: SharedState, vr_state: SharedVrState) {
     std::thread::spawn(move || {
-        let patterns = LogPatterns::new();
+        let mut patterns = LogPatterns::new();
+        let mut detection_settings = LogDetectionSettings::default();
 
         loop {
             let log_dir_path = {
                 let state = state.lock().expect("state lock");
+                if state.settings.log_detection != detection_settings {
+                    detection_settings = state.settings.log_detection.clone();
+                    // 設定の保存時に検証済み
+                    patterns.detection = detection_settings.compile().unwrap_or_default();
+                }
                 get_effective_log_dir(&state.settings)
             };
 
@@ -1209,8 +6384,28 @@ fn start_log_monitor(app_handle: AppHandle, state: SharedState, vr_state: Shared
                                 let mut should_emit_round_started = false;
                                 let mut should_emit_round_ended = false;
                                 let mut killers_changed = false;
-
-                                for line in buffer.lines() {
+                                let rounds_before = state_guard.data.rounds.len();
+                                let killers_before = state_guard.current_round.killers.clone();
+                                let dead_before = state_guard.current_round.is_dead;
+                                let account_before = state_guard.current_account.clone();
+                                let vr_enabled_before = state_guard.vr_overlay_enabled();
+                                let latest_code_before = state_guard
+                                    .data
+                                    .history
+                                    .last()
+                                    .map(|e| (e.code.clone(), e.timestamp.clone()));
+
+                                let lines = if state_guard.monitoring_paused {
+                                    ""
+                                } else {
+                                    buffer.as_str()
+                                };
+                                let mut raw_lines = Vec::new();
+                                for line in lines.lines() {
+                                    if state_guard.raw_log_stream && patterns.is_relevant(line) {
+                                        raw_lines.push(line.to_string());
+                                    }
+                                    record_log_line_metrics(line);
                                     let event = process_log_line(line, &patterns, &mut state_guard);
                                     match event {
                                         LogEvent::RoundStarted => {
@@ -1233,56 +6428,219 @@ fn start_log_monitor(app_handle: AppHandle, state: SharedState, vr_state: Shared
                                     maybe_copy_latest_code(line, &mut state_guard);
                                 }
                                 state_guard.last_offset = new_offset;
+                                if let Some(rounds) = state_guard.data.rounds.get(rounds_before..) {
+                                    rounds.iter().for_each(|r| metrics::record_round(r.is_dead));
+                                }
+                                for line in &raw_lines {
+                                    let _ = app_handle.emit("raw_log_line", line);
+                                }
 
                                 // 変更があればデータファイルに永続化してイベント発行
                                 if should_emit_state {
                                     let data_clone = state_guard.data.clone();
-                                    let snapshot = AppSnapshot {
-                                        settings: state_guard.settings.clone(),
-                                        history: state_guard.data.history.clone(),
-                                        latest_code: state_guard.data.history.last().cloned(),
-                                        stats: state_guard.data.stats.clone(),
-                                        survivals: state_guard.data.stats.survivals,
-                                        current_round: state_guard.current_round.clone(),
-                                        instance_round_counts: state_guard.instance_round_counts.clone(),
-                                    };
-                                    let auto_switch = state_guard.settings.auto_switch_tab;
-                                    let vr_enabled = state_guard.settings.vr_overlay_enabled;
+                                    let snapshot = state_guard.snapshot();
+                                    let show_window_on_code =
+                                        state_guard.settings.show_window_on_code;
+                                    let vr_enabled = state_guard.vr_overlay_enabled();
+                                    let haptic = haptic_command(&state_guard.settings);
+                                    let terror_options = vr_terror_options(&state_guard.settings);
                                     let killers = state_guard.current_round.killers.clone();
                                     let round_type = state_guard
                                         .current_round
                                         .round_type
                                         .clone()
                                         .unwrap_or_else(|| "Classic".to_string());
+                                    let milestones =
+                                        std::mem::take(&mut state_guard.pending_milestones);
+                                    let predicted_next_round =
+                                        state_guard.predicted_next_round.clone();
+                                    let completed_goals =
+                                        std::mem::take(&mut state_guard.pending_goals);
+                                    let session_summaries =
+                                        std::mem::take(&mut state_guard.pending_session_summaries);
+                                    // 新しいラウンドが記録された場合のみ統計パネルを更新
+                                    let vr_stats = (state_guard.settings.vr_overlay_stats_panel
+                                        && state_guard.data.rounds.len() > rounds_before)
+                                        .then(|| vr_stats_command(&state_guard));
+                                    let new_code = state_guard
+                                        .data
+                                        .history
+                                        .last()
+                                        .filter(|e| {
+                                            latest_code_before.as_ref()
+                                                != Some(&(e.code.clone(), e.timestamp.clone()))
+                                        })
+                                        .cloned();
+                                    let vr_code = new_code
+                                        .as_ref()
+                                        .filter(|_| state_guard.settings.vr_overlay_show_code)
+                                        .map(vr_code_command);
+                                    let notifications = state_guard.notifications();
+                                    let sounds = state_guard.settings.sounds.clone();
+                                    let quiet = state_guard.settings.quiet_hours.clone();
+                                    // 同じ敵の再通知を避けるため、敵の顔ぶれが変わったときだけ扱う
+                                    let terrors_spawned =
+                                        !killers.is_empty() && killers != killers_before;
+                                    // 死亡直後にラウンドが終わった場合も記録済みラウンドから拾う
+                                    let died = !dead_before
+                                        && (state_guard.current_round.is_dead
+                                            || state_guard
+                                                .data
+                                                .rounds
+                                                .get(rounds_before..)
+                                                .is_some_and(|r| r.iter().any(|r| r.is_dead)));
+                                    let account_changed =
+                                        state_guard.current_account != account_before;
+                                    let map_name = state_guard.current_round.map_name.clone();
+                                    let round_record = state_guard
+                                        .data
+                                        .rounds
+                                        .get(rounds_before..)
+                                        .and_then(|r| r.last())
+                                        .cloned();
                                     drop(state_guard); // ロックを解放してからファイル書き込み
-                                    let _ = persist_data(&app_handle, &data_clone);
+                                    if account_changed {
+                                        apply_account_overrides(&app_handle, vr_enabled_before);
+                                    }
+                                    if let Err(e) = persist_data(&app_handle, &data_clone) {
+                                        app_errors::report(
+                                            &app_handle,
+                                            Severity::Error,
+                                            format!("Failed to save data: {}", e),
+                                        );
+                                    }
                                     let _ = app_handle.emit("state_updated", &snapshot);
 
-                                    // ラウンド開始/終了イベントを発行（自動タブ切替用）
-                                    if should_emit_round_started && auto_switch {
-                                        let _ = app_handle.emit("round_started", ());
+                                    // 場面ごとのイベントを発行（スナップショットを比べなくても反応できるように）
+                                    if should_emit_round_started {
+                                        let _ = app_handle.emit(
+                                            "round_started",
+                                            RoundStartedPayload {
+                                                round_type: round_type.clone(),
+                                                map_name: map_name.clone(),
+                                            },
+                                        );
+                                    }
+                                    if terrors_spawned {
+                                        let _ = app_handle.emit(
+                                            "terrors_spawned",
+                                            TerrorsSpawnedPayload {
+                                                killers: killers.clone(),
+                                                round_type: round_type.clone(),
+                                            },
+                                        );
+                                    }
+                                    if died {
+                                        let _ = app_handle.emit(
+                                            "player_died",
+                                            PlayerDiedPayload {
+                                                round_type: round_record
+                                                    .as_ref()
+                                                    .map(|r| r.round_type.clone())
+                                                    .unwrap_or_else(|| round_type.clone()),
+                                                map_name: map_name.clone(),
+                                            },
+                                        );
+                                    }
+                                    if should_emit_round_ended {
+                                        // 記録されたラウンド（記録されなかった場合はnull）
+                                        let _ = app_handle.emit("round_ended", &round_record);
+                                    }
+                                    if let Some(entry) = &new_code {
+                                        let _ = app_handle.emit("code_found", entry);
+                                    }
+                                    if should_emit_round_ended {
+                                        let _ = app_handle
+                                            .emit("next_round_predicted", &predicted_next_round);
+                                    }
+
+                                    for milestone in &milestones {
+                                        println!("[tsst] マイルストーン達成: {}", milestone.title);
+                                        let _ = app_handle.emit("milestone_unlocked", milestone);
+                                        if notifications.milestones {
+                                            notify(
+                                                &app_handle,
+                                                "マイルストーン達成",
+                                                &milestone.title,
+                                            );
+                                        }
+                                    }
+                                    if let Some(entry) =
+                                        new_code.as_ref().filter(|_| notifications.code_captured)
+                                    {
+                                        notify_code_captured(&app_handle, entry);
+                                    }
+                                    if new_code.is_some() && show_window_on_code {
+                                        show_history_window(&app_handle);
+                                    } else if new_code.is_some() && notifications.taskbar_flash {
+                                        request_attention(&app_handle);
+                                    }
+                                    if new_code.is_some() {
+                                        metrics::record_code_captured();
+                                        refresh_jump_list(&app_handle);
+                                    }
+                                    for goal in &completed_goals {
+                                        println!("[tsst] 目標達成: {}", goal.title);
+                                        let _ = app_handle.emit("goal_completed", goal);
+                                    }
+                                    for summary in &session_summaries {
+                                        emit_session_ended(&app_handle, summary);
+                                    }
+                                    let play_sounds = sounds.enabled
+                                        && (should_emit_round_started
+                                            || terrors_spawned
+                                            || died
+                                            || new_code.is_some())
+                                        && !quiet.is_active();
+                                    if play_sounds && should_emit_round_started {
+                                        audio::play_event(&sounds, SoundEvent::RoundStarted);
+                                    }
+                                    if play_sounds && terrors_spawned {
+                                        audio::play_terror_spawned(&sounds, &killers);
                                     }
-                                    if should_emit_round_ended && auto_switch {
-                                        let _ = app_handle.emit("round_ended", ());
+                                    if play_sounds && died {
+                                        audio::play_event(&sounds, SoundEvent::Death);
+                                    }
+                                    if play_sounds && new_code.is_some() {
+                                        audio::play_event(&sounds, SoundEvent::CodeSaved);
+                                    }
+                                    // 同じ顔ぶれのまま状態が更新されるたびに再通知しない
+                                    if terrors_spawned {
+                                        alert_watchlisted_terrors(
+                                            &app_handle,
+                                            &killers,
+                                            &round_type,
+                                            &terror_options.watchlist,
+                                        );
                                     }
 
                                     // VRオーバーレイに敵情報を送信
                                     if vr_enabled {
                                         if killers_changed && !killers.is_empty() {
-                                            let terror_infos: Vec<VrTerrorInfo> = get_terrors_data(&killers, &round_type)
-                                                .into_iter()
-                                                .map(|d| d.into())
-                                                .collect();
                                             let _ = send_vr_command(
                                                 &vr_state,
-                                                &VrCommand::UpdateTerrors {
-                                                    terrors: terror_infos,
-                                                    round_type: round_type.clone(),
-                                                },
+                                                &update_terrors_command(
+                                                    &killers,
+                                                    &round_type,
+                                                    &terror_options,
+                                                ),
                                             );
+                                            if let Some(haptic) = &haptic {
+                                                let _ = send_vr_command(&vr_state, haptic);
+                                            }
                                         }
                                         if should_emit_round_ended {
                                             let _ = send_vr_command(&vr_state, &VrCommand::Clear);
+                                            let _ = send_vr_command(
+                                                &vr_state,
+                                                &VrCommand::RoundTimer { elapsed_secs: None },
+                                            );
+                                        }
+                                        if let Some(stats) = &vr_stats {
+                                            let _ = send_vr_command(&vr_state, stats);
+                                        }
+                                        if let Some(code) = &vr_code {
+                                            let _ = send_vr_command(&vr_state, code);
                                         }
                                     }
                                 }
@@ -1291,22 +6649,138 @@ fn start_log_monitor(app_handle: AppHandle, state: SharedState, vr_state: Shared
                     }
                 }
             }
+            let copy_prompt = state
+                .lock()
+                .ok()
+                .and_then(|mut s| s.pending_copy_prompt.take());
+            if let Some(entry) = copy_prompt {
+                prompt_copy(&app_handle, &vr_state, &entry);
+            }
+            send_vr_round_timer(&state, &vr_state);
+            refresh_tray(&app_handle, &state);
             std::thread::sleep(Duration::from_secs(1));
         }
     });
 }
 
+/// コード記録後にトレイのバッジを表示しておく時間
+const NEW_CODE_BADGE_DURATION: Duration = Duration::from_secs(60);
+
+fn current_tray_status(state: &AppState) -> TrayStatus {
+    if state.current_round.is_active {
+        if state.current_round.is_dead {
+            TrayStatus::Dead
+        } else {
+            TrayStatus::RoundActive
+        }
+    } else if state
+        .last_code_captured_at
+        .is_some_and(|t| t.elapsed() < NEW_CODE_BADGE_DURATION)
+    {
+        TrayStatus::NewCode
+    } else {
+        TrayStatus::Idle
+    }
+}
+
+/// トレイのツールチップ（最新コードの記録時刻・ラウンドタイプ・セッションの生存数）
+fn tray_tooltip(state: &AppState) -> String {
+    let latest = state
+        .data
+        .history
+        .last()
+        .map(|entry| entry.timestamp.as_str())
+        .unwrap_or("なし");
+    let round = if state.current_round.is_active {
+        state.current_round.round_type.as_deref().unwrap_or("不明")
+    } else {
+        "待機中"
+    };
+    let (survivals, rounds) = session_round_counts(state);
+    format!(
+        "ToN Simple Save Tool\n最新コード: {}\nラウンド: {}\nセッション生存: {}/{}",
+        latest, round, survivals, rounds
+    )
+}
+
+/// 状態が変わっていればトレイアイコンとツールチップを更新する
+fn refresh_tray(app_handle: &AppHandle, state: &SharedState) {
+    let (status, tooltip) = {
+        let mut state = state.lock().expect("state lock");
+        let status = current_tray_status(&state);
+        let tooltip = tray_tooltip(&state);
+        let status = (state.tray_status != Some(status)).then_some(status);
+        let tooltip = (state.tray_tooltip.as_ref() != Some(&tooltip)).then_some(tooltip);
+        if status.is_none() && tooltip.is_none() {
+            return;
+        }
+        if status.is_some() {
+            state.tray_status = status;
+        }
+        if tooltip.is_some() {
+            state.tray_tooltip = tooltip.clone();
+        }
+        (status, tooltip)
+    };
+    let Some(tray) = app_handle.tray_by_id(tray::TRAY_ID) else {
+        return;
+    };
+    if let (Some(status), Some(base)) = (status, app_handle.default_window_icon()) {
+        if let Err(e) = tray.set_icon(Some(tray::status_icon(base, status))) {
+            println!("[tsst] Failed to update tray icon: {}", e);
+        }
+    }
+    if let Some(tooltip) = tooltip {
+        if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+            println!("[tsst] Failed to update tray tooltip: {}", e);
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let shared_state: SharedState = Arc::new(Mutex::new(AppState::default()));
     let shared_vr_state: SharedVrState = Arc::new(Mutex::new(VrOverlayState::default()));
 
+    let mut context = tauri::generate_context!();
+    if headless::requested(std::env::args().skip(1)) {
+        headless::enable();
+        // メインウィンドウを作らない（WebViewを初期化しない）
+        context.config_mut().app.windows.clear();
+    }
+
     tauri::Builder::default()
         .manage(shared_state)
         .manage(shared_vr_state)
+        .manage(EventServer::default())
+        .manage(RestApi::default())
+        .manage(OscChatbox::default())
+        .manage(Shocker::default())
+        .manage(HeartRateMonitor::default())
+        .manage(RgbSync::default())
+        .manage(CloudBackup::default())
+        // ジャンプリストから起動された2つ目のプロセスの引数を起動中のアプリで処理する
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            if !handle_launch_args(app, &args) {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+        }))
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                        handle_hotkey(app, shortcut);
+                    }
+                })
+                .build(),
+        )
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_autostart::init(
             MacosLauncher::LaunchAgent,
             None,
@@ -1316,7 +6790,21 @@ pub fn run() {
             let app_handle = app.handle().clone();
 
             // 設定ファイルを読み込み
-            if let Some(settings) = load_settings(&app_handle) {
+            if let Some(mut settings) = load_settings(&app_handle) {
+                // 以前の版が設定ファイルに書いていた秘密情報は資格情報ストアに移して消す
+                match move_secrets_to_keyring(&mut settings) {
+                    Ok(true) => {
+                        if let Err(e) = persist_settings(&app_handle, &settings) {
+                            println!("[tsst] Failed to remove secrets from settings: {}", e);
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(e) => app_errors::report(
+                        &app_handle,
+                        Severity::Warning,
+                        format!("Failed to move secrets to the credential store: {}", e),
+                    ),
+                }
                 if let Ok(mut state) = app.state::<SharedState>().lock() {
                     state.settings = settings;
                 }
@@ -1336,60 +6824,179 @@ pub fn run() {
                     state
                         .lock()
                         .ok()
-                        .map(|s| (s.settings.vr_overlay_enabled, s.settings.clone()))
+                        .map(|s| (s.vr_overlay_enabled(), s.active_settings()))
                 };
 
                 if let Some((true, settings)) = should_start_vr {
                     let vr_state = app.state::<SharedVrState>();
-                    if is_steamvr_running() {
-                        let _ = start_vr_overlay(&app_handle, vr_state.inner(), &settings);
-                    } else {
-                        // SteamVRが起動していない場合は待機状態にする
-                        if let Ok(mut state) = vr_state.lock() {
-                            state.waiting_for_steamvr = true;
-                            println!("[tsst] SteamVR not running at startup, waiting for SteamVR to start...");
-                        }
+                    if let Err(e) = start_vr_overlay(&app_handle, vr_state.inner(), &settings) {
+                        app_errors::report(&app_handle, Severity::Error, e);
                     }
                 }
             }
 
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.hide();
+                let (compact_mode, start_minimized) = app
+                    .state::<SharedState>()
+                    .lock()
+                    .ok()
+                    .map(|s| (s.settings.compact_mode.clone(), s.settings.start_minimized))
+                    .unwrap_or_else(|| (CompactModeSettings::default(), default_start_minimized()));
+                // 前回コンパクト表示で終了していればそのサイズで開く
+                if compact_mode.enabled {
+                    let _ = apply_compact_mode(&window, &compact_mode, None);
+                }
+                if !start_minimized {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+
+            // デスクトップオーバーレイを前回の位置で開く
+            let desktop_overlay = app
+                .state::<SharedState>()
+                .lock()
+                .ok()
+                .map(|s| s.settings.desktop_overlay.clone())
+                .filter(|overlay| overlay.enabled && !headless::is_enabled());
+            if let Some(overlay) = desktop_overlay {
+                if let Err(e) = open_desktop_overlay(&app_handle, &overlay) {
+                    app_errors::report(
+                        &app_handle,
+                        Severity::Error,
+                        format!("Failed to open desktop overlay: {}", e),
+                    );
+                }
             }
 
-            let show_item = tauri::menu::MenuItemBuilder::new("設定")
-                .id("show")
-                .build(app)?;
-            let quit_item = tauri::menu::MenuItemBuilder::new("終了")
-                .id("quit")
-                .build(app)?;
-            let tray_menu = tauri::menu::Menu::with_items(app, &[&show_item, &quit_item])?;
+            if !headless::is_enabled() {
+                let show_item = tauri::menu::MenuItemBuilder::new("設定")
+                    .id("show")
+                    .build(app)?;
+                let quit_item = tauri::menu::MenuItemBuilder::new("終了")
+                    .id("quit")
+                    .build(app)?;
+                let tray_menu = tauri::menu::Menu::with_items(app, &[&show_item, &quit_item])?;
+
+                tauri::tray::TrayIconBuilder::with_id(tray::TRAY_ID)
+                    .icon(
+                        app.default_window_icon()
+                            .cloned()
+                            .expect("failed to get default window icon"),
+                    )
+                    .menu(&tray_menu)
+                    .on_menu_event(|app, event| match event.id().as_ref() {
+                        "show" => open_settings_window(app),
+                        "quit" => quit_app(app),
+                        _ => {}
+                    })
+                    .build(app)?;
+            }
 
-            tauri::tray::TrayIconBuilder::new()
-                .icon(
-                    app.default_window_icon()
-                        .cloned()
-                        .expect("failed to get default window icon"),
-                )
-                .menu(&tray_menu)
-                .on_menu_event(|app, event| match event.id().as_ref() {
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                            let _ = app.emit("open_settings", ());
-                        }
-                    }
-                    "quit" => {
-                        // VRオーバーレイを停止
-                        let vr_state = app.state::<SharedVrState>();
-                        let _ = stop_vr_overlay(vr_state.inner());
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
-                .build(app)?;
+            refresh_jump_list(&app_handle);
+            let hotkey_settings = {
+                let state = app.state::<SharedState>();
+                let state = state.lock().expect("state lock");
+                state.settings.hotkeys.clone()
+            };
+            if let Err(e) = hotkeys::register(&app_handle, &hotkey_settings) {
+                app_errors::report(
+                    &app_handle,
+                    Severity::Warning,
+                    format!("Failed to register hotkeys: {}", e),
+                );
+            }
+            // バックエンドが発行したイベントをWebSocketとSSEでも配信する
+            for event in event_server::FORWARDED_EVENTS {
+                let handle = app_handle.clone();
+                app.listen_any(event, move |e| {
+                    handle.state::<EventServer>().broadcast(event, e.payload());
+                    handle.state::<RestApi>().broadcast(event, e.payload());
+                });
+            }
+            if headless::is_enabled() {
+                for event in headless::PRINTED_EVENTS {
+                    app.listen_any(event, move |e| headless::print_event(event, e.payload()));
+                }
+            }
+            for event in WebhookEvent::ALL {
+                let handle = app_handle.clone();
+                app.listen_any(event.event_name(), move |e| {
+                    dispatch_webhooks(&handle, event, e.payload());
+                });
+            }
+            {
+                let handle = app_handle.clone();
+                app.listen_any("terrors_spawned", move |e| {
+                    send_terror_chatbox(&handle, e.payload());
+                });
+            }
+            {
+                let handle = app_handle.clone();
+                app.listen_any("player_died", move |_| trigger_shock_on_death(&handle));
+            }
+            for event in ["terrors_spawned", "round_ended", "code_found"] {
+                let handle = app_handle.clone();
+                app.listen_any(event, move |e| {
+                    sync_rgb_lighting(&handle, event, e.payload());
+                });
+            }
+            for event in ["terrors_spawned", "code_found"] {
+                let handle = app_handle.clone();
+                app.listen_any(event, move |e| {
+                    bridge_vr_notification(&handle, event, e.payload());
+                });
+            }
+            let event_server_settings = {
+                let state = app.state::<SharedState>();
+                let state = state.lock().expect("state lock");
+                state.settings.event_server.clone()
+            };
+            if let Err(e) = configure_event_server(&app_handle, &event_server_settings) {
+                app_errors::report(
+                    &app_handle,
+                    Severity::Warning,
+                    format!("Failed to start event server: {}", e),
+                );
+            }
+            let rest_api_settings = {
+                let state = app.state::<SharedState>();
+                let state = state.lock().expect("state lock");
+                state.settings.rest_api.clone()
+            };
+            if let Err(e) = app
+                .state::<RestApi>()
+                .configure(&rest_api_settings, rest_api_handler(&app_handle))
+            {
+                app_errors::report(
+                    &app_handle,
+                    Severity::Warning,
+                    format!("Failed to start REST API: {}", e),
+                );
+            }
+            let heart_rate_settings = {
+                let state = app.state::<SharedState>();
+                let state = state.lock().expect("state lock");
+                state.settings.heart_rate.clone()
+            };
+            if let Err(e) = app
+                .state::<HeartRateMonitor>()
+                .configure(&heart_rate_settings, heart_rate_handler(&app_handle))
+            {
+                app_errors::report(
+                    &app_handle,
+                    Severity::Warning,
+                    format!("Failed to start heart rate monitor: {}", e),
+                );
+            }
+
+            // 起動していない状態でジャンプリストから起動された場合
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            handle_launch_args(&app_handle, &args);
 
+            start_cloud_backup_worker(app_handle.clone());
+            start_terror_data_updater(app_handle.clone());
             start_log_monitor(
                 app_handle.clone(),
                 app.state::<SharedState>().inner().clone(),
@@ -1403,23 +7010,128 @@ pub fn run() {
                 app.state::<SharedVrState>().inner().clone(),
             );
 
+            start_update_checker(
+                app_handle.clone(),
+                app.state::<SharedState>().inner().clone(),
+            );
+
+            // VRChat監視スレッドを開始
+            start_vrchat_monitor(
+                app_handle.clone(),
+                app.state::<SharedState>().inner().clone(),
+            );
+
             Ok(())
         })
-        .on_window_event(|window, event| {
-            if let WindowEvent::CloseRequested { api, .. } = event {
+        .on_window_event(|window, event| match event {
+            WindowEvent::CloseRequested { api, .. } => {
                 api.prevent_close();
                 let _ = window.hide();
             }
+            WindowEvent::Moved(_) | WindowEvent::Resized(_) => save_window_bounds(window),
+            _ => {}
         })
         .invoke_handler(tauri::generate_handler![
             get_state,
-            set_log_dir,
-            set_auto_switch_tab,
+            get_recent_errors,
+            get_history_page,
+            validate_log_dir,
+            detect_log_dirs,
+            export_settings,
+            import_settings,
+            import_tonsavemanager,
+            export_tonsavemanager,
+            get_imported_history,
+            update_settings,
+            list_settings_profiles,
+            save_settings_profile,
+            load_settings_profile,
+            delete_settings_profile,
+            set_ui_appearance,
+            set_event_server,
+            set_rest_api,
+            regenerate_rest_api_token,
+            set_webhooks,
+            test_webhook,
+            set_osc_chatbox,
+            set_log_detection,
+            set_shock_settings,
+            engage_shock_kill_switch,
+            set_heart_rate,
+            set_open_rgb,
+            set_vr_notifications,
+            set_cloud_backup,
+            restore_from_cloud,
+            set_terror_data_updates,
+            check_terror_data_update,
+            check_for_updates,
+            set_auto_update_check,
+            reset_stats,
+            get_stats_archives,
+            get_round_records,
+            invalidate_round,
+            recompute_stats,
+            query_stats,
+            compare_periods,
+            copy_stats_summary,
+            get_sessions,
+            get_session,
+            export_rounds_csv,
+            export_data,
+            render_summary_image,
+            get_milestones,
+            get_death_causes,
+            get_combo_stats,
+            get_economy_stats,
+            set_goal,
+            delete_goal,
+            get_goals,
             set_vr_overlay_enabled,
             set_vr_overlay_position,
+            set_overlay_binary_path,
+            set_vr_overlay_transform,
+            set_vr_overlay_scale,
+            set_vr_overlay_gaze_mode,
+            set_vr_overlay_stats_panel,
+            set_vr_overlay_show_code,
+            set_vr_overlay_round_timer,
+            set_vr_haptics,
+            set_vr_overlay_theme,
+            set_vr_overlay_icon_dir,
+            set_terror_watchlist,
+            set_spoiler_mode,
+            show_vr_message,
+            set_notification_settings,
+            set_notification_templates,
+            copy_code,
+            set_sound_settings,
+            play_test_sound,
+            set_terror_sound,
+            set_quiet_hours,
+            show_desktop_overlay,
+            hide_desktop_overlay,
+            set_desktop_overlay_click_through,
+            toggle_compact_mode,
+            set_account_overrides,
+            get_known_accounts,
+            open_stats_window,
+            set_monitoring_paused,
+            set_hotkeys,
+            open_data_dir,
+            open_config_dir,
+            open_vrchat_log_dir,
+            tail_vrchat_log,
+            get_all_terrors,
+            set_raw_log_stream,
+            set_vr_overlay_panel,
+            set_vr_overlay_auto_hide,
+            show_vr_overlay_panel,
+            get_vr_overlay_status,
+            preview_vr_overlay,
+            get_overlay_log_tail,
             get_terror_info,
             get_terrors_info,
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
@@ -0,0 +1,69 @@
+//! 設定したパターンによる追加のログ検出
+//!
+//! 最後の生存者・テラーのスタン・ポイント獲得・アイテム購入はログに出る形式を確認できていないため、
+//! 組み込みのパターンは持たない。
+//! パターンを設定した項目だけを検出し、空の項目は検出しない
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct LogDetectionSettings {
+    /// 最後の生存者になった行
+    pub last_survivor_pattern: String,
+    /// テラーをスタンした行
+    pub stun_pattern: String,
+    /// ポイント獲得の行（1つ目のグループが獲得したポイント）
+    pub points_pattern: String,
+    /// アイテム購入の行（1つ目のグループがアイテム名、2つ目のグループがあれば使ったポイント）
+    pub purchase_pattern: String,
+}
+
+impl LogDetectionSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        self.compile().map(|_| ())
+    }
+
+    pub fn compile(&self) -> Result<LogDetectionPatterns, String> {
+        Ok(LogDetectionPatterns {
+            last_survivor: compile_pattern("last survivor", &self.last_survivor_pattern)?,
+            stun: compile_pattern("stun", &self.stun_pattern)?,
+            points: compile_pattern("points", &self.points_pattern)?,
+            purchase: compile_pattern("purchase", &self.purchase_pattern)?,
+        })
+    }
+}
+
+/// 設定から組み立てた検出パターン（未設定の項目は`None`）
+#[derive(Default)]
+pub struct LogDetectionPatterns {
+    pub last_survivor: Option<Regex>,
+    pub stun: Option<Regex>,
+    pub points: Option<Regex>,
+    pub purchase: Option<Regex>,
+}
+
+impl LogDetectionPatterns {
+    /// いずれかの設定済みパターンに一致する行か
+    pub fn is_match(&self, line: &str) -> bool {
+        [
+            &self.last_survivor,
+            &self.stun,
+            &self.points,
+            &self.purchase,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|re| re.is_match(line))
+    }
+}
+
+fn compile_pattern(name: &str, pattern: &str) -> Result<Option<Regex>, String> {
+    if pattern.trim().is_empty() {
+        return Ok(None);
+    }
+    Regex::new(pattern)
+        .map(Some)
+        .map_err(|e| format!("invalid {} pattern: {}", name, e))
+}
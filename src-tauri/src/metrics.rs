@@ -0,0 +1,83 @@
+//! Prometheus形式のメトリクス
+//!
+//! REST APIの`GET /metrics`で公開し、Grafanaなどでプレイの傾向をグラフにできるようにする。
+//! カウンターはアプリの起動からの値（再起動で0に戻るのはPrometheusの`rate()`が扱う）
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ROUNDS: AtomicU64 = AtomicU64::new(0);
+static DEATHS: AtomicU64 = AtomicU64::new(0);
+static SURVIVALS: AtomicU64 = AtomicU64::new(0);
+static CODES_CAPTURED: AtomicU64 = AtomicU64::new(0);
+static LINES_READ: AtomicU64 = AtomicU64::new(0);
+static PARSE_ERRORS: AtomicU64 = AtomicU64::new(0);
+/// 最後に処理したログ行の日時から処理までの遅れ（ミリ秒）
+static MONITOR_LAG_MS: AtomicU64 = AtomicU64::new(0);
+
+/// 記録したラウンド
+pub fn record_round(is_dead: bool) {
+    ROUNDS.fetch_add(1, Ordering::Relaxed);
+    if is_dead {
+        DEATHS.fetch_add(1, Ordering::Relaxed);
+    } else {
+        SURVIVALS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// 記録したセーブコード
+pub fn record_code_captured() {
+    CODES_CAPTURED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 監視で読んだログ行
+pub fn record_line_read() {
+    LINES_READ.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 読み取れなかったログ行（空行以外で日時のない行）
+pub fn record_parse_error() {
+    PARSE_ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn set_monitor_lag_ms(lag_ms: u64) {
+    MONITOR_LAG_MS.store(lag_ms, Ordering::Relaxed);
+}
+
+/// テキスト形式（version 0.0.4）で書き出す
+pub fn render() -> String {
+    let counters = [
+        ("ton_rounds_total", "Rounds recorded", &ROUNDS),
+        ("ton_deaths_total", "Rounds ended in death", &DEATHS),
+        ("ton_survivals_total", "Rounds survived", &SURVIVALS),
+        (
+            "ton_codes_captured_total",
+            "Save codes captured",
+            &CODES_CAPTURED,
+        ),
+        (
+            "ton_log_lines_read_total",
+            "Log lines read by the monitor",
+            &LINES_READ,
+        ),
+        (
+            "ton_parse_errors_total",
+            "Log lines that could not be parsed",
+            &PARSE_ERRORS,
+        ),
+    ];
+    let mut text = String::new();
+    for (name, help, counter) in counters {
+        let _ = writeln!(text, "# HELP {} {}", name, help);
+        let _ = writeln!(text, "# TYPE {} counter", name);
+        let _ = writeln!(text, "{} {}", name, counter.load(Ordering::Relaxed));
+    }
+    let lag_secs = MONITOR_LAG_MS.load(Ordering::Relaxed) as f64 / 1000.0;
+    let _ = writeln!(
+        text,
+        "# HELP ton_monitor_lag_seconds Delay between the last log line and its processing"
+    );
+    let _ = writeln!(text, "# TYPE ton_monitor_lag_seconds gauge");
+    let _ = writeln!(text, "ton_monitor_lag_seconds {}", lag_secs);
+    text
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::stats::current_streak;
+use crate::terror_updates::get_terrors_data;
+use crate::{now_timestamp, AppData, RoundRecord};
+
+const ROUNDS_MILESTONE: u32 = 100;
+const SURVIVALS_MILESTONE: u32 = 50;
+const STREAK_MILESTONE: u32 = 10;
+
+/// 達成済みマイルストーン
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    pub id: String,
+    pub title: String,
+    pub unlocked_at: String,
+}
+
+/// ラウンド終了後にマイルストーンをチェックし、新たに達成したものを記録して返す
+pub fn check_milestones(data: &mut AppData, record: &RoundRecord) -> Vec<Milestone> {
+    let mut candidates: Vec<(String, String)> = Vec::new();
+
+    let rounds_played = data.stats.survivals + data.stats.deaths;
+    if rounds_played >= ROUNDS_MILESTONE {
+        candidates.push((
+            format!("rounds_{}", ROUNDS_MILESTONE),
+            format!("{}ラウンドプレイ", ROUNDS_MILESTONE),
+        ));
+    }
+    if data.stats.survivals >= SURVIVALS_MILESTONE {
+        candidates.push((
+            format!("survivals_{}", SURVIVALS_MILESTONE),
+            format!("{}回生存", SURVIVALS_MILESTONE),
+        ));
+    }
+    if current_streak(&data.rounds) >= STREAK_MILESTONE {
+        candidates.push((
+            format!("streak_{}", STREAK_MILESTONE),
+            format!("{}連続生存", STREAK_MILESTONE),
+        ));
+    }
+    if !record.is_dead {
+        for terror in get_terrors_data(&record.killers, &record.round_type) {
+            candidates.push((
+                format!("first_survival:{}", terror.name),
+                format!("{} から初めて生存", terror.name),
+            ));
+        }
+    }
+
+    let mut unlocked = Vec::new();
+    for (id, title) in candidates {
+        if data.milestones.iter().any(|m| m.id == id) {
+            continue;
+        }
+        let milestone = Milestone {
+            id,
+            title,
+            unlocked_at: now_timestamp(),
+        };
+        data.milestones.push(milestone.clone());
+        unlocked.push(milestone);
+    }
+    unlocked
+}
@@ -0,0 +1,86 @@
+//! 通知文のテンプレート
+//!
+//! `{code}` `{terrors}` `{round_type}` `{map}` `{time}` を記録内容で置き換える
+
+use serde::{Deserialize, Serialize};
+
+/// テンプレートで使える差し込み項目
+pub const PLACEHOLDERS: [&str; 5] = ["code", "terrors", "round_type", "map", "time"];
+
+/// 通知ごとのタイトルと本文のテンプレート
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationTemplates {
+    /// 新しいセーブコードを記録したとき
+    pub code_captured_title: String,
+    pub code_captured_body: String,
+    /// ウォッチリストのテラーが出現したとき（`{terrors}`は出現したテラー名）
+    pub watchlist_title: String,
+    pub watchlist_body: String,
+}
+
+impl Default for NotificationTemplates {
+    fn default() -> Self {
+        Self {
+            code_captured_title: "セーブコードを記録しました".to_string(),
+            code_captured_body: "{time} に記録".to_string(),
+            watchlist_title: "要注意テラー出現".to_string(),
+            watchlist_body: "{terrors}".to_string(),
+        }
+    }
+}
+
+impl NotificationTemplates {
+    /// 未知の差し込み項目がないか確かめる
+    pub fn validate(&self) -> Result<(), String> {
+        for template in [
+            &self.code_captured_title,
+            &self.code_captured_body,
+            &self.watchlist_title,
+            &self.watchlist_body,
+        ] {
+            check_placeholders(template, &PLACEHOLDERS)?;
+        }
+        Ok(())
+    }
+}
+
+/// `{name}`形式の差し込み項目がすべて`allowed`に含まれるか確かめる
+///
+/// 英小文字と`_`以外を含む`{...}`は差し込み項目とみなさない（JSONの波括弧など）
+pub fn check_placeholders(template: &str, allowed: &[&str]) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(len) = rest.find('}') else {
+            break;
+        };
+        let name = &rest[..len];
+        let is_placeholder =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+        if is_placeholder && !allowed.contains(&name) {
+            return Err(format!("unknown placeholder {{{}}} in template", name));
+        }
+    }
+    Ok(())
+}
+
+/// テンプレートに差し込む値（ないものは空文字）
+#[derive(Debug, Default)]
+pub struct TemplateValues<'a> {
+    pub code: &'a str,
+    pub terrors: &'a str,
+    pub round_type: &'a str,
+    pub map: &'a str,
+    pub time: &'a str,
+}
+
+/// 差し込み項目を値で置き換える
+pub fn render(template: &str, values: &TemplateValues) -> String {
+    template
+        .replace("{code}", values.code)
+        .replace("{terrors}", values.terrors)
+        .replace("{round_type}", values.round_type)
+        .replace("{map}", values.map)
+        .replace("{time}", values.time)
+}
@@ -0,0 +1,78 @@
+//! VRChatのチャットボックスへのOSC送信
+//!
+//! 出現したテラー名を`/chatbox/input`に送り、ツールを使っていないフレンドにも頭上に表示されるようにする。
+//! VRChatのチャットボックスには送信間隔の制限があるため、前回から一定時間内のメッセージは送らない
+
+use std::net::UdpSocket;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// チャットボックスに表示できる最大文字数
+const CHATBOX_MAX_CHARS: usize = 144;
+/// 送信間隔として受け付ける範囲（秒）
+pub const MIN_INTERVAL_RANGE: (u32, u32) = (2, 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OscChatboxSettings {
+    pub enabled: bool,
+    /// VRChatのOSC受信ポート
+    pub port: u16,
+    /// 連続生存数も表示する
+    pub include_streak: bool,
+    /// 前回の送信から次に送るまでの最短間隔（秒）
+    pub min_interval_secs: u32,
+}
+
+impl Default for OscChatboxSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9000,
+            include_streak: false,
+            min_interval_secs: 3,
+        }
+    }
+}
+
+/// 送信間隔を管理する（Tauriのstateとして管理する）
+#[derive(Default)]
+pub struct OscChatbox {
+    last_sent: Mutex<Option<Instant>>,
+}
+
+impl OscChatbox {
+    /// チャットボックスに送る（間隔の制限で送らなかった場合は`Ok(false)`）
+    pub fn send(&self, settings: &OscChatboxSettings, text: &str) -> Result<bool, String> {
+        let mut last_sent = self.last_sent.lock().map_err(|_| "osc lock failed")?;
+        let interval = Duration::from_secs(u64::from(settings.min_interval_secs));
+        if last_sent.is_some_and(|at| at.elapsed() < interval) {
+            return Ok(false);
+        }
+        let text: String = text.chars().take(CHATBOX_MAX_CHARS).collect();
+        let socket = UdpSocket::bind("127.0.0.1:0").map_err(|e| e.to_string())?;
+        socket
+            .send_to(&chatbox_message(&text), ("127.0.0.1", settings.port))
+            .map_err(|e| format!("failed to send osc message: {}", e))?;
+        *last_sent = Some(Instant::now());
+        Ok(true)
+    }
+}
+
+/// `/chatbox/input`のOSCメッセージ（すぐに表示し、通知音は鳴らさない）
+fn chatbox_message(text: &str) -> Vec<u8> {
+    let mut message = Vec::new();
+    push_osc_string(&mut message, "/chatbox/input");
+    push_osc_string(&mut message, ",sTF");
+    push_osc_string(&mut message, text);
+    message
+}
+
+/// 終端のNULを付けて4バイト境界まで埋める
+fn push_osc_string(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(value.as_bytes());
+    let padding = 4 - value.len() % 4;
+    buffer.extend(std::iter::repeat_n(0, padding));
+}
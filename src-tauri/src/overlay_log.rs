@@ -0,0 +1,93 @@
+//! VRオーバーレイの出力を保存するサイズ上限付きのログファイル
+
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+/// 1ファイルあたりの最大サイズ
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// 現在のファイルを含めて残すファイル数（vr-overlay.log, .1, .2）
+const MAX_LOG_FILES: usize = 3;
+
+/// 上限を超えたら `.1`, `.2` と古い順にずらしていくログファイル
+pub struct RotatingLog {
+    path: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+/// ローテーション後のファイルパス（0は現在のファイル）
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+impl RotatingLog {
+    pub fn open(path: PathBuf) -> Self {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .ok();
+        Self { path, file, size }
+    }
+
+    pub fn write_line(&mut self, line: &str) {
+        let bytes = line.len() as u64 + 1;
+        if self.size > 0 && self.size + bytes > MAX_LOG_BYTES {
+            self.rotate();
+        }
+        if let Some(ref mut file) = self.file {
+            if writeln!(file, "{}", line).is_ok() {
+                self.size += bytes;
+            }
+        }
+    }
+
+    fn rotate(&mut self) {
+        // 閉じてからリネームする（Windowsでは開いたままだと失敗する）
+        self.file = None;
+        let _ = fs::remove_file(rotated_path(&self.path, MAX_LOG_FILES - 1));
+        for index in (0..MAX_LOG_FILES - 1).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                let _ = fs::rename(&from, rotated_path(&self.path, index + 1));
+            }
+        }
+        self.file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .ok();
+        self.size = 0;
+    }
+}
+
+/// 最新のログから末尾`lines`行を返す（足りなければローテーション済みのファイルも読む）
+pub fn tail(path: &Path, lines: usize) -> Vec<String> {
+    let mut collected: Vec<String> = Vec::new();
+    for index in 0..MAX_LOG_FILES {
+        if collected.len() >= lines {
+            break;
+        }
+        let Ok(file) = File::open(rotated_path(path, index)) else {
+            continue;
+        };
+        let mut file_lines: Vec<String> =
+            BufReader::new(file).lines().map_while(Result::ok).collect();
+        file_lines.append(&mut collected);
+        collected = file_lines;
+    }
+    let skip = collected.len().saturating_sub(lines);
+    collected.split_off(skip)
+}
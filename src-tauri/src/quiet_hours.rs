@@ -0,0 +1,121 @@
+//! おやすみモード（通知・効果音を止める時間帯や条件）
+//!
+//! 記録は止めず、通知と効果音だけを抑制する
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// おやすみモードの設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct QuietHoursSettings {
+    /// 時間帯による抑制を有効にする
+    pub enabled: bool,
+    /// 開始時刻（`HH:MM`）
+    pub start: String,
+    /// 終了時刻（`HH:MM`、開始より前なら日をまたぐ）
+    pub end: String,
+    /// VRChat以外の全画面アプリが前面にある間も抑制する
+    pub while_fullscreen: bool,
+}
+
+impl Default for QuietHoursSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "23:00".to_string(),
+            end: "07:00".to_string(),
+            while_fullscreen: false,
+        }
+    }
+}
+
+pub fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| format!("invalid time: {}", value))
+}
+
+impl QuietHoursSettings {
+    /// 指定時刻が抑制する時間帯に含まれるか
+    pub fn in_quiet_hours(&self, now: NaiveTime) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+
+    /// 今、通知・効果音を抑制すべきか
+    pub fn is_active(&self) -> bool {
+        self.in_quiet_hours(Local::now().time())
+            || (self.while_fullscreen && other_fullscreen_app_focused())
+    }
+}
+
+/// VRChat以外の全画面ウィンドウが前面にあるか
+#[cfg(windows)]
+fn other_fullscreen_app_focused() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, RECT};
+    use windows_sys::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetShellWindow, GetWindowRect, GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() || hwnd == GetShellWindow() {
+            return false;
+        }
+
+        let mut rect: RECT = std::mem::zeroed();
+        if GetWindowRect(hwnd, &mut rect) == 0 {
+            return false;
+        }
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info: MONITORINFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if GetMonitorInfoW(monitor, &mut info) == 0 {
+            return false;
+        }
+        let screen = info.rcMonitor;
+        let fullscreen = rect.left <= screen.left
+            && rect.top <= screen.top
+            && rect.right >= screen.right
+            && rect.bottom >= screen.bottom;
+        if !fullscreen {
+            return false;
+        }
+
+        // 前面のプロセスがVRChatなら抑制しない
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut pid);
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return true;
+        }
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let ok = QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut len) != 0;
+        CloseHandle(process);
+        if !ok {
+            return true;
+        }
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        !path.to_ascii_lowercase().ends_with("\\vrchat.exe")
+    }
+}
+
+#[cfg(not(windows))]
+fn other_fullscreen_app_focused() -> bool {
+    false
+}
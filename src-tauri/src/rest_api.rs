@@ -0,0 +1,396 @@
+//! ローカルのREST API
+//!
+//! スクリプトや連携ツールがフロントエンドを通さずに記録を参照できるようにする。
+//! 127.0.0.1でのみ待ち受け、`Authorization: Bearer <token>`か`?token=<token>`で認証する
+//! （OBSのブラウザソースなどヘッダーを付けられないクライアント向けにクエリでも受け付ける）。
+//! LANからの接続を許可した場合も、他の端末からはダッシュボードだけを読み取り専用のトークンで開ける
+//!
+//! - `GET /state` 現在の状態（`get_state`から設定を除いたもの）
+//! - `GET /history` セーブコードの履歴
+//! - `GET /stats` ラウンド統計
+//! - `GET /events` イベントのServer-Sent Eventsストリーム（WebSocketサーバーと同じイベント）
+//! - `POST /copy-code` セーブコードをコピー（本文に`{"timestamp": ...}`で指定、省略時は最新）
+//!
+//! Stream Deckの「API Request」系アクション向けに、本文なしで使える操作も用意する
+//!
+//! - `POST /toggle-overlay` VRオーバーレイの表示を切り替え
+//! - `POST /toggle-monitoring` ログ監視の一時停止を切り替え
+//! - `GET /stats.txt` 共有用の統計テキスト（text/plain）
+//!
+//! - `GET /metrics` Prometheus形式のメトリクス（スクレイプ設定の`authorization`でトークンを渡す）
+//!
+//! スマートフォンなどのブラウザ向けのダッシュボード（`/dashboard?token=<ダッシュボード用のトークン>`を開く）
+//!
+//! - `GET /dashboard` 履歴・統計・現在のラウンドを表示するページ
+//! - `GET /dashboard.json` ページが定期的に取得する内容
+
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// 停止の確認間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// 受け付けるリクエスト本文の上限
+const MAX_BODY_BYTES: u64 = 4096;
+/// イベントがなくても接続を保つためにコメント行を送る間隔
+const SSE_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// ダッシュボードのページ
+const DASHBOARD_HTML: &str = include_str!("../assets/dashboard.html");
+/// LANの他の端末から開けるパス
+const DASHBOARD_PATHS: [&str; 2] = ["/dashboard", "/dashboard.json"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RestApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// 認証用のトークン（初めて有効にしたときに生成する）
+    pub token: String,
+    /// LANの他の端末からの接続を受け付ける（ダッシュボードのみ）
+    pub allow_lan: bool,
+    /// ダッシュボード専用の読み取りトークン（APIのトークンをLANに流さないため分ける）
+    pub dashboard_token: String,
+}
+
+impl Default for RestApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 11399,
+            token: String::new(),
+            allow_lan: false,
+            dashboard_token: String::new(),
+        }
+    }
+}
+
+/// APIで受け付ける操作
+#[derive(Debug)]
+pub enum ApiRequest {
+    State,
+    History,
+    Stats,
+    CopyCode {
+        timestamp: Option<String>,
+    },
+    ToggleOverlay,
+    ToggleMonitoring,
+    /// 文字列で返す（text/plainで応答する）
+    StatsText,
+    /// Prometheusのテキスト形式で返す
+    Metrics,
+    /// ダッシュボードに表示する内容
+    Dashboard,
+}
+
+/// パスごとの処理
+enum Route {
+    Api(ApiRequest),
+    Events,
+    DashboardPage,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CopyCodeBody {
+    timestamp: Option<String>,
+}
+
+/// 操作を実行してJSONを返す（lib側で状態を参照する）
+pub type Handler = Arc<dyn Fn(ApiRequest) -> Result<Value, String> + Send + Sync>;
+
+type SseClients = Arc<Mutex<Vec<Sender<String>>>>;
+
+struct Running {
+    settings: RestApiSettings,
+    stop: Arc<AtomicBool>,
+    sse_clients: SseClients,
+}
+
+/// 起動中のサーバー（Tauriのstateとして管理する）
+#[derive(Default)]
+pub struct RestApi {
+    running: Mutex<Option<Running>>,
+}
+
+/// 推測されにくいトークンを作る（OSの乱数から32桁の16進数）
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("failed to generate token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// トークンを比べる（一致するまでの時間から推測されないよう、常に全体を比べる）
+pub fn token_matches(provided: &str, expected: &str) -> bool {
+    let (provided, expected) = (provided.as_bytes(), expected.as_bytes());
+    !expected.is_empty()
+        && provided.len() == expected.len()
+        && provided
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+impl RestApi {
+    /// 設定に合わせて起動・停止する（設定が変わったら起動し直す）
+    pub fn configure(&self, settings: &RestApiSettings, handler: Handler) -> Result<(), String> {
+        let mut running = self.running.lock().map_err(|_| "rest api lock failed")?;
+        if let Some(current) = running.as_ref() {
+            if settings.enabled && current.settings == *settings {
+                return Ok(());
+            }
+            current.stop.store(true, Ordering::Relaxed);
+            *running = None;
+            println!("[tsst] REST API stopped");
+        }
+        if settings.enabled {
+            if settings.token.is_empty() {
+                return Err("rest api token is empty".to_string());
+            }
+            if settings.allow_lan && settings.dashboard_token.is_empty() {
+                return Err("dashboard token is empty".to_string());
+            }
+            *running = Some(start(settings, handler)?);
+            println!(
+                "[tsst] REST API listening on {}:{}",
+                settings.host(),
+                settings.port
+            );
+        }
+        Ok(())
+    }
+
+    /// `/events`に接続中のクライアントにイベントを送る（`payload`はTauriのイベントと同じJSON）
+    pub fn broadcast(&self, event: &str, payload: &str) {
+        let Ok(running) = self.running.lock() else {
+            return;
+        };
+        let Some(running) = running.as_ref() else {
+            return;
+        };
+        let Ok(mut clients) = running.sse_clients.lock() else {
+            return;
+        };
+        if clients.is_empty() {
+            return;
+        }
+        // dataは1行にまとめる（JSONは改行を含まないが念のため）
+        let text = format!("event: {}\ndata: {}\n\n", event, payload.replace('\n', " "));
+        clients.retain(|client| client.send(text.clone()).is_ok());
+    }
+}
+
+impl RestApiSettings {
+    /// 待ち受けるアドレス
+    fn host(&self) -> &'static str {
+        if self.allow_lan {
+            "0.0.0.0"
+        } else {
+            "127.0.0.1"
+        }
+    }
+}
+
+fn start(settings: &RestApiSettings, handler: Handler) -> Result<Running, String> {
+    let server = Server::http((settings.host(), settings.port))
+        .map_err(|e| format!("failed to listen on port {}: {}", settings.port, e))?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let sse_clients: SseClients = Arc::default();
+    {
+        let stop = stop.clone();
+        let sse_clients = sse_clients.clone();
+        let settings = settings.clone();
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                match server.recv_timeout(POLL_INTERVAL) {
+                    Ok(Some(request)) => handle(request, &settings, &handler, &sse_clients),
+                    Ok(None) => {}
+                    Err(e) => {
+                        println!("[tsst] REST API receive failed: {}", e);
+                        std::thread::sleep(POLL_INTERVAL);
+                    }
+                }
+            }
+        });
+    }
+    Ok(Running {
+        settings: settings.clone(),
+        stop,
+        sse_clients,
+    })
+}
+
+fn handle(
+    mut request: Request,
+    settings: &RestApiSettings,
+    handler: &Handler,
+    sse_clients: &SseClients,
+) {
+    let from_lan = request
+        .remote_addr()
+        .is_none_or(|addr| !addr.ip().is_loopback());
+    let is_dashboard = DASHBOARD_PATHS.contains(&request_path(&request));
+    // LANからはダッシュボードのトークンだけを受け付ける（本体のトークンはこのPCの中だけで使う）
+    let authorized = (!from_lan && is_authorized(&request, &settings.token))
+        || (is_dashboard && is_authorized(&request, &settings.dashboard_token));
+    let (status, body) = if from_lan && !is_dashboard {
+        (403, json!({ "error": "only the dashboard is available" }))
+    } else if !authorized {
+        (401, json!({ "error": "unauthorized" }))
+    } else {
+        match route(&mut request) {
+            Ok(Route::Api(ApiRequest::StatsText)) => {
+                respond_text(
+                    request,
+                    handler(ApiRequest::StatsText),
+                    "text/plain; charset=utf-8",
+                );
+                return;
+            }
+            Ok(Route::Api(ApiRequest::Metrics)) => {
+                respond_text(
+                    request,
+                    handler(ApiRequest::Metrics),
+                    "text/plain; version=0.0.4; charset=utf-8",
+                );
+                return;
+            }
+            Ok(Route::DashboardPage) => {
+                respond_text(
+                    request,
+                    Ok(Value::String(DASHBOARD_HTML.to_string())),
+                    "text/html; charset=utf-8",
+                );
+                return;
+            }
+            Ok(Route::Api(api_request)) => match handler(api_request) {
+                Ok(value) => (200, value),
+                Err(e) => (500, json!({ "error": e })),
+            },
+            Ok(Route::Events) => {
+                let (tx, rx) = mpsc::channel();
+                if let Ok(mut clients) = sse_clients.lock() {
+                    clients.push(tx);
+                }
+                std::thread::spawn(move || {
+                    if let Err(e) = stream_events(request, rx) {
+                        println!("[tsst] SSE client disconnected: {}", e);
+                    }
+                });
+                return;
+            }
+            Err((status, e)) => (status, json!({ "error": e })),
+        }
+    };
+
+    let mut response = Response::from_string(body.to_string()).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+        response.add_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        println!("[tsst] REST API response failed: {}", e);
+    }
+}
+
+fn respond_text(request: Request, result: Result<Value, String>, content_type: &str) {
+    let (status, text) = match result {
+        Ok(Value::String(text)) => (200, text),
+        Ok(value) => (200, value.to_string()),
+        Err(e) => (500, e),
+    };
+    let mut response = Response::from_string(text).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+        response.add_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        println!("[tsst] REST API response failed: {}", e);
+    }
+}
+
+/// 応答ヘッダーを書いてから、切断されるかサーバーが止まるまでイベントを送り続ける
+fn stream_events(request: Request, rx: mpsc::Receiver<String>) -> std::io::Result<()> {
+    let mut writer = request.into_writer();
+    writer.write_all(
+        b"HTTP/1.1 200 OK\r\n\
+          Content-Type: text/event-stream\r\n\
+          Cache-Control: no-cache\r\n\
+          Connection: keep-alive\r\n\
+          Access-Control-Allow-Origin: *\r\n\r\n",
+    )?;
+    writer.flush()?;
+    loop {
+        match rx.recv_timeout(SSE_KEEPALIVE_INTERVAL) {
+            Ok(text) => writer.write_all(text.as_bytes())?,
+            Err(RecvTimeoutError::Timeout) => writer.write_all(b": keepalive\n\n")?,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        writer.flush()?;
+    }
+}
+
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let header = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .map(str::trim);
+    let query = request
+        .url()
+        .split_once('?')
+        .and_then(|(_, query)| query.split('&').find_map(|p| p.strip_prefix("token=")));
+    header
+        .or(query)
+        .is_some_and(|provided| token_matches(provided, token))
+}
+
+/// クエリを除いたパス
+fn request_path(request: &Request) -> &str {
+    request.url().split('?').next().unwrap_or_default()
+}
+
+fn route(request: &mut Request) -> Result<Route, (u16, String)> {
+    let path = request_path(request).to_string();
+    match (request.method(), path.as_str()) {
+        (Method::Get, "/state") => Ok(Route::Api(ApiRequest::State)),
+        (Method::Get, "/history") => Ok(Route::Api(ApiRequest::History)),
+        (Method::Get, "/stats") => Ok(Route::Api(ApiRequest::Stats)),
+        (Method::Get, "/events") => Ok(Route::Events),
+        (Method::Get, "/stats.txt") => Ok(Route::Api(ApiRequest::StatsText)),
+        (Method::Get, "/metrics") => Ok(Route::Api(ApiRequest::Metrics)),
+        (Method::Get, "/dashboard") => Ok(Route::DashboardPage),
+        (Method::Get, "/dashboard.json") => Ok(Route::Api(ApiRequest::Dashboard)),
+        (Method::Post, "/toggle-overlay") => Ok(Route::Api(ApiRequest::ToggleOverlay)),
+        (Method::Post, "/toggle-monitoring") => Ok(Route::Api(ApiRequest::ToggleMonitoring)),
+        (Method::Post, "/copy-code") => {
+            let mut body = String::new();
+            request
+                .as_reader()
+                .take(MAX_BODY_BYTES)
+                .read_to_string(&mut body)
+                .map_err(|e| (400, e.to_string()))?;
+            let body: CopyCodeBody = if body.trim().is_empty() {
+                CopyCodeBody::default()
+            } else {
+                serde_json::from_str(&body).map_err(|e| (400, format!("invalid body: {}", e)))?
+            };
+            Ok(Route::Api(ApiRequest::CopyCode {
+                timestamp: body.timestamp,
+            }))
+        }
+        (
+            _,
+            "/state" | "/history" | "/stats" | "/events" | "/copy-code" | "/stats.txt"
+            | "/toggle-overlay" | "/toggle-monitoring" | "/metrics" | "/dashboard"
+            | "/dashboard.json",
+        ) => Err((405, "method not allowed".to_string())),
+        _ => Err((404, "not found".to_string())),
+    }
+}
@@ -0,0 +1,311 @@
+//! OpenRGBのSDKサーバーとのライティング連携
+//!
+//! ラウンド中は出現したテラーの色に、セーブコードを記録したときは緑に点滅させる。
+//! 通信は専用のスレッドでまとめて行い、ログ監視を止めないようにする
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// 接続・送受信のタイムアウト
+const IO_TIMEOUT: Duration = Duration::from_secs(1);
+/// セーブコード記録時に緑にしておく時間
+const FLASH_DURATION: Duration = Duration::from_millis(800);
+/// セーブコード記録時の色
+const FLASH_COLOR: Rgb = Rgb(0x00, 0xFF, 0x40);
+/// SDKサーバーに名乗るクライアント名
+const CLIENT_NAME: &str = "ToN Simple Save Tool";
+
+const PACKET_REQUEST_CONTROLLER_COUNT: u32 = 0;
+const PACKET_REQUEST_CONTROLLER_DATA: u32 = 1;
+const PACKET_SET_CLIENT_NAME: u32 = 50;
+const PACKET_UPDATE_LEDS: u32 = 1050;
+const PACKET_SET_CUSTOM_MODE: u32 = 1100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OpenRgbSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// ラウンド中にテラーの色にする
+    pub sync_terror_color: bool,
+    /// セーブコードを記録したら緑に点滅させる
+    pub flash_on_code: bool,
+    /// ラウンド外の色
+    pub idle_color: String,
+}
+
+impl Default for OpenRgbSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "127.0.0.1".to_string(),
+            port: 6742,
+            sync_terror_color: true,
+            flash_on_code: true,
+            idle_color: "#FFFFFF".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// `#RRGGBB`形式の色
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        Some(Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8))
+    }
+
+    /// テラーデータの`"R, G, B"`形式の色
+    pub fn from_components(components: &str) -> Option<Self> {
+        let mut parts = components.split(',').map(|part| part.trim().parse::<u8>());
+        let (Some(Ok(r)), Some(Ok(g)), Some(Ok(b)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        Some(Rgb(r, g, b))
+    }
+}
+
+enum Command {
+    /// 色を変える（点滅後はこの色に戻す）
+    Set(Rgb),
+    /// 一時的に緑にして元の色に戻す
+    Flash,
+}
+
+struct Worker {
+    address: (String, u16),
+    sender: Sender<Command>,
+}
+
+/// 送信用スレッドの窓口（Tauriのstateとして管理する）
+#[derive(Default)]
+pub struct RgbSync {
+    worker: Mutex<Option<Worker>>,
+}
+
+impl RgbSync {
+    pub fn set_color(&self, settings: &OpenRgbSettings, color: Rgb) {
+        self.send(settings, Command::Set(color));
+    }
+
+    pub fn flash(&self, settings: &OpenRgbSettings) {
+        self.send(settings, Command::Flash);
+    }
+
+    /// 接続先が変わったら送信用スレッドを作り直す（古いスレッドは送信元がなくなると終わる）
+    fn send(&self, settings: &OpenRgbSettings, command: Command) {
+        let Ok(mut worker) = self.worker.lock() else {
+            return;
+        };
+        let address = (settings.host.clone(), settings.port);
+        if !matches!(worker.as_ref(), Some(w) if w.address == address) {
+            let (sender, receiver) = mpsc::channel();
+            let thread_address = address.clone();
+            std::thread::spawn(move || run(thread_address, receiver));
+            *worker = Some(Worker { address, sender });
+        }
+        if let Some(w) = worker.as_ref() {
+            let _ = w.sender.send(command);
+        }
+    }
+}
+
+fn run(address: (String, u16), receiver: mpsc::Receiver<Command>) {
+    let mut connection: Option<Connection> = None;
+    let mut current = None;
+    for command in receiver {
+        if let Command::Set(color) = command {
+            current = Some(color);
+        }
+        let conn = match connection.as_mut() {
+            Some(conn) => conn,
+            None => match Connection::open(&address) {
+                Ok(conn) => connection.insert(conn),
+                Err(e) => {
+                    println!("[tsst] OpenRGB connection failed: {}", e);
+                    continue;
+                }
+            },
+        };
+        let result = match command {
+            Command::Set(color) => conn.set_all(color),
+            Command::Flash => conn.set_all(FLASH_COLOR).and_then(|_| {
+                std::thread::sleep(FLASH_DURATION);
+                conn.set_all(current.unwrap_or(Rgb(0, 0, 0)))
+            }),
+        };
+        if let Err(e) = result {
+            println!("[tsst] OpenRGB update failed: {}", e);
+            // 次の操作で接続し直す
+            connection = None;
+        }
+    }
+}
+
+struct Connection {
+    stream: TcpStream,
+    /// コントローラーごとのLED数
+    led_counts: Vec<u16>,
+}
+
+impl Connection {
+    fn open(address: &(String, u16)) -> Result<Self, String> {
+        let addr = address
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or("openrgb address not found")?;
+        let stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT).map_err(|e| e.to_string())?;
+        stream
+            .set_read_timeout(Some(IO_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        let mut conn = Connection {
+            stream,
+            led_counts: Vec::new(),
+        };
+
+        let mut name = CLIENT_NAME.as_bytes().to_vec();
+        name.push(0);
+        conn.write_packet(0, PACKET_SET_CLIENT_NAME, &name)?;
+
+        conn.write_packet(0, PACKET_REQUEST_CONTROLLER_COUNT, &[])?;
+        let count = conn.read_packet(PACKET_REQUEST_CONTROLLER_COUNT)?;
+        let count = Reader::new(&count).u32()?;
+        for index in 0..count {
+            conn.write_packet(index, PACKET_REQUEST_CONTROLLER_DATA, &[])?;
+            let data = conn.read_packet(PACKET_REQUEST_CONTROLLER_DATA)?;
+            conn.led_counts.push(led_count(&data)?);
+        }
+        println!(
+            "[tsst] OpenRGB connected: {} controllers",
+            conn.led_counts.len()
+        );
+        Ok(conn)
+    }
+
+    /// すべてのコントローラーのLEDを同じ色にする
+    fn set_all(&mut self, color: Rgb) -> Result<(), String> {
+        let led_counts = self.led_counts.clone();
+        for (index, &count) in led_counts.iter().enumerate() {
+            self.write_packet(index as u32, PACKET_SET_CUSTOM_MODE, &[])?;
+            // data_size(u32) + LED数(u16) + 色(4バイト×LED数)
+            let size = 4 + 2 + 4 * u32::from(count);
+            let mut data = Vec::with_capacity(size as usize);
+            data.extend_from_slice(&size.to_le_bytes());
+            data.extend_from_slice(&count.to_le_bytes());
+            for _ in 0..count {
+                data.extend_from_slice(&[color.0, color.1, color.2, 0]);
+            }
+            self.write_packet(index as u32, PACKET_UPDATE_LEDS, &data)?;
+        }
+        Ok(())
+    }
+
+    fn write_packet(&mut self, device: u32, id: u32, data: &[u8]) -> Result<(), String> {
+        let mut packet = Vec::with_capacity(16 + data.len());
+        packet.extend_from_slice(b"ORGB");
+        packet.extend_from_slice(&device.to_le_bytes());
+        packet.extend_from_slice(&id.to_le_bytes());
+        packet.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        packet.extend_from_slice(data);
+        self.stream.write_all(&packet).map_err(|e| e.to_string())
+    }
+
+    fn read_packet(&mut self, expected_id: u32) -> Result<Vec<u8>, String> {
+        let mut header = [0u8; 16];
+        self.stream
+            .read_exact(&mut header)
+            .map_err(|e| e.to_string())?;
+        let mut reader = Reader::new(&header);
+        if reader.bytes(4)? != b"ORGB" {
+            return Err("invalid openrgb packet".to_string());
+        }
+        let _device = reader.u32()?;
+        let id = reader.u32()?;
+        let size = reader.u32()?;
+        let mut data = vec![0u8; size as usize];
+        self.stream
+            .read_exact(&mut data)
+            .map_err(|e| e.to_string())?;
+        if id != expected_id {
+            return Err(format!("unexpected openrgb packet {}", id));
+        }
+        Ok(data)
+    }
+}
+
+/// コントローラーデータ（プロトコル0）からLED数を読み取る
+fn led_count(data: &[u8]) -> Result<u16, String> {
+    let mut r = Reader::new(data);
+    r.u32()?; // data_size
+    r.u32()?; // type
+    for _ in 0..5 {
+        r.string()?; // name, description, version, serial, location
+    }
+    let num_modes = r.u16()?;
+    r.u32()?; // active_mode
+    for _ in 0..num_modes {
+        r.string()?; // name
+        r.bytes(4 * 9)?; // value, flags, speed_min/max, colors_min/max, speed, direction, color_mode
+        let num_colors = r.u16()?;
+        r.bytes(4 * num_colors as usize)?;
+    }
+    let num_zones = r.u16()?;
+    for _ in 0..num_zones {
+        r.string()?; // name
+        r.bytes(4 * 4)?; // type, leds_min, leds_max, leds_count
+        let matrix_len = r.u16()?;
+        r.bytes(matrix_len as usize)?;
+    }
+    r.u16()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or("openrgb packet too short")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// 長さ(u16)付きの文字列（内容は使わない）
+    fn string(&mut self) -> Result<(), String> {
+        let len = self.u16()?;
+        self.bytes(len as usize).map(|_| ())
+    }
+}
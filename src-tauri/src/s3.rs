@@ -0,0 +1,170 @@
+//! S3互換ストレージ（AWS S3・MinIO・Backblaze B2など）へのオブジェクトの読み書き
+//!
+//! 署名はAWS Signature Version 4で行う。本文のハッシュも署名に含める
+
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 通信のタイムアウト
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct S3Settings {
+    /// エンドポイントのURL（例: `https://s3.us-west-002.backblazeb2.com`）
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    /// オブジェクト名の前に付けるフォルダ（空なら直下）
+    pub prefix: String,
+    pub access_key_id: String,
+    /// `エンドポイント/バケット/オブジェクト`の形でアクセスする（MinIOなど）
+    ///
+    /// `false`なら`バケット.エンドポイント/オブジェクト`の形にする
+    pub path_style: bool,
+}
+
+impl Default for S3Settings {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            region: "us-east-1".to_string(),
+            bucket: String::new(),
+            prefix: String::new(),
+            access_key_id: String::new(),
+            path_style: true,
+        }
+    }
+}
+
+impl S3Settings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.endpoint.starts_with("http://") || self.endpoint.starts_with("https://")) {
+            return Err(format!(
+                "s3 endpoint must start with http(s)://: {}",
+                self.endpoint
+            ));
+        }
+        if self.bucket.is_empty() || self.access_key_id.is_empty() {
+            return Err("s3 bucket and access key id must be set".to_string());
+        }
+        Ok(())
+    }
+
+    /// オブジェクトのURLと、署名に使うホスト名・パス
+    fn object_location(&self, name: &str) -> (String, String, String) {
+        let (scheme, rest) = self
+            .endpoint
+            .split_once("://")
+            .unwrap_or(("https", &self.endpoint));
+        let host = rest.trim_end_matches('/').to_string();
+        let mut key = self.prefix.trim_matches('/').to_string();
+        if !key.is_empty() {
+            key.push('/');
+        }
+        key.push_str(name);
+        let key = uri_encode_path(&key);
+        let (host, path) = if self.path_style {
+            (host, format!("/{}/{}", self.bucket, key))
+        } else {
+            (format!("{}.{}", self.bucket, host), format!("/{}", key))
+        };
+        (format!("{}://{}{}", scheme, host, path), host, path)
+    }
+}
+
+/// オブジェクトを書き込む
+pub fn put_object(
+    settings: &S3Settings,
+    secret_key: &str,
+    name: &str,
+    payload: &[u8],
+) -> Result<(), String> {
+    let (url, host, path) = settings.object_location(name);
+    let request = signed_request(settings, secret_key, "PUT", &url, &host, &path, payload);
+    request
+        .set("Content-Type", "application/json")
+        .send_bytes(payload)
+        .map(|_| ())
+        .map_err(|e| format!("s3 upload failed: {}", e))
+}
+
+/// オブジェクトを読み込む
+pub fn get_object(settings: &S3Settings, secret_key: &str, name: &str) -> Result<String, String> {
+    let (url, host, path) = settings.object_location(name);
+    signed_request(settings, secret_key, "GET", &url, &host, &path, &[])
+        .call()
+        .map_err(|e| format!("s3 download failed: {}", e))?
+        .into_string()
+        .map_err(|e| e.to_string())
+}
+
+fn signed_request(
+    settings: &S3Settings,
+    secret_key: &str,
+    method: &str,
+    url: &str,
+    host: &str,
+    path: &str,
+    payload: &[u8],
+) -> ureq::Request {
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex(&Sha256::digest(payload));
+
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, path, host, payload_hash, amz_date, signed_headers, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, settings.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    for part in [settings.region.as_str(), "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        settings.access_key_id, scope, signed_headers, signature
+    );
+
+    ureq::request(method, url)
+        .timeout(REQUEST_TIMEOUT)
+        .set("x-amz-content-sha256", &payload_hash)
+        .set("x-amz-date", &amz_date)
+        .set("Authorization", &authorization)
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// パスの各部分をURIエンコードする（`/`はそのまま）
+fn uri_encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
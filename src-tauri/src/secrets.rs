@@ -0,0 +1,33 @@
+//! パスワード・APIキーなどの秘密情報の保存
+//!
+//! 設定ファイルやエクスポート・プロファイルに残らないよう、OSの資格情報ストアに保存する
+
+/// 資格情報ストアのサービス名
+const KEYRING_SERVICE: &str = "ton-simple-save-tool";
+
+/// 保存する（空文字なら削除する）
+pub fn store(account: &str, secret: &str) -> Result<(), String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, account).map_err(|e| e.to_string())?;
+    if secret.is_empty() {
+        return match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        };
+    }
+    entry.set_password(secret).map_err(|e| e.to_string())
+}
+
+pub fn load(account: &str) -> Result<String, String> {
+    keyring::Entry::new(KEYRING_SERVICE, account)
+        .and_then(|entry| entry.get_password())
+        .map_err(|e| format!("failed to read {} credentials: {}", account, e))
+}
+
+/// 受け取った値があれば保存して設定からは消す（保存したら`true`）
+pub fn take_into_store(account: &str, secret: &mut String) -> Result<bool, String> {
+    if secret.is_empty() {
+        return Ok(false);
+    }
+    store(account, &std::mem::take(secret))?;
+    Ok(true)
+}
@@ -0,0 +1,264 @@
+//! OpenShock / PiShockとの連携（死亡時に振動・電気刺激を送る）
+//!
+//! 既定では無効で、強さ・長さ・間隔には設定に関わらず上限を設ける。
+//! キルスイッチを入れると以後の送信を止め、OpenShockでは動作中の刺激も停止する
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::secrets;
+
+/// 強さの上限（%）
+const HARD_MAX_INTENSITY: u8 = 100;
+/// 1回の長さとして受け付ける範囲（ミリ秒、OpenShockは300ms未満を受け付けない）
+const DURATION_MS_RANGE: (u32, u32) = (300, 5000);
+/// 送信間隔の下限（秒）
+const MIN_COOLDOWN_SECS: u32 = 10;
+/// APIの応答を待つ時間
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// APIに送る送信元の名前
+const SENDER_NAME: &str = "ToN Simple Save Tool";
+/// 資格情報ストアでのアカウント名
+const OPENSHOCK_SECRET: &str = "openshock";
+const PISHOCK_SECRET: &str = "pishock";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShockProvider {
+    #[default]
+    OpenShock,
+    PiShock,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShockMode {
+    #[default]
+    Vibrate,
+    Shock,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OpenShockSettings {
+    pub api_url: String,
+    /// APIトークン（設定ファイルには書かず、受け取ったら資格情報ストアに移す）
+    #[serde(skip_serializing)]
+    pub token: String,
+    pub shocker_id: String,
+}
+
+impl Default for OpenShockSettings {
+    fn default() -> Self {
+        Self {
+            api_url: "https://api.openshock.app".to_string(),
+            token: String::new(),
+            shocker_id: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct PiShockSettings {
+    pub username: String,
+    /// APIキー（設定ファイルには書かず、受け取ったら資格情報ストアに移す）
+    #[serde(skip_serializing)]
+    pub api_key: String,
+    /// 共有コード
+    pub share_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ShockSettings {
+    pub enabled: bool,
+    pub provider: ShockProvider,
+    pub mode: ShockMode,
+    /// 強さ（%、`max_intensity`を超えない）
+    pub intensity: u8,
+    /// 利用者が決める強さの上限（%）
+    pub max_intensity: u8,
+    /// 1回の長さ（ミリ秒）
+    pub duration_ms: u32,
+    /// 前回の送信から次に送るまでの最短間隔（秒）
+    pub cooldown_secs: u32,
+    pub openshock: OpenShockSettings,
+    pub pishock: PiShockSettings,
+}
+
+impl Default for ShockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: ShockProvider::default(),
+            mode: ShockMode::default(),
+            intensity: 10,
+            max_intensity: 25,
+            duration_ms: 1000,
+            cooldown_secs: 30,
+            openshock: OpenShockSettings::default(),
+            pishock: PiShockSettings::default(),
+        }
+    }
+}
+
+impl ShockSettings {
+    /// 受け取ったトークン・APIキーを資格情報ストアに移す（移したものがあれば`true`）
+    pub fn store_secrets(&mut self) -> Result<bool, String> {
+        let openshock = secrets::take_into_store(OPENSHOCK_SECRET, &mut self.openshock.token)?;
+        let pishock = secrets::take_into_store(PISHOCK_SECRET, &mut self.pishock.api_key)?;
+        Ok(openshock || pishock)
+    }
+
+    /// 上限に収め、有効にする場合は接続先の設定がそろっているか確かめる
+    pub fn normalize(&mut self) -> Result<(), String> {
+        self.max_intensity = self.max_intensity.clamp(1, HARD_MAX_INTENSITY);
+        self.intensity = self.intensity.clamp(1, self.max_intensity);
+        self.duration_ms = self
+            .duration_ms
+            .clamp(DURATION_MS_RANGE.0, DURATION_MS_RANGE.1);
+        self.cooldown_secs = self.cooldown_secs.max(MIN_COOLDOWN_SECS);
+        if !self.enabled {
+            return Ok(());
+        }
+        let missing = match self.provider {
+            ShockProvider::OpenShock => self.openshock.shocker_id.is_empty(),
+            ShockProvider::PiShock => {
+                self.pishock.username.is_empty() || self.pishock.share_code.is_empty()
+            }
+        };
+        if missing {
+            return Err(format!("{:?} credentials are not set", self.provider));
+        }
+        Ok(())
+    }
+
+    /// 有効にする場合、使う接続先のトークン・APIキーが資格情報ストアにあるか確かめる
+    pub fn check_credentials(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        secrets::load(match self.provider {
+            ShockProvider::OpenShock => OPENSHOCK_SECRET,
+            ShockProvider::PiShock => PISHOCK_SECRET,
+        })
+        .map(|_| ())
+        .map_err(|_| format!("{:?} credentials are not set", self.provider))
+    }
+}
+
+/// 送信間隔とキルスイッチの状態（Tauriのstateとして管理する）
+#[derive(Default)]
+pub struct Shocker {
+    last_triggered: Mutex<Option<Instant>>,
+    killed: AtomicBool,
+}
+
+impl Shocker {
+    /// 設定に従って送る（無効・キルスイッチ中・間隔内なら送らずに`Ok(false)`）
+    pub fn trigger(&self, settings: &ShockSettings) -> Result<bool, String> {
+        if !settings.enabled || self.killed.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        {
+            let mut last = self
+                .last_triggered
+                .lock()
+                .map_err(|_| "shock lock failed")?;
+            let cooldown =
+                Duration::from_secs(u64::from(settings.cooldown_secs.max(MIN_COOLDOWN_SECS)));
+            if last.is_some_and(|at| at.elapsed() < cooldown) {
+                return Ok(false);
+            }
+            // 失敗しても連続で送らないよう、送信前に記録する
+            *last = Some(Instant::now());
+        }
+        let mut settings = settings.clone();
+        settings.normalize()?;
+        match settings.provider {
+            ShockProvider::OpenShock => send_openshock(&settings, control_type(settings.mode)),
+            ShockProvider::PiShock => send_pishock(&settings),
+        }?;
+        Ok(true)
+    }
+
+    /// キルスイッチを入れ、OpenShockなら動作中の刺激も止める
+    pub fn kill(&self, settings: &ShockSettings) -> Result<(), String> {
+        self.killed.store(true, Ordering::SeqCst);
+        match settings.provider {
+            ShockProvider::OpenShock if !settings.openshock.shocker_id.is_empty() => {
+                send_openshock(settings, "Stop")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// キルスイッチを戻す（設定から改めて有効にしたとき）
+    pub fn release(&self) {
+        self.killed.store(false, Ordering::SeqCst);
+    }
+}
+
+fn control_type(mode: ShockMode) -> &'static str {
+    match mode {
+        ShockMode::Vibrate => "Vibrate",
+        ShockMode::Shock => "Shock",
+    }
+}
+
+fn send_openshock(settings: &ShockSettings, control_type: &str) -> Result<(), String> {
+    let openshock = &settings.openshock;
+    let token = secrets::load(OPENSHOCK_SECRET)?;
+    let url = format!(
+        "{}/2/shockers/control",
+        openshock.api_url.trim_end_matches('/')
+    );
+    let body = json!({
+        "shocks": [{
+            "id": openshock.shocker_id,
+            "type": control_type,
+            "intensity": settings.intensity,
+            "duration": settings.duration_ms,
+            "exclusive": true,
+        }],
+        "customName": SENDER_NAME,
+    });
+    ureq::post(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .set("OpenShockToken", &token)
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .map(|_| ())
+        .map_err(|e| format!("OpenShock request failed: {}", e))
+}
+
+fn send_pishock(settings: &ShockSettings) -> Result<(), String> {
+    let pishock = &settings.pishock;
+    let api_key = secrets::load(PISHOCK_SECRET)?;
+    let op = match settings.mode {
+        ShockMode::Shock => 0,
+        ShockMode::Vibrate => 1,
+    };
+    // PiShockの長さは秒単位
+    let duration_secs = (settings.duration_ms / 1000).max(1);
+    let body = json!({
+        "Username": pishock.username,
+        "Apikey": api_key,
+        "Code": pishock.share_code,
+        "Name": SENDER_NAME,
+        "Op": op,
+        "Duration": duration_secs,
+        "Intensity": settings.intensity,
+    });
+    ureq::post("https://do.pishock.com/api/apioperate")
+        .timeout(REQUEST_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .map(|_| ())
+        .map_err(|e| format!("PiShock request failed: {}", e))
+}
@@ -0,0 +1,489 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use crate::terror_data::round_type_to_english;
+use crate::terror_updates::get_terrors_data;
+use crate::{RoundRecord, RoundStats};
+
+/// 統計クエリの絞り込み条件（未指定の項目は条件なし）
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct StatsFilter {
+    /// ラウンドタイプ（ログの表記か英語名）
+    pub round_type: Option<String>,
+    pub map_name: Option<String>,
+    pub terror_id: Option<u32>,
+    /// 開始日時（"2026.01.21" または "2026.01.21 20:00:00" 形式）
+    pub from: Option<String>,
+    /// 終了日時（日付のみの場合はその日を含む）
+    pub to: Option<String>,
+    pub account: Option<String>,
+}
+
+/// ラウンド所要時間の集計
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DurationStats {
+    /// ラウンド中の合計プレイ時間（秒）
+    pub playtime_secs: u64,
+    pub avg_duration_secs: Option<f64>,
+    pub median_duration_secs: Option<u32>,
+}
+
+impl DurationStats {
+    fn from_durations(mut durations: Vec<u32>) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+        durations.sort_unstable();
+        let total: u64 = durations.iter().map(|&d| d as u64).sum();
+        let mid = durations.len() / 2;
+        let median = if durations.len().is_multiple_of(2) {
+            (durations[mid - 1] + durations[mid]) / 2
+        } else {
+            durations[mid]
+        };
+        Self {
+            playtime_secs: total,
+            avg_duration_secs: Some(total as f64 / durations.len() as f64),
+            median_duration_secs: Some(median),
+        }
+    }
+}
+
+/// ポイント獲得・アイテム購入の集計
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct EconomyStats {
+    pub points_earned: u64,
+    pub points_spent: u64,
+    pub items_bought: u32,
+}
+
+/// ポイント獲得・アイテム購入1回分の記録（統計の再構築に使う）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EconomyEvent {
+    pub timestamp: String,
+    #[serde(default)]
+    pub points_earned: u64,
+    #[serde(default)]
+    pub points_spent: u64,
+    #[serde(default)]
+    pub items_bought: u32,
+}
+
+impl EconomyStats {
+    pub fn add(&mut self, event: &EconomyEvent) {
+        self.points_earned += event.points_earned;
+        self.points_spent += event.points_spent;
+        self.items_bought += event.items_bought;
+    }
+}
+
+/// 統計クエリの集計結果
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct StatsQueryResult {
+    pub rounds: u32,
+    pub survivals: u32,
+    pub deaths: u32,
+    pub survival_rate: f64,
+    /// インスタンス人数別の生存率
+    pub by_lobby_size: Vec<LobbySizeBucket>,
+}
+
+/// インスタンス人数の区分ごとの集計
+#[derive(Debug, Clone, Serialize)]
+pub struct LobbySizeBucket {
+    pub label: String,
+    pub min_players: u32,
+    pub max_players: Option<u32>,
+    pub rounds: u32,
+    pub survivals: u32,
+    pub survival_rate: f64,
+}
+
+/// インスタンス人数の区分（上限なしの区分は最後）
+const LOBBY_SIZE_BUCKETS: [(u32, Option<u32>); 5] = [
+    (1, Some(4)),
+    (5, Some(8)),
+    (9, Some(12)),
+    (13, Some(16)),
+    (17, None),
+];
+
+/// 日時文字列をログと同じ "YYYY.MM.DD HH:MM:SS" 形式に揃える
+fn normalize_timestamp(value: &str) -> String {
+    value.trim().replace('-', ".").replace('T', " ")
+}
+
+/// ログ形式の日時文字列をパース
+pub fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y.%m.%d %H:%M:%S").ok()
+}
+
+/// 2つのログ日時の間隔（秒）
+pub fn duration_between(start: &str, end: &str) -> Option<u32> {
+    let start = parse_timestamp(start)?;
+    let end = parse_timestamp(end)?;
+    u32::try_from((end - start).num_seconds()).ok()
+}
+
+/// 日時が範囲内かどうか（文字列比較、終了側は指定された桁数までを比較）
+pub fn in_range(timestamp: &str, from: Option<&str>, to: Option<&str>) -> bool {
+    if let Some(from) = from {
+        if timestamp < normalize_timestamp(from).as_str() {
+            return false;
+        }
+    }
+    if let Some(to) = to {
+        let to = normalize_timestamp(to);
+        let len = to.len().min(timestamp.len());
+        if &timestamp[..len] > to.as_str() {
+            return false;
+        }
+    }
+    true
+}
+
+impl StatsFilter {
+    pub fn matches(&self, record: &RoundRecord) -> bool {
+        if let Some(ref round_type) = self.round_type {
+            if &record.round_type != round_type
+                && &round_type_to_english(&record.round_type) != round_type
+            {
+                return false;
+            }
+        }
+        if let Some(ref map_name) = self.map_name {
+            if record.map_name.as_ref() != Some(map_name) {
+                return false;
+            }
+        }
+        if let Some(terror_id) = self.terror_id {
+            if !record.killers.contains(&terror_id) {
+                return false;
+            }
+        }
+        if let Some(ref account) = self.account {
+            if record.account.as_ref() != Some(account) {
+                return false;
+            }
+        }
+        in_range(&record.ended_at, self.from.as_deref(), self.to.as_deref())
+    }
+}
+
+/// 生存率（0.0〜1.0、ラウンドなしの場合は0.0）
+pub fn survival_rate(survivals: u32, rounds: u32) -> f64 {
+    if rounds == 0 {
+        0.0
+    } else {
+        survivals as f64 / rounds as f64
+    }
+}
+
+/// 集計期間内（stats.since以降）のラウンド記録から所要時間の統計を再計算する
+pub fn refresh_duration_stats(stats: &mut RoundStats, rounds: &[RoundRecord]) {
+    let mut overall = Vec::new();
+    let mut by_type: HashMap<String, Vec<u32>> = HashMap::new();
+    for record in rounds
+        .iter()
+        .filter(|r| !r.excluded && in_range(&r.ended_at, stats.since.as_deref(), None))
+    {
+        if let Some(duration) = record.duration_secs {
+            overall.push(duration);
+            by_type
+                .entry(record.round_type.clone())
+                .or_default()
+                .push(duration);
+        }
+    }
+
+    stats.durations = DurationStats::from_durations(overall);
+    for (round_type, round_stats) in stats.round_types.iter_mut() {
+        round_stats.durations = by_type
+            .remove(round_type)
+            .map(DurationStats::from_durations)
+            .unwrap_or_default();
+    }
+}
+
+/// 直近の連続生存数（無効化されたラウンドは無視）
+pub fn current_streak(rounds: &[RoundRecord]) -> u32 {
+    rounds
+        .iter()
+        .rev()
+        .filter(|r| !r.excluded)
+        .take_while(|r| !r.is_dead)
+        .count() as u32
+}
+
+/// 最長の連続生存数（無効化されたラウンドは無視）
+pub fn best_streak<'a>(rounds: impl IntoIterator<Item = &'a RoundRecord>) -> u32 {
+    let mut best = 0;
+    let mut streak = 0;
+    for record in rounds.into_iter().filter(|r| !r.excluded) {
+        if record.is_dead {
+            streak = 0;
+        } else {
+            streak += 1;
+            best = best.max(streak);
+        }
+    }
+    best
+}
+
+/// 無効化されていないラウンドのうち条件に合うものを集計
+pub fn query_rounds(rounds: &[RoundRecord], filter: &StatsFilter) -> StatsQueryResult {
+    let mut result = StatsQueryResult::default();
+    let mut buckets: Vec<LobbySizeBucket> = LOBBY_SIZE_BUCKETS
+        .iter()
+        .map(|&(min, max)| LobbySizeBucket {
+            label: match max {
+                Some(max) => format!("{}-{}", min, max),
+                None => format!("{}+", min),
+            },
+            min_players: min,
+            max_players: max,
+            rounds: 0,
+            survivals: 0,
+            survival_rate: 0.0,
+        })
+        .collect();
+
+    for record in rounds.iter().filter(|r| !r.excluded && filter.matches(r)) {
+        result.rounds += 1;
+        if record.is_dead {
+            result.deaths += 1;
+        } else {
+            result.survivals += 1;
+        }
+
+        if let Some(count) = record.player_count {
+            if let Some(bucket) = buckets.iter_mut().find(|b| {
+                count >= b.min_players && !matches!(b.max_players, Some(max) if count > max)
+            }) {
+                bucket.rounds += 1;
+                if !record.is_dead {
+                    bucket.survivals += 1;
+                }
+            }
+        }
+    }
+
+    result.survival_rate = survival_rate(result.survivals, result.rounds);
+    for bucket in buckets.iter_mut() {
+        bucket.survival_rate = survival_rate(bucket.survivals, bucket.rounds);
+    }
+    result.by_lobby_size = buckets.into_iter().filter(|b| b.rounds > 0).collect();
+    result
+}
+
+/// テラー別の死亡数
+#[derive(Debug, Clone, Serialize)]
+pub struct DeathCause {
+    pub name: String,
+    pub color: Option<String>,
+    pub deaths: u32,
+    pub encounters: u32,
+    pub death_rate: f64,
+}
+
+/// テラー別の死亡ランキング（死亡数の多い順）
+///
+/// ログには直接の死因が記録されないため、死亡したラウンドに出現していた
+/// テラー全員に1回ずつ死亡を計上する。
+pub fn death_causes(rounds: &[RoundRecord]) -> Vec<DeathCause> {
+    let mut causes: HashMap<String, DeathCause> = HashMap::new();
+    for record in rounds.iter().filter(|r| !r.excluded) {
+        for terror in get_terrors_data(&record.killers, &record.round_type) {
+            let cause = causes
+                .entry(terror.name.clone())
+                .or_insert_with(|| DeathCause {
+                    name: terror.name,
+                    color: terror.color,
+                    deaths: 0,
+                    encounters: 0,
+                    death_rate: 0.0,
+                });
+            cause.encounters += 1;
+            if record.is_dead {
+                cause.deaths += 1;
+            }
+        }
+    }
+
+    let mut causes: Vec<DeathCause> = causes
+        .into_values()
+        .map(|mut cause| {
+            cause.death_rate = cause.deaths as f64 / cause.encounters as f64;
+            cause
+        })
+        .collect();
+    causes.sort_by(|a, b| {
+        b.deaths
+            .cmp(&a.deaths)
+            .then_with(|| b.death_rate.total_cmp(&a.death_rate))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    causes
+}
+
+/// 特殊ラウンドが来るまでのクラシック連続数の既定値（観測データがない場合）
+const DEFAULT_CLASSIC_RUN: usize = 2;
+
+fn is_classic(round_type: &str) -> bool {
+    round_type_to_english(round_type) == "Classic"
+}
+
+/// インスタンス内のラウンドタイプの順序から次のラウンドを予測する
+///
+/// 特殊ラウンドの直後はクラシック、それ以外はこれまでに観測した
+/// 「特殊ラウンドまでのクラシック連続数」の中央値に達したら特殊と予測する。
+pub fn predict_next_round(sequence: &[String]) -> Option<String> {
+    let last = sequence.last()?;
+    if !is_classic(last) {
+        return Some("Classic".to_string());
+    }
+
+    let mut runs = Vec::new();
+    let mut run = 0;
+    for round_type in sequence {
+        if is_classic(round_type) {
+            run += 1;
+        } else {
+            if run > 0 {
+                runs.push(run);
+            }
+            run = 0;
+        }
+    }
+
+    let expected = if runs.is_empty() {
+        DEFAULT_CLASSIC_RUN
+    } else {
+        runs.sort_unstable();
+        runs[runs.len() / 2]
+    };
+
+    let prediction = if run >= expected {
+        "Special"
+    } else {
+        "Classic"
+    };
+    Some(prediction.to_string())
+}
+
+/// 期間指定（"2026.01.21" 形式、未指定側は無制限）
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct DateRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// 期間ごとの集計
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodSummary {
+    #[serde(flatten)]
+    pub stats: StatsQueryResult,
+    pub deadliest_terror: Option<String>,
+}
+
+/// 2期間の比較結果（差分は b - a）
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodComparison {
+    pub a: PeriodSummary,
+    pub b: PeriodSummary,
+    pub survival_rate_delta: f64,
+    pub rounds_delta: i64,
+    pub deadliest_terror_changed: bool,
+}
+
+fn period_summary(rounds: &[RoundRecord], range: &DateRange) -> PeriodSummary {
+    let filter = StatsFilter {
+        from: range.from.clone(),
+        to: range.to.clone(),
+        ..Default::default()
+    };
+    let matched: Vec<RoundRecord> = rounds
+        .iter()
+        .filter(|r| filter.matches(r))
+        .cloned()
+        .collect();
+    PeriodSummary {
+        stats: query_rounds(&matched, &filter),
+        deadliest_terror: death_causes(&matched)
+            .into_iter()
+            .find(|c| c.deaths > 0)
+            .map(|c| c.name),
+    }
+}
+
+pub fn compare_periods(rounds: &[RoundRecord], a: &DateRange, b: &DateRange) -> PeriodComparison {
+    let a = period_summary(rounds, a);
+    let b = period_summary(rounds, b);
+    PeriodComparison {
+        survival_rate_delta: b.stats.survival_rate - a.stats.survival_rate,
+        rounds_delta: b.stats.rounds as i64 - a.stats.rounds as i64,
+        deadliest_terror_changed: a.deadliest_terror != b.deadliest_terror,
+        a,
+        b,
+    }
+}
+
+/// 複数テラーの組み合わせごとの集計
+#[derive(Debug, Clone, Serialize)]
+pub struct ComboStats {
+    /// ソート済みのテラーID
+    pub terror_ids: Vec<u32>,
+    pub names: Vec<String>,
+    pub encounters: u32,
+    pub survivals: u32,
+    pub survival_rate: f64,
+}
+
+/// 2体以上のテラーが出現したラウンドの組み合わせ別集計（遭遇回数の多い順）
+pub fn combo_stats(rounds: &[RoundRecord]) -> Vec<ComboStats> {
+    let mut combos: HashMap<Vec<(u32, String)>, ComboStats> = HashMap::new();
+    for record in rounds.iter().filter(|r| !r.excluded && r.killers.len() > 1) {
+        let mut key: Vec<(u32, String)> = record
+            .killers
+            .iter()
+            .copied()
+            .zip(
+                get_terrors_data(&record.killers, &record.round_type)
+                    .into_iter()
+                    .map(|d| d.name),
+            )
+            .collect();
+        key.sort();
+        let combo = combos.entry(key.clone()).or_insert_with(|| ComboStats {
+            terror_ids: key.iter().map(|(id, _)| *id).collect(),
+            names: key.iter().map(|(_, name)| name.clone()).collect(),
+            encounters: 0,
+            survivals: 0,
+            survival_rate: 0.0,
+        });
+        combo.encounters += 1;
+        if !record.is_dead {
+            combo.survivals += 1;
+        }
+    }
+
+    let mut combos: Vec<ComboStats> = combos
+        .into_values()
+        .map(|mut combo| {
+            combo.survival_rate = survival_rate(combo.survivals, combo.encounters);
+            combo
+        })
+        .collect();
+    combos.sort_by(|a, b| {
+        b.encounters
+            .cmp(&a.encounters)
+            .then_with(|| a.terror_ids.cmp(&b.terror_ids))
+    });
+    combos
+}
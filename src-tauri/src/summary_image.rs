@@ -0,0 +1,117 @@
+use std::{fs, path::Path};
+
+use ab_glyph::{FontVec, PxScale};
+use image::{Rgba, RgbaImage};
+use imageproc::{
+    drawing::{draw_filled_rect_mut, draw_text_mut},
+    rect::Rect,
+};
+
+use crate::stats::{best_streak, death_causes, query_rounds, DateRange, StatsFilter};
+use crate::RoundRecord;
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 420;
+const PADDING: i32 = 40;
+
+const BACKGROUND: Rgba<u8> = Rgba([15, 15, 20, 255]);
+const ACCENT: Rgba<u8> = Rgba([0, 140, 255, 255]);
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const GRAY: Rgba<u8> = Rgba([160, 160, 160, 255]);
+
+/// 期間の統計をまとめた共有用PNGカードを描画して保存する
+pub fn render_summary_card(
+    font_path: &Path,
+    output_path: &Path,
+    rounds: &[RoundRecord],
+    range: &DateRange,
+) -> Result<(), String> {
+    let font_data = fs::read(font_path).map_err(|e| format!("Failed to read font: {}", e))?;
+    let font = FontVec::try_from_vec(font_data).map_err(|e| format!("Invalid font: {}", e))?;
+
+    let filter = StatsFilter {
+        from: range.from.clone(),
+        to: range.to.clone(),
+        ..Default::default()
+    };
+    let matched: Vec<RoundRecord> = rounds
+        .iter()
+        .filter(|r| !r.excluded && filter.matches(r))
+        .cloned()
+        .collect();
+    let stats = query_rounds(&matched, &filter);
+    let nemesis = death_causes(&matched).into_iter().find(|c| c.deaths > 0);
+
+    let mut image = RgbaImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+    draw_filled_rect_mut(&mut image, Rect::at(0, 0).of_size(WIDTH, 6), ACCENT);
+
+    let title = PxScale::from(36.0);
+    let large = PxScale::from(30.0);
+    let small = PxScale::from(22.0);
+
+    let period = match (&range.from, &range.to) {
+        (Some(from), Some(to)) => format!("{} 〜 {}", from, to),
+        (Some(from), None) => format!("{} 〜", from),
+        (None, Some(to)) => format!("〜 {}", to),
+        (None, None) => "全期間".to_string(),
+    };
+
+    let mut y = PADDING;
+    draw_text_mut(
+        &mut image,
+        WHITE,
+        PADDING,
+        y,
+        title,
+        &font,
+        "ToN Simple Save Tool",
+    );
+    y += 48;
+    draw_text_mut(&mut image, GRAY, PADDING, y, small, &font, &period);
+    y += 56;
+    draw_text_mut(
+        &mut image,
+        WHITE,
+        PADDING,
+        y,
+        large,
+        &font,
+        &format!(
+            "{}ラウンド  生存 {}  死亡 {}",
+            stats.rounds, stats.survivals, stats.deaths
+        ),
+    );
+    y += 48;
+    draw_text_mut(
+        &mut image,
+        ACCENT,
+        PADDING,
+        y,
+        large,
+        &font,
+        &format!("生存率 {:.1}%", stats.survival_rate * 100.0),
+    );
+    y += 48;
+    draw_text_mut(
+        &mut image,
+        WHITE,
+        PADDING,
+        y,
+        small,
+        &font,
+        &format!("最長連続生存: {}", best_streak(&matched)),
+    );
+    y += 36;
+    let nemesis_text = match nemesis {
+        Some(cause) => format!("天敵: {} (死亡 {}回)", cause.name, cause.deaths),
+        None => "天敵: なし".to_string(),
+    };
+    draw_text_mut(&mut image, WHITE, PADDING, y, small, &font, &nemesis_text);
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    image
+        .save(output_path)
+        .map_err(|e| format!("Failed to save image: {}", e))
+}
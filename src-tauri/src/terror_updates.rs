@@ -0,0 +1,174 @@
+//! テラーデータの配信による更新
+//!
+//! 設定したURL（GitHubのrawファイルなど）から定期的にテラー名・色のJSONを取得し、
+//! 検証してからローカルにキャッシュして、同梱のデータより優先して使う。
+//! アプリを更新しなくても新しいテラーが「不明」のまま表示されないようにするためのもの
+//!
+//! ```json
+//! { "version": "2026-10-01", "terrors": [{ "id": 5, "round_type": null, "name": "...", "color": "255, 0, 0" }] }
+//! ```
+//!
+//! `round_type`を指定した項目はそのラウンドタイプでだけ使う（指定なしより優先する）。
+//! 色は同梱のデータと同じ`"R, G, B"`形式で書く。
+//! 能力の説明は同梱のデータのものを使う
+
+use std::collections::HashSet;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rgb_sync::Rgb;
+use crate::terror_data::{self, TerrorData};
+
+/// 取得のタイムアウト
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// 確認間隔として受け付ける範囲（時間）
+pub const INTERVAL_HOURS_RANGE: (u32, u32) = (1, 168);
+
+/// 取得したデータ（未取得なら同梱のデータだけを使う）
+static DATASET: RwLock<Option<TerrorDataset>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TerrorDataUpdateSettings {
+    pub enabled: bool,
+    pub url: String,
+    /// 確認する間隔（時間）
+    pub interval_hours: u32,
+}
+
+impl Default for TerrorDataUpdateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            interval_hours: 24,
+        }
+    }
+}
+
+impl TerrorDataUpdateSettings {
+    pub fn validate(&mut self) -> Result<(), String> {
+        self.interval_hours = self
+            .interval_hours
+            .clamp(INTERVAL_HOURS_RANGE.0, INTERVAL_HOURS_RANGE.1);
+        if self.enabled && !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            return Err(format!(
+                "terror data url must start with http(s)://: {}",
+                self.url
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrorDataset {
+    pub version: String,
+    pub terrors: Vec<TerrorEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrorEntry {
+    pub id: u32,
+    #[serde(default)]
+    pub round_type: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl TerrorDataset {
+    /// JSONを読み込んで中身を確かめる
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let dataset: TerrorDataset =
+            serde_json::from_str(json).map_err(|e| format!("invalid terror data: {}", e))?;
+        if dataset.terrors.is_empty() {
+            return Err("terror data has no entries".to_string());
+        }
+        let mut keys = HashSet::new();
+        for entry in &dataset.terrors {
+            if entry.name.trim().is_empty() {
+                return Err(format!("terror {} has no name", entry.id));
+            }
+            if let Some(color) = &entry.color {
+                if Rgb::from_components(color).is_none() {
+                    return Err(format!("terror {} has invalid color: {}", entry.id, color));
+                }
+            }
+            if !keys.insert((entry.id, entry.round_type.clone())) {
+                return Err(format!("terror {} is listed twice", entry.id));
+            }
+        }
+        Ok(dataset)
+    }
+
+    fn find(&self, id: u32, round_type: &str) -> Option<&TerrorEntry> {
+        self.terrors
+            .iter()
+            .find(|e| e.id == id && e.round_type.as_deref() == Some(round_type))
+            .or_else(|| {
+                self.terrors
+                    .iter()
+                    .find(|e| e.id == id && e.round_type.is_none())
+            })
+    }
+}
+
+/// 取得したデータに切り替える（`None`なら同梱のデータだけに戻す）
+pub fn install(dataset: Option<TerrorDataset>) {
+    if let Ok(mut current) = DATASET.write() {
+        *current = dataset;
+    }
+}
+
+/// 使用中のデータのバージョン
+pub fn current_version() -> Option<String> {
+    DATASET
+        .read()
+        .ok()
+        .and_then(|d| d.as_ref().map(|d| d.version.clone()))
+}
+
+/// URLから取得する（呼び出し元のスレッドで完了まで待つ）
+pub fn fetch(url: &str) -> Result<(TerrorDataset, String), String> {
+    let body = ureq::get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()
+        .map_err(|e| format!("failed to fetch terror data: {}", e))?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    let dataset = TerrorDataset::parse(&body)?;
+    Ok((dataset, body))
+}
+
+fn apply(data: &mut TerrorData, id: u32, round_type: &str) {
+    let Ok(dataset) = DATASET.read() else {
+        return;
+    };
+    if let Some(entry) = dataset.as_ref().and_then(|d| d.find(id, round_type)) {
+        data.name = entry.name.clone();
+        if entry.color.is_some() {
+            data.color = entry.color.clone();
+        }
+    }
+}
+
+/// テラーの情報（取得したデータがあればそちらを優先する）
+pub fn get_terror_data(id: u32, round_type: &str) -> TerrorData {
+    let mut data = terror_data::get_terror_data(id, round_type);
+    apply(&mut data, id, round_type);
+    data
+}
+
+/// 複数のテラーの情報（取得したデータがあればそちらを優先する）
+pub fn get_terrors_data(ids: &[u32], round_type: &str) -> Vec<TerrorData> {
+    let mut terrors = terror_data::get_terrors_data(ids, round_type);
+    if terrors.len() == ids.len() {
+        for (data, &id) in terrors.iter_mut().zip(ids) {
+            apply(data, id, round_type);
+        }
+    }
+    terrors
+}
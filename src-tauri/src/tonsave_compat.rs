@@ -0,0 +1,75 @@
+//! ToNSaveManager互換のWebSocketメッセージ
+//!
+//! ToNSaveManagerのWebSocket APIを前提にした配信用オーバーレイをそのまま使えるよう、
+//! イベントを同じ形式（`Type`ごとのメッセージ）に変換する。
+//! `Command`は1で設定、0で解除を表す
+
+use serde_json::{json, Value};
+
+use crate::rgb_sync::Rgb;
+use crate::terror_data::round_type_to_english;
+use crate::terror_updates::get_terror_data;
+
+/// Tauriのイベントを互換形式のメッセージに変換する（対応するものがなければ空）
+pub fn messages(event: &str, payload: &Value) -> Vec<Value> {
+    match event {
+        "code_found" => payload
+            .get("code")
+            .and_then(Value::as_str)
+            .map(|code| vec![json!({ "Type": "SAVED", "Value": code })])
+            .unwrap_or_default(),
+        "round_started" => {
+            let round_type = str_field(payload, "round_type");
+            let mut messages = vec![json!({
+                "Type": "ROUND_TYPE",
+                "Command": 1,
+                "Name": round_type_to_english(round_type),
+                "DisplayName": round_type,
+            })];
+            if let Some(map) = payload.get("map_name").and_then(Value::as_str) {
+                messages.push(json!({ "Type": "LOCATION", "Command": 1, "Name": map }));
+            }
+            messages.push(json!({ "Type": "ALIVE", "Value": true }));
+            messages
+        }
+        "terrors_spawned" => {
+            let round_type = str_field(payload, "round_type");
+            let terrors: Vec<_> = payload
+                .get("killers")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_u64)
+                .map(|id| get_terror_data(id as u32, round_type))
+                .collect();
+            let names: Vec<&str> = terrors.iter().map(|t| t.name.as_str()).collect();
+            let color = terrors
+                .first()
+                .and_then(|t| t.color.as_deref())
+                .and_then(Rgb::from_components)
+                .map(|Rgb(r, g, b)| (u32::from(r) << 16) | (u32::from(g) << 8) | u32::from(b))
+                .unwrap_or(0xFFFFFF);
+            vec![json!({
+                "Type": "TERRORS",
+                "Command": 1,
+                "Names": names,
+                "DisplayName": names.join(" & "),
+                "DisplayColor": color,
+            })]
+        }
+        "player_died" => vec![
+            json!({ "Type": "DEATH", "Name": "", "Message": "", "IsLocal": true }),
+            json!({ "Type": "ALIVE", "Value": false }),
+        ],
+        "round_ended" => vec![
+            json!({ "Type": "TERRORS", "Command": 0 }),
+            json!({ "Type": "ROUND_TYPE", "Command": 0 }),
+            json!({ "Type": "LOCATION", "Command": 0 }),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+fn str_field<'a>(payload: &'a Value, key: &str) -> &'a str {
+    payload.get(key).and_then(Value::as_str).unwrap_or_default()
+}
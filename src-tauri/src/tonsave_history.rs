@@ -0,0 +1,179 @@
+//! ToNSaveManagerのセーブ履歴ファイルの読み込み・書き出し
+//!
+//! ToNSaveManagerは履歴をコレクション（`Entries`の配列）ごとに保存する。
+//! 読み込みでは、バージョンによって入れ子の形が違うため、`Content`（セーブコード）と`Timestamp`を
+//! 持つものを入れ子をたどってすべて拾う。書き出しは1つのコレクションにまとめる
+
+use std::collections::HashSet;
+use std::{fs, path::Path};
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::export::terror_names;
+use crate::{AppData, CodeEntry};
+
+/// このツールの履歴の日時形式（ログと同じ）
+const TIMESTAMP_FORMAT: &str = "%Y.%m.%d %H:%M:%S";
+
+/// 取り込みの結果
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ImportSummary {
+    /// 取り込んだ件数
+    pub imported: usize,
+    /// 既に記録済みだったため取り込まなかった件数
+    pub duplicates: usize,
+}
+
+/// ファイルの内容からセーブコードを取り出す（同じファイル内の重複は除く）
+pub fn parse_entries(json: &str) -> Result<Vec<CodeEntry>, String> {
+    let root: Value =
+        serde_json::from_str(json).map_err(|e| format!("invalid ToNSaveManager file: {}", e))?;
+    let mut entries = Vec::new();
+    collect_entries(&root, &mut entries);
+    if entries.is_empty() {
+        return Err("no save codes found in file".to_string());
+    }
+    let mut seen = HashSet::new();
+    entries.retain(|entry: &CodeEntry| seen.insert(entry.code.clone()));
+    Ok(entries)
+}
+
+fn collect_entries(value: &Value, entries: &mut Vec<CodeEntry>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_entries(item, entries)),
+        Value::Object(map) => {
+            let code = map.get("Content").and_then(Value::as_str).map(str::trim);
+            let timestamp = map
+                .get("Timestamp")
+                .and_then(Value::as_str)
+                .and_then(to_log_timestamp);
+            if let (Some(code), Some(timestamp)) = (code, timestamp) {
+                if !code.is_empty() {
+                    entries.push(CodeEntry {
+                        code: code.to_string(),
+                        timestamp,
+                        round_type: map
+                            .get("RoundType")
+                            .or_else(|| map.get("RT"))
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        terror_names: None,
+                        round_type_english: None,
+                    });
+                }
+                return;
+            }
+            map.values().for_each(|item| collect_entries(item, entries));
+        }
+        _ => {}
+    }
+}
+
+/// .NETのDateTime（ISO 8601形式、オフセットなしはローカル時刻）をこのツールの形式にする
+fn to_log_timestamp(value: &str) -> Option<String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(
+            datetime
+                .with_timezone(&Local)
+                .format(TIMESTAMP_FORMAT)
+                .to_string(),
+        );
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|datetime| datetime.format(TIMESTAMP_FORMAT).to_string())
+}
+
+/// 記録済みのコードを除いて取り込み、日時順に並べ直す
+pub fn merge(
+    imported_history: &mut Vec<CodeEntry>,
+    history: &[CodeEntry],
+    entries: Vec<CodeEntry>,
+) -> ImportSummary {
+    let mut known: HashSet<String> = history
+        .iter()
+        .chain(imported_history.iter())
+        .map(|entry| entry.code.clone())
+        .collect();
+    let mut summary = ImportSummary::default();
+    for entry in entries {
+        if known.insert(entry.code.clone()) {
+            imported_history.push(entry);
+            summary.imported += 1;
+        } else {
+            summary.duplicates += 1;
+        }
+    }
+    imported_history.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    summary
+}
+
+/// ToNSaveManagerの日時形式（ISO 8601、オフセットなし）
+fn to_iso_timestamp(value: &str) -> String {
+    NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT)
+        .map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S").to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// 履歴・取り込んだ履歴・ラウンド記録のセーブコードを重複なしで日時順にまとめる
+pub fn all_codes(data: &AppData) -> Vec<CodeEntry> {
+    let from_rounds = data.rounds.iter().filter_map(|record| {
+        let code = record.save_code.clone()?;
+        let names = terror_names(record);
+        Some(CodeEntry {
+            code,
+            timestamp: record.ended_at.clone(),
+            round_type: Some(record.round_type.clone()),
+            terror_names: (!names.is_empty()).then(|| vec![names]),
+            round_type_english: None,
+        })
+    });
+    let mut seen = HashSet::new();
+    // 同じコードは詳しい情報を持つ履歴のものを残す
+    let mut entries: Vec<CodeEntry> = data
+        .history
+        .iter()
+        .cloned()
+        .chain(from_rounds)
+        .chain(data.imported_history.iter().cloned())
+        .filter(|entry| seen.insert(entry.code.clone()))
+        .collect();
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    entries
+}
+
+/// ToNSaveManagerで読み込める形式で書き出す（ラウンドタイプとテラー名はメモに入れる）
+pub fn write_file(path: &Path, entries: &[CodeEntry]) -> Result<usize, String> {
+    let entries_json: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            let note = [
+                entry.round_type.clone().unwrap_or_default(),
+                entry.terror_names.as_deref().unwrap_or_default().join(", "),
+            ]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" / ");
+            json!({
+                "Timestamp": to_iso_timestamp(&entry.timestamp),
+                "Content": entry.code,
+                "Note": note,
+            })
+        })
+        .collect();
+    let collection = json!([{
+        "Name": "ToN Simple Save Tool",
+        "Timestamp": entries.last().map(|entry| to_iso_timestamp(&entry.timestamp)),
+        "Entries": entries_json,
+    }]);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let payload = serde_json::to_string_pretty(&collection).map_err(|err| err.to_string())?;
+    fs::write(path, payload).map_err(|err| err.to_string())?;
+    Ok(entries.len())
+}
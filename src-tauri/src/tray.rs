@@ -0,0 +1,61 @@
+//! トレイアイコンの状態表示
+
+use tauri::image::Image;
+
+/// トレイアイコンのID
+pub const TRAY_ID: &str = "main";
+
+/// トレイアイコンで表す状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayStatus {
+    Idle,
+    RoundActive,
+    Dead,
+    /// セーブコードを記録した直後
+    NewCode,
+}
+
+impl TrayStatus {
+    /// 右下に付けるバッジの色（待機中はバッジなし）
+    fn badge_color(self) -> Option<[u8; 3]> {
+        match self {
+            TrayStatus::Idle => None,
+            TrayStatus::RoundActive => Some([60, 200, 90]),
+            TrayStatus::Dead => Some([230, 60, 60]),
+            TrayStatus::NewCode => Some([40, 150, 255]),
+        }
+    }
+}
+
+/// 元のアイコンにステータスのバッジ（円）を重ねた画像を作る
+pub fn status_icon(base: &Image<'_>, status: TrayStatus) -> Image<'static> {
+    let width = base.width();
+    let height = base.height();
+    let mut rgba = base.rgba().to_vec();
+
+    if let Some([r, g, b]) = status.badge_color() {
+        let radius = (width.min(height) as f32) * 0.22;
+        let cx = width as f32 - radius - 1.0;
+        let cy = height as f32 - radius - 1.0;
+        for y in 0..height {
+            for x in 0..width {
+                let dx = x as f32 + 0.5 - cx;
+                let dy = y as f32 + 0.5 - cy;
+                let distance = (dx * dx + dy * dy).sqrt();
+                if distance > radius {
+                    continue;
+                }
+                let i = ((y * width + x) * 4) as usize;
+                // 縁は白くして暗い背景でも見えるようにする
+                let color = if distance > radius * 0.75 {
+                    [255, 255, 255]
+                } else {
+                    [r, g, b]
+                };
+                rgba[i..i + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
@@ -0,0 +1,276 @@
+//! VRオーバーレイとのIPC
+//!
+//! localhostのTCP接続上で改行区切りのJSONフレームを送受信する。
+//! 接続直後にお互いの`{"hello": {"protocol_version": N}}`を交換し、その後
+//! アプリ→オーバーレイ: `{"id": 1, "command": {...}}`
+//! オーバーレイ→アプリ: `{"ack": 1, "ok": true, "error": null}`
+//! オーバーレイ側の操作はいつでも `{"event": {"type": "copy_code"}}` のように送られてくる
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// アプリが想定するIPCプロトコルのバージョン
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// コマンドの応答を待つ最大時間
+const ACK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Serialize)]
+struct Frame<'a, T> {
+    id: u64,
+    command: &'a T,
+}
+
+/// コマンドに対するオーバーレイからの応答
+#[derive(Debug, Deserialize)]
+struct Ack {
+    ack: u64,
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Hello {
+    protocol_version: u32,
+}
+
+#[derive(Serialize)]
+struct HelloFrame {
+    hello: Hello,
+}
+
+/// SteamVRのコントローラー割り当てから実行する操作（オーバーレイがアクションマニフェストを公開する）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InputAction {
+    ToggleOverlay,
+    CopyCode,
+    CyclePanels,
+    /// VRオーバーレイ自体を無効にする（再び有効にするのはホットキーかメインウィンドウから）
+    DisableOverlay,
+}
+
+/// オーバーレイ上でのユーザー操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OverlayEvent {
+    /// パネルが閉じられた
+    PanelDismissed,
+    /// レーザーポインターでテラーがクリックされた
+    TerrorClicked { index: usize, name: String },
+    /// 「コードをコピー」ボタンが押された
+    CopyCode,
+    /// コントローラーに割り当てたアクションが押された
+    InputAction { action: InputAction },
+}
+
+/// オーバーレイから受信するメッセージ
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Incoming {
+    Hello { hello: Hello },
+    Event { event: OverlayEvent },
+    Ack(Ack),
+}
+
+/// 接続済みのオーバーレイとのチャネル
+pub struct VrIpc {
+    stream: TcpStream,
+    next_id: u64,
+    incoming: Receiver<Incoming>,
+    pending: PendingAcks,
+}
+
+/// 応答を待っているコマンドのIDと、応答の受け渡し先
+type PendingAcks = Arc<Mutex<HashMap<u64, Sender<Ack>>>>;
+
+/// 送信済みのコマンドの応答待ち（接続のロックを外してから待てるよう分けている）
+pub struct PendingAck {
+    id: u64,
+    ack: Receiver<Ack>,
+    pending: PendingAcks,
+}
+
+/// オーバーレイの接続を待ち受けるリスナーを作成（ポートはOSが割り当て）
+pub fn bind() -> Result<TcpListener, String> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| format!("Failed to bind VR overlay IPC: {}", e))
+}
+
+/// オーバーレイからの接続を指定時間まで待つ
+///
+/// 受信した操作イベントは`on_event`に渡される（受信スレッドから呼ばれる）
+pub fn accept(
+    listener: &TcpListener,
+    timeout: Duration,
+    on_event: impl Fn(OverlayEvent) + Send + 'static,
+) -> Result<VrIpc, String> {
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure VR overlay IPC: {}", e))?;
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => return VrIpc::new(stream, on_event),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err("VR overlay did not connect in time".to_string());
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => return Err(format!("Failed to accept VR overlay connection: {}", e)),
+        }
+    }
+}
+
+impl VrIpc {
+    fn new(
+        stream: TcpStream,
+        on_event: impl Fn(OverlayEvent) + Send + 'static,
+    ) -> Result<Self, String> {
+        stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.set_write_timeout(Some(ACK_TIMEOUT)))
+            .and_then(|_| stream.set_nodelay(true))
+            .map_err(|e| format!("Failed to configure VR overlay IPC: {}", e))?;
+        let reader = stream
+            .try_clone()
+            .map_err(|e| format!("Failed to configure VR overlay IPC: {}", e))?;
+
+        let (tx, rx) = mpsc::channel();
+        let pending = PendingAcks::default();
+        let acks = pending.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Incoming>(&line) {
+                    Ok(Incoming::Event { event }) => on_event(event),
+                    // タイムアウトした過去のコマンドへの応答などは読み捨てる
+                    Ok(Incoming::Ack(ack)) => {
+                        let waiter = acks.lock().ok().and_then(|mut acks| acks.remove(&ack.ack));
+                        if let Some(waiter) = waiter {
+                            let _ = waiter.send(ack);
+                        }
+                    }
+                    Ok(message) => {
+                        if tx.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => println!("[tsst] Invalid message from VR overlay: {}", e),
+                }
+            }
+            // 応答を待っているコマンドに切断を知らせる
+            if let Ok(mut acks) = acks.lock() {
+                acks.clear();
+            }
+            println!("[tsst] VR overlay IPC connection closed");
+        });
+
+        Ok(Self {
+            stream,
+            next_id: 0,
+            incoming: rx,
+            pending,
+        })
+    }
+
+    fn write_line<T: Serialize>(&mut self, message: &T) -> Result<(), String> {
+        let mut line = serde_json::to_string(message)
+            .map_err(|e| format!("Failed to serialize VR command: {}", e))?;
+        line.push('\n');
+        self.stream
+            .write_all(line.as_bytes())
+            .and_then(|_| self.stream.flush())
+            .map_err(|e| format!("Failed to write VR command: {}", e))
+    }
+
+    /// バージョンを交換し、オーバーレイのプロトコルバージョンを返す
+    /// （helloを返さない古いオーバーレイは`None`）
+    pub fn handshake(&mut self) -> Result<Option<u32>, String> {
+        self.write_line(&HelloFrame {
+            hello: Hello {
+                protocol_version: PROTOCOL_VERSION,
+            },
+        })?;
+
+        let deadline = Instant::now() + ACK_TIMEOUT;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.incoming.recv_timeout(remaining) {
+                Ok(Incoming::Hello { hello }) => return Ok(Some(hello.protocol_version)),
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err("VR overlay connection closed".to_string());
+                }
+            }
+        }
+    }
+
+    /// コマンドを送信し、応答を待つ（タイムアウト・拒否はエラー）
+    pub fn send<T: Serialize>(&mut self, command: &T) -> Result<(), String> {
+        self.start_send(command)?.wait()
+    }
+
+    /// コマンドを書き込み、応答待ちを返す（応答は`PendingAck::wait`で待つ）
+    pub fn start_send<T: Serialize>(&mut self, command: &T) -> Result<PendingAck, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+
+        let (tx, ack) = mpsc::channel();
+        self.pending
+            .lock()
+            .map_err(|_| "vr ipc lock failed")?
+            .insert(id, tx);
+        let pending = PendingAck {
+            id,
+            ack,
+            pending: self.pending.clone(),
+        };
+        self.write_line(&Frame { id, command })?;
+        Ok(pending)
+    }
+}
+
+impl PendingAck {
+    pub fn wait(self) -> Result<(), String> {
+        match self.ack.recv_timeout(ACK_TIMEOUT) {
+            Ok(ack) if ack.ok => Ok(()),
+            Ok(ack) => Err(format!(
+                "VR overlay rejected command: {}",
+                ack.error.unwrap_or_default()
+            )),
+            Err(RecvTimeoutError::Timeout) => Err(format!(
+                "VR overlay did not acknowledge command within {}s",
+                ACK_TIMEOUT.as_secs()
+            )),
+            Err(RecvTimeoutError::Disconnected) => Err("VR overlay connection closed".to_string()),
+        }
+    }
+}
+
+impl Drop for PendingAck {
+    fn drop(&mut self) {
+        // 応答が来なかった場合も待ち受けを残さない
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&self.id);
+        }
+    }
+}
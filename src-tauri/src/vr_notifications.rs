@@ -0,0 +1,101 @@
+//! XSOverlay / OVR Toolkitへの通知
+//!
+//! 既に使っているオーバーレイツールの通知として表示し、同梱のVRオーバーレイを起動しなくても済むようにする
+
+use std::net::UdpSocket;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tungstenite::Message;
+
+/// XSOverlayの通知APIのポート（UDP）
+const XSOVERLAY_PORT: u16 = 42069;
+/// OVR ToolkitのWebSocket API
+const OVR_TOOLKIT_URL: &str = "ws://127.0.0.1:11450/api";
+/// 通知を表示しておく秒数
+const NOTIFICATION_TIMEOUT_SECS: f32 = 4.0;
+/// 送信元として表示する名前
+const SOURCE_APP: &str = "ToN Simple Save Tool";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VrNotificationTarget {
+    #[default]
+    XsOverlay,
+    OvrToolkit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VrNotificationSettings {
+    pub enabled: bool,
+    pub target: VrNotificationTarget,
+    /// テラーが出現したとき
+    pub terrors_spawned: bool,
+    /// セーブコードを記録したとき
+    pub code_captured: bool,
+}
+
+impl Default for VrNotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: VrNotificationTarget::default(),
+            terrors_spawned: true,
+            code_captured: true,
+        }
+    }
+}
+
+/// 通知を送る（OVR Toolkitは接続を待つので呼び出し元のスレッドで完了まで待つ）
+pub fn send(target: VrNotificationTarget, title: &str, body: &str) -> Result<(), String> {
+    match target {
+        VrNotificationTarget::XsOverlay => send_xsoverlay(title, body),
+        VrNotificationTarget::OvrToolkit => send_ovr_toolkit(title, body),
+    }
+}
+
+fn send_xsoverlay(title: &str, body: &str) -> Result<(), String> {
+    let message = json!({
+        "messageType": 1,
+        "index": 0,
+        "timeout": NOTIFICATION_TIMEOUT_SECS,
+        "height": if body.is_empty() { 100.0 } else { 175.0 },
+        "opacity": 1.0,
+        "volume": 0.7,
+        "audioPath": "default",
+        "title": title,
+        "content": body,
+        "useBase64Icon": false,
+        "icon": "default",
+        "sourceApp": SOURCE_APP,
+    });
+    let socket = UdpSocket::bind("127.0.0.1:0").map_err(|e| e.to_string())?;
+    socket
+        .send_to(
+            message.to_string().as_bytes(),
+            ("127.0.0.1", XSOVERLAY_PORT),
+        )
+        .map(|_| ())
+        .map_err(|e| format!("failed to send XSOverlay notification: {}", e))
+}
+
+fn send_ovr_toolkit(title: &str, body: &str) -> Result<(), String> {
+    let (mut socket, _) = tungstenite::connect(OVR_TOOLKIT_URL)
+        .map_err(|e| format!("failed to connect to OVR Toolkit: {}", e))?;
+    if let tungstenite::stream::MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(2)));
+    }
+    // 通知の内容はJSON文字列として入れる
+    let message = json!({
+        "messageType": "SendNotification",
+        "json": json!({ "title": title, "body": body }).to_string(),
+    });
+    socket
+        .send(Message::text(message.to_string()))
+        .map_err(|e| format!("failed to send OVR Toolkit notification: {}", e))?;
+    let _ = socket.close(None);
+    let _ = socket.flush();
+    Ok(())
+}
@@ -0,0 +1,213 @@
+//! 利用者が設定したWebhookの送信
+//!
+//! イベントごとにURLへリクエストを送り、IFTTTやn8n、自前のサービスなどと連携できるようにする。
+//! 本文のテンプレートには通知と同じ差し込み項目と`{event}`が使える（値はJSONの文字列としてエスケープする）
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::notification_templates::{self, TemplateValues};
+use crate::terror_updates::get_terror_data;
+
+/// 送信のタイムアウト
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Webhookを送るきっかけになるイベント（Tauriのイベント名と同じ）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    CodeFound,
+    RoundStarted,
+    TerrorsSpawned,
+    PlayerDied,
+    RoundEnded,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 5] = [
+        WebhookEvent::CodeFound,
+        WebhookEvent::RoundStarted,
+        WebhookEvent::TerrorsSpawned,
+        WebhookEvent::PlayerDied,
+        WebhookEvent::RoundEnded,
+    ];
+
+    pub fn event_name(self) -> &'static str {
+        match self {
+            WebhookEvent::CodeFound => "code_found",
+            WebhookEvent::RoundStarted => "round_started",
+            WebhookEvent::TerrorsSpawned => "terrors_spawned",
+            WebhookEvent::PlayerDied => "player_died",
+            WebhookEvent::RoundEnded => "round_ended",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    Post,
+    Put,
+    /// 本文は送らない
+    Get,
+}
+
+impl HttpMethod {
+    fn as_str(self) -> &'static str {
+        match self {
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Get => "GET",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Webhook {
+    pub name: String,
+    pub enabled: bool,
+    pub url: String,
+    pub method: HttpMethod,
+    /// JSON本文のテンプレート
+    pub body_template: String,
+    /// 送信するイベント
+    pub events: Vec<WebhookEvent>,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            url: String::new(),
+            method: HttpMethod::default(),
+            body_template: r#"{"event": "{event}", "code": "{code}"}"#.to_string(),
+            events: vec![WebhookEvent::CodeFound],
+        }
+    }
+}
+
+impl Webhook {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            return Err(format!(
+                "webhook url must start with http(s)://: {}",
+                self.url
+            ));
+        }
+        let mut allowed = notification_templates::PLACEHOLDERS.to_vec();
+        allowed.push("event");
+        notification_templates::check_placeholders(&self.body_template, &allowed)
+    }
+}
+
+/// イベントの内容からテンプレートに差し込む値
+#[derive(Debug, Default, Clone)]
+pub struct EventValues {
+    pub event: String,
+    pub code: String,
+    pub terrors: String,
+    pub round_type: String,
+    pub map: String,
+    pub time: String,
+}
+
+impl EventValues {
+    /// Tauriのイベントの内容（JSON）から値を取り出す
+    pub fn from_payload(event: WebhookEvent, payload: &Value) -> Self {
+        let text = |key: &str| {
+            payload
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let round_type = text("round_type");
+        let terrors = match event {
+            WebhookEvent::CodeFound => payload
+                .get("terror_names")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => payload
+                .get("killers")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_u64)
+                .map(|id| get_terror_data(id as u32, &round_type).name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        Self {
+            event: event.event_name().to_string(),
+            // round_endedはラウンド記録なので`save_code`に入っている
+            code: Some(text("code"))
+                .filter(|code| !code.is_empty())
+                .unwrap_or_else(|| text("save_code")),
+            terrors,
+            round_type,
+            map: text("map_name"),
+            time: chrono::Local::now().format("%H:%M").to_string(),
+        }
+    }
+
+    /// 動作確認用の値
+    pub fn sample() -> Self {
+        Self {
+            event: "test".to_string(),
+            code: "TEST-CODE".to_string(),
+            terrors: "Test Terror".to_string(),
+            round_type: "Classic".to_string(),
+            map: "Test Map".to_string(),
+            time: chrono::Local::now().format("%H:%M").to_string(),
+        }
+    }
+}
+
+/// JSONの文字列の中に入れられるようにエスケープする（前後の`"`は付けない）
+fn escape_json(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted
+        .get(1..quoted.len().saturating_sub(1))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 本文を組み立てて送信する（呼び出し元のスレッドで完了まで待つ）
+pub fn send(webhook: &Webhook, values: &EventValues) -> Result<(), String> {
+    let escaped = [
+        escape_json(&values.code),
+        escape_json(&values.terrors),
+        escape_json(&values.round_type),
+        escape_json(&values.map),
+        escape_json(&values.time),
+    ];
+    let template_values = TemplateValues {
+        code: &escaped[0],
+        terrors: &escaped[1],
+        round_type: &escaped[2],
+        map: &escaped[3],
+        time: &escaped[4],
+    };
+    let body = notification_templates::render(&webhook.body_template, &template_values)
+        .replace("{event}", &escape_json(&values.event));
+
+    let request = ureq::request(webhook.method.as_str(), &webhook.url).timeout(REQUEST_TIMEOUT);
+    let result = match webhook.method {
+        HttpMethod::Get => request.call(),
+        _ => request
+            .set("Content-Type", "application/json")
+            .send_string(&body),
+    };
+    result
+        .map(|_| ())
+        .map_err(|e| format!("webhook {} failed: {}", webhook.name, e))
+}
@@ -13,7 +13,8 @@ import {
   RealtimePage,
 } from "./components/pages";
 import { UpdateBanner } from "./components/common";
-import { useUpdater } from "./hooks";
+import { CompactView } from "./components/overlay/CompactView";
+import { useAppearance, useUpdater } from "./hooks";
 
 type CodeEntry = {
   code: string;
@@ -46,23 +47,24 @@ type AppSettings = {
   auto_switch_tab?: boolean;
   vr_overlay_enabled?: boolean;
   vr_overlay_position?: "RightHand" | "LeftHand" | "Above";
+  compact_mode?: { enabled: boolean };
 };
 
 type AppSnapshot = {
   settings: AppSettings;
-  history: CodeEntry[];
   latest_code?: CodeEntry | null;
   stats: RoundStats;
   survivals: number;
   current_round: CurrentRoundInfo;
   instance_round_counts: Record<string, number>;
+  monitoring_paused?: boolean;
+  current_account?: string | null;
 };
 
 function App() {
   const [currentPage, setCurrentPage] = useState("home");
   const [snapshot, setSnapshot] = useState<AppSnapshot>({
     settings: {},
-    history: [],
     latest_code: null,
     stats: { total_rounds: 0, deaths: 0, round_types: {} },
     survivals: 0,
@@ -74,7 +76,11 @@ function App() {
     instance_round_counts: {},
   });
   const [autoStartEnabled, setAutoStartEnabled] = useState(false);
+  const [compactMode, setCompactMode] = useState(false);
   const previousPageRef = useRef<string>("home");
+  // イベントリスナーは一度だけ登録するため、自動タブ切替の設定はrefで参照する
+  const autoSwitchRef = useRef(false);
+  autoSwitchRef.current = snapshot.settings.auto_switch_tab ?? false;
 
   // アップデート機能
   const {
@@ -87,12 +93,16 @@ function App() {
     downloadAndInstall,
     dismissUpdate,
   } = useUpdater();
+  useAppearance();
 
   useEffect(() => {
     let unlistenState: (() => void) | undefined;
     let unlistenSettings: (() => void) | undefined;
     let unlistenRoundStarted: (() => void) | undefined;
     let unlistenRoundEnded: (() => void) | undefined;
+    let unlistenCompact: (() => void) | undefined;
+    let unlistenPaused: (() => void) | undefined;
+    let unlistenShowHistory: (() => void) | undefined;
 
     const init = async () => {
       await refreshState();
@@ -105,6 +115,7 @@ function App() {
       });
       // ラウンド開始イベント：リアルタイムタブへ自動切替
       unlistenRoundStarted = await listen("round_started", () => {
+        if (!autoSwitchRef.current) return;
         setCurrentPage((prev) => {
           if (prev !== "realtime") {
             previousPageRef.current = prev;
@@ -114,8 +125,19 @@ function App() {
       });
       // ラウンド終了イベント：元のタブへ戻る
       unlistenRoundEnded = await listen("round_ended", () => {
+        if (!autoSwitchRef.current) return;
         setCurrentPage(previousPageRef.current);
       });
+      unlistenCompact = await listen<boolean>("compact_mode_changed", (event) => {
+        setCompactMode(event.payload);
+      });
+      unlistenPaused = await listen<boolean>("monitoring_paused_changed", (event) => {
+        setSnapshot((prev) => ({ ...prev, monitoring_paused: event.payload }));
+      });
+      // 新しいセーブコード記録時：履歴タブへ切替
+      unlistenShowHistory = await listen("show_history", () => {
+        setCurrentPage("history");
+      });
     };
 
     init();
@@ -125,27 +147,36 @@ function App() {
       unlistenSettings?.();
       unlistenRoundStarted?.();
       unlistenRoundEnded?.();
+      unlistenCompact?.();
+      unlistenPaused?.();
+      unlistenShowHistory?.();
     };
   }, []);
 
   const refreshState = async () => {
     const data = (await invoke("get_state")) as AppSnapshot;
     setSnapshot(data);
+    setCompactMode(data.settings.compact_mode?.enabled ?? false);
+  };
+
+  const toggleCompactMode = async () => {
+    const data = (await invoke("toggle_compact_mode")) as AppSettings;
+    setSnapshot((prev) => ({ ...prev, settings: data }));
   };
 
   const handleChooseLogDir = async () => {
     const dir = await open({ directory: true, multiple: false });
     if (typeof dir === "string") {
-      const data = (await invoke("set_log_dir", {
-        logDir: dir,
+      const data = (await invoke("update_settings", {
+        patch: { log_dir: dir },
       })) as AppSettings;
       setSnapshot((prev) => ({ ...prev, settings: data }));
     }
   };
 
   const handleResetLogDir = async () => {
-    const data = (await invoke("set_log_dir", {
-      logDir: null,
+    const data = (await invoke("update_settings", {
+      patch: { log_dir: null },
     })) as AppSettings;
     setSnapshot((prev) => ({ ...prev, settings: data }));
   };
@@ -162,8 +193,8 @@ function App() {
 
   const toggleAutoSwitchTab = async () => {
     const newValue = !snapshot.settings.auto_switch_tab;
-    const data = (await invoke("set_auto_switch_tab", {
-      enabled: newValue,
+    const data = (await invoke("update_settings", {
+      patch: { auto_switch_tab: newValue },
     })) as AppSettings;
     setSnapshot((prev) => ({ ...prev, settings: data }));
   };
@@ -203,7 +234,7 @@ function App() {
       case "realtime":
         return <RealtimePage currentRound={snapshot.current_round} />;
       case "history":
-        return <HistoryPage history={snapshot.history} />;
+        return <HistoryPage />;
       case "settings":
         return (
           <SettingsPage
@@ -236,6 +267,16 @@ function App() {
     }
   };
 
+  if (compactMode) {
+    return (
+      <CompactView
+        latestCode={snapshot.latest_code}
+        currentRound={snapshot.current_round}
+        onExit={toggleCompactMode}
+      />
+    );
+  }
+
   return (
     <div className="flex flex-col h-full bg-[#1a1a1a]">
       <TitleBar />
@@ -0,0 +1,94 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface CodeEntry {
+  code: string;
+  timestamp: string;
+}
+
+interface CurrentRoundInfo {
+  is_active: boolean;
+  round_type?: string | null;
+  killers: number[];
+}
+
+interface TerrorData {
+  name: string;
+  color?: string | null;
+}
+
+interface CompactViewProps {
+  latestCode?: CodeEntry | null;
+  currentRound: CurrentRoundInfo;
+  onExit: () => void;
+}
+
+// コンパクト表示：最新コードと現在のテラーだけを表示する小さなウィジェット
+export function CompactView({ latestCode, currentRound, onExit }: CompactViewProps) {
+  const [terrors, setTerrors] = useState<TerrorData[]>([]);
+  const killers = currentRound.is_active ? currentRound.killers : [];
+  const roundType = currentRound.round_type || "Classic";
+  const killersKey = killers.join(",");
+
+  useEffect(() => {
+    if (killers.length === 0) {
+      setTerrors([]);
+      return;
+    }
+    invoke<TerrorData[]>("get_terrors_info", { killerIds: killers, roundType })
+      .then(setTerrors)
+      .catch((error) => {
+        console.error("Failed to fetch terror data:", error);
+        setTerrors([]);
+      });
+  }, [killersKey, roundType]);
+
+  const copyLatest = async () => {
+    if (latestCode) {
+      await navigator.clipboard.writeText(latestCode.code);
+    }
+  };
+
+  return (
+    <div className="flex flex-col h-full bg-[#1a1a1a] text-white select-none">
+      <div data-tauri-drag-region className="flex items-center justify-between px-3 py-1.5 border-b border-white/10">
+        <span data-tauri-drag-region className="text-xs text-gray-400">ToN Simple Save Tool</span>
+        <button onClick={onExit} className="text-xs text-gray-400 hover:text-white">
+          通常表示
+        </button>
+      </div>
+      <div className="flex-1 overflow-hidden p-3 space-y-3">
+        <button
+          onClick={copyLatest}
+          disabled={!latestCode}
+          className="w-full text-left rounded-md bg-white/5 hover:bg-white/10 px-3 py-2 disabled:opacity-50"
+        >
+          <div className="text-xs text-gray-500">
+            {latestCode ? `最新コード ${latestCode.timestamp}（クリックでコピー）` : "コードなし"}
+          </div>
+          <div className="text-sm font-mono truncate">{latestCode?.code ?? "-"}</div>
+        </button>
+        <div className="space-y-1">
+          {terrors.length === 0 ? (
+            <div className="text-xs text-gray-500">
+              {currentRound.is_active ? "テラー未出現" : "待機中"}
+            </div>
+          ) : (
+            terrors.map((terror, index) => (
+              <div
+                key={`${terror.name}-${index}`}
+                className="text-sm font-semibold pl-2"
+                style={{
+                  borderLeftWidth: 3,
+                  borderLeftColor: terror.color ? `rgb(${terror.color})` : "transparent",
+                }}
+              >
+                {terror.name}
+              </div>
+            ))
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface CurrentRoundInfo {
+  is_active: boolean;
+  round_type?: string | null;
+  killers: number[];
+}
+
+interface TerrorAbility {
+  label: string;
+  value: string;
+}
+
+interface TerrorData {
+  name: string;
+  color?: string | null;
+  abilities: TerrorAbility[];
+}
+
+type AppSnapshot = {
+  current_round: CurrentRoundInfo;
+};
+
+// デスクトップオーバーレイ（ゲーム画面の上に重ねる透過ウィンドウ）
+export function DesktopOverlay() {
+  const [currentRound, setCurrentRound] = useState<CurrentRoundInfo | null>(null);
+  const [terrors, setTerrors] = useState<TerrorData[]>([]);
+
+  useEffect(() => {
+    document.documentElement.style.background = "transparent";
+    document.body.style.background = "transparent";
+
+    let unlisten: (() => void) | undefined;
+    (async () => {
+      const snapshot = await invoke<AppSnapshot>("get_state");
+      setCurrentRound(snapshot.current_round);
+      unlisten = await listen<AppSnapshot>("state_updated", (event) => {
+        setCurrentRound(event.payload.current_round);
+      });
+    })();
+    return () => unlisten?.();
+  }, []);
+
+  const killers = currentRound?.is_active ? currentRound.killers : [];
+  const roundType = currentRound?.round_type || "Classic";
+  const killersKey = killers.join(",");
+
+  useEffect(() => {
+    if (killers.length === 0) {
+      setTerrors([]);
+      return;
+    }
+    invoke<TerrorData[]>("get_terrors_info", { killerIds: killers, roundType })
+      .then(setTerrors)
+      .catch((error) => {
+        console.error("Failed to fetch terror data:", error);
+        setTerrors([]);
+      });
+  }, [killersKey, roundType]);
+
+  return (
+    // クリック透過をオフにしている間はドラッグで移動できる
+    <div data-tauri-drag-region className="h-screen w-screen p-2 space-y-2 select-none">
+      {terrors.map((terror, index) => (
+        <div
+          key={`${terror.name}-${index}`}
+          className="rounded-md bg-black/70 px-3 py-2"
+          style={{
+            borderLeftWidth: 4,
+            borderLeftColor: terror.color ? `rgb(${terror.color})` : "transparent",
+          }}
+        >
+          <div className="text-sm font-semibold text-white">{terror.name}</div>
+          {terror.abilities.slice(0, 4).map((ability, idx) => (
+            <div key={idx} className="text-xs">
+              <span className="text-blue-400 font-medium">{ability.label}: </span>
+              <span className="text-gray-300">{ability.value}</span>
+            </div>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+}
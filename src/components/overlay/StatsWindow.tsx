@@ -0,0 +1,61 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { HomePage } from "../pages";
+import { useAppearance } from "../../hooks";
+
+interface CodeEntry {
+  code: string;
+  timestamp: string;
+  round_type?: string | null;
+}
+
+interface RoundTypeStats {
+  survivals: number;
+  deaths: number;
+}
+
+interface RoundStats {
+  total_rounds: number;
+  deaths: number;
+  round_types: Record<string, RoundTypeStats>;
+}
+
+type AppSnapshot = {
+  latest_code?: CodeEntry | null;
+  stats: RoundStats;
+  survivals: number;
+  instance_round_counts: Record<string, number>;
+};
+
+// 統計ウィンドウ（メインウィンドウとは別に2枚目のモニターなどに置く）
+export function StatsWindow() {
+  const [snapshot, setSnapshot] = useState<AppSnapshot | null>(null);
+  useAppearance();
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    (async () => {
+      setSnapshot(await invoke<AppSnapshot>("get_state"));
+      unlisten = await listen<AppSnapshot>("state_updated", (event) => {
+        setSnapshot(event.payload);
+      });
+    })();
+    return () => unlisten?.();
+  }, []);
+
+  if (!snapshot) {
+    return null;
+  }
+
+  return (
+    <div className="h-screen overflow-y-auto p-4 bg-[#1a1a1a] text-white">
+      <HomePage
+        latestCode={snapshot.latest_code}
+        stats={snapshot.stats}
+        survivals={snapshot.survivals}
+        instanceRoundCounts={snapshot.instance_round_counts}
+      />
+    </div>
+  );
+}
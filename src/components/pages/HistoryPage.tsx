@@ -1,7 +1,9 @@
 import { History, Copy, ClipboardCheck } from "lucide-react";
 import { motion } from "framer-motion";
-import { SectionHeader, Card, EmptyState } from "../common";
-import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { SectionHeader, Card, EmptyState, Button } from "../common";
+import { useCallback, useEffect, useState } from "react";
 
 interface CodeEntry {
   code: string;
@@ -11,12 +13,39 @@ interface CodeEntry {
   round_type_english?: string | null;
 }
 
-interface HistoryPageProps {
-  history: CodeEntry[];
+interface HistoryPageData {
+  entries: CodeEntry[];
+  total: number;
+  offset: number;
+  limit: number;
 }
 
-export function HistoryPage({ history }: HistoryPageProps) {
+const PAGE_SIZE = 20;
+
+export function HistoryPage() {
   const [copiedIndex, setCopiedIndex] = useState<number | null>(null);
+  const [offset, setOffset] = useState(0);
+  const [page, setPage] = useState<HistoryPageData | null>(null);
+
+  const loadPage = useCallback(async () => {
+    const data = (await invoke("get_history_page", {
+      offset,
+      limit: PAGE_SIZE,
+    })) as HistoryPageData;
+    setPage(data);
+  }, [offset]);
+
+  // 新しいセーブコードが記録されたら読み直す
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    loadPage();
+    listen("code_found", () => {
+      loadPage();
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, [loadPage]);
 
   const handleCopy = async (code: string, index: number) => {
     await navigator.clipboard.writeText(code);
@@ -24,15 +53,19 @@ export function HistoryPage({ history }: HistoryPageProps) {
     setTimeout(() => setCopiedIndex(null), 2000);
   };
 
-  const reversedHistory = [...history].reverse();
+  const entries = page?.entries ?? [];
+  const total = page?.total ?? 0;
 
   return (
     <div className="space-y-6">
-      <SectionHeader title="コード履歴" description="最大10件まで表示します" />
+      <SectionHeader
+        title="コード履歴"
+        description="記録・取り込んだすべてのセーブコードを新しい順に表示します"
+      />
 
-      {reversedHistory.length > 0 ? (
+      {entries.length > 0 ? (
         <Card hover={false} className="divide-y divide-white/5">
-          {reversedHistory.map((entry, index) => (
+          {entries.map((entry, index) => (
             <motion.div
               key={`${entry.timestamp}-${index}`}
               initial={{ opacity: 0 }}
@@ -42,7 +75,7 @@ export function HistoryPage({ history }: HistoryPageProps) {
             >
               <div className="flex items-center gap-4">
                 <div className="w-8 h-8 rounded-full bg-[#0078d4]/20 flex items-center justify-center text-[#0078d4] text-sm font-medium">
-                  {index + 1}
+                  {offset + index + 1}
                 </div>
                 <div className="flex flex-col gap-1">
                   <div className="flex items-center gap-2">
@@ -98,6 +131,28 @@ export function HistoryPage({ history }: HistoryPageProps) {
           description="セーブコードが検出されると表示されます。"
         />
       )}
+
+      {total > PAGE_SIZE && (
+        <div className="flex items-center justify-between">
+          <Button
+            size="sm"
+            disabled={offset === 0}
+            onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))}
+          >
+            新しい履歴
+          </Button>
+          <div className="text-sm text-gray-400">
+            {offset + 1}〜{Math.min(offset + PAGE_SIZE, total)} / {total}件
+          </div>
+          <Button
+            size="sm"
+            disabled={offset + PAGE_SIZE >= total}
+            onClick={() => setOffset(offset + PAGE_SIZE)}
+          >
+            古い履歴
+          </Button>
+        </div>
+      )}
     </div>
   );
 }
@@ -1 +1,2 @@
 export * from "./useUpdater";
+export * from "./useAppearance";
@@ -0,0 +1,44 @@
+import { useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface UiAppearance {
+  theme: "dark" | "light" | "system";
+  accent_color: string;
+  font_size: number;
+}
+
+function applyAppearance(appearance: UiAppearance) {
+  const root = document.documentElement;
+  const prefersLight = window.matchMedia("(prefers-color-scheme: light)").matches;
+  const light =
+    appearance.theme === "light" || (appearance.theme === "system" && prefersLight);
+  root.dataset.theme = light ? "light" : "dark";
+  root.style.setProperty("--accent", appearance.accent_color);
+  root.style.fontSize = `${appearance.font_size}px`;
+}
+
+// バックエンドに保存された見た目の設定を画面に反映する
+export function useAppearance() {
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    const init = async () => {
+      const snapshot = (await invoke("get_state")) as {
+        settings: { ui_appearance?: UiAppearance };
+      };
+      if (snapshot.settings.ui_appearance) {
+        applyAppearance(snapshot.settings.ui_appearance);
+      }
+      unlisten = await listen<UiAppearance>("ui_appearance_changed", (event) => {
+        applyAppearance(event.payload);
+      });
+    };
+
+    init();
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+}
@@ -1,6 +1,7 @@
 import { useState, useCallback, useEffect } from "react";
 import { check, Update } from "@tauri-apps/plugin-updater";
 import { getVersion } from "@tauri-apps/api/app";
+import { listen } from "@tauri-apps/api/event";
 
 export interface UpdateInfo {
   version: string;
@@ -138,14 +139,16 @@ export function useUpdater() {
     setError(null);
   }, []);
 
-  // 起動時に自動チェック
+  // 自動チェックはバックエンドが設定に従って行い、見つかったら通知してくる
   useEffect(() => {
-    // 少し遅延させて起動後にチェック
-    const timer = setTimeout(() => {
+    let unlisten: (() => void) | undefined;
+    listen("update_available", () => {
       checkForUpdates(true);
-    }, 3000);
+    }).then((fn) => {
+      unlisten = fn;
+    });
 
-    return () => clearTimeout(timer);
+    return () => unlisten?.();
   }, [checkForUpdates]);
 
   return {
@@ -1,9 +1,24 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { DesktopOverlay } from "./components/overlay/DesktopOverlay";
+import { StatsWindow } from "./components/overlay/StatsWindow";
+
+// デスクトップオーバーレイ・統計ウィンドウは同じバンドルをハッシュで切り替える
+const route = window.location.hash;
+
+function Root() {
+  if (route === "#/desktop-overlay") {
+    return <DesktopOverlay />;
+  }
+  if (route === "#/stats") {
+    return <StatsWindow />;
+  }
+  return <App />;
+}
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    <Root />
   </React.StrictMode>,
 );